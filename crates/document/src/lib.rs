@@ -0,0 +1,421 @@
+//! Semantic model of a PDF file.
+//!
+//! [`Document`] sits on top of the syntax tree of a whole file and answers
+//! questions which need more than a single node: which indirect object does
+//! `12 0 R` refer to, and what is its value?
+//!
+//! When a file has been incrementally updated, the same object may be defined
+//! several times. The definition of the newest revision wins, in the order
+//! given by the `/Prev` chain, and objects freed by a later revision are gone.
+//!
+//! Strings and streams of encrypted files are decrypted by
+//! [`Document::string`], [`Document::stream_data`] and
+//! [`Document::decode_stream`] only. The syntax tree, and the accessors and
+//! functions working on it like `ast::LiteralString::value` or
+//! [`filters::decode_stream`], keep the encrypted bytes.
+//!
+//! The pages of the document are listed by [`Document::pages`], with the
+//! attributes they inherit from the page tree.
+//!
+//! See ISO `32000-2:2020`, Sections 7.3.10 Indirect Objects and 7.5.6
+//! Incremental Updates.
+
+mod object;
+mod page;
+mod revision;
+mod security;
+
+use std::collections::BTreeMap;
+
+use filters::DecodeError;
+use syntax::{AstNode, Edition, Parse, PdfDocument, ast};
+
+pub use crate::{
+    object::{ObjectId, PdfObject},
+    page::{Page, Rectangle},
+    revision::{ObjectChange, Revision},
+    security::{CryptMethod, SecurityError, SecurityHandler},
+};
+
+/// How many references are followed when resolving a value before assuming
+/// the references form a cycle.
+const MAX_REFERENCE_CHAIN: usize = 32;
+
+/// The indirect objects of a parsed PDF file, indexed by their ids.
+#[derive(Debug, Clone)]
+pub struct Document {
+    parse: Parse<PdfDocument>,
+    objects: BTreeMap<ObjectId, ast::IndirectObjectExpr>,
+    /// The encryption dictionary of an encrypted file, and the security
+    /// handler authenticated with the last password tried.
+    encryption: Option<(ast::DictionaryExpr, Result<SecurityHandler, SecurityError>)>,
+}
+
+impl Document {
+    pub fn new(parse: Parse<PdfDocument>) -> Document {
+        let _p = tracing::info_span!("Document::new").entered();
+        let mut objects = BTreeMap::new();
+        let bodies = parse.tree().revisions().filter_map(|it| it.body());
+        for object in bodies.flat_map(|it| it.indirect_object_exprs()) {
+            if let Some(id) = ObjectId::of_object(&object) {
+                objects.insert(id, object);
+            }
+        }
+        let mut document = Document {
+            parse,
+            objects,
+            encryption: None,
+        };
+
+        // Reading the cross-reference sections may already need objects, e.g.
+        // the `/Length` of a cross-reference stream, so the index in file
+        // order is only replaced once the order of the revisions is known.
+        let mut objects = BTreeMap::new();
+        for revision in document.revisions() {
+            for object in revision.syntax().body().into_iter().flat_map(|it| it.indirect_object_exprs()) {
+                if let Some(id) = ObjectId::of_object(&object) {
+                    objects.insert(id, object);
+                }
+            }
+            for id in revision.freed() {
+                objects.remove(&id);
+            }
+        }
+        document.objects = objects;
+
+        // Most encrypted files only restrict permissions, and can be opened
+        // with the empty user password.
+        if let Some(PdfObject::Dict(encrypt)) = document.trailer().and_then(|it| document.value(it.get(b"/Encrypt")?)) {
+            let handler = SecurityHandler::new(&document, &encrypt, b"");
+            document.encryption = Some((encrypt, handler));
+        }
+        document
+    }
+
+    pub fn parse(text: &[u8], edition: Edition) -> Document {
+        Document::new(PdfDocument::parse(text, edition))
+    }
+
+    /// Parses `text` with the edition declared by its header, or the latest
+    /// one if the header is missing or malformed.
+    pub fn detect_and_parse(text: &[u8]) -> Document {
+        Document::parse(text, PdfDocument::detect_edition(text).unwrap_or(Edition::LATEST))
+    }
+
+    /// The parse result the document was built from.
+    pub fn syntax(&self) -> &Parse<PdfDocument> {
+        &self.parse
+    }
+
+    /// The trailer dictionary of the newest revision, or the dictionary of its
+    /// cross-reference stream.
+    pub fn trailer(&self) -> Option<ast::DictionaryExpr> {
+        self.parse
+            .tree()
+            .revisions()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .find_map(|revision| match revision.trailer().and_then(|it| it.dictionary_expr()) {
+                Some(dictionary) => Some(dictionary),
+                None => revision::xref_stream(&revision)?.1.dictionary_expr(),
+            })
+    }
+
+    /// The document catalog, the root of the object hierarchy (`/Root`).
+    pub fn catalog(&self) -> Option<ast::DictionaryExpr> {
+        match self.value(self.trailer()?.get(b"/Root")?)? {
+            PdfObject::Dict(it) => Some(it),
+            _ => None,
+        }
+    }
+
+    /// The version of PDF the file conforms to.
+    ///
+    /// This is the version of the header, unless the catalog declares a later
+    /// one with `/Version`, as incremental updates may do.
+    /// See ISO `32000-2:2020`, Section 7.5.2 File header.
+    pub fn edition(&self) -> Option<Edition> {
+        let header = self.parse.tree().edition();
+        let catalog = self.catalog().and_then(|it| self.value(it.get(b"/Version")?)).and_then(|it| match it {
+            PdfObject::Name(name) => String::from_utf8(name.value().ok()?).ok()?.parse().ok(),
+            _ => None,
+        });
+        header.max(catalog)
+    }
+
+    /// Whether the file is encrypted. Its strings and streams can only be
+    /// read once the right password is given, see [`Document::authenticate`].
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    /// The handler decrypting the file, or `None` if the file isn't encrypted
+    /// or no correct password has been given.
+    pub fn security_handler(&self) -> Option<&SecurityHandler> {
+        self.encryption.as_ref()?.1.as_ref().ok()
+    }
+
+    /// Authenticates with the user or the owner `password` of an encrypted
+    /// file. The empty user password is tried when the document is created.
+    pub fn authenticate(&mut self, password: &[u8]) -> Result<(), SecurityError> {
+        let Some((encrypt, _)) = &self.encryption else {
+            return Ok(());
+        };
+        let handler = SecurityHandler::new(self, encrypt, password);
+        let result = handler.as_ref().map(|_| ()).map_err(Clone::clone);
+        self.encryption.as_mut().unwrap().1 = handler;
+        result
+    }
+
+    /// The bytes of a string, decrypted if the file is encrypted.
+    ///
+    /// Returns `None` if the string is malformed, or the file is encrypted and
+    /// no correct password has been given.
+    pub fn string(&self, literal: &ast::Literal) -> Option<Vec<u8>> {
+        let bytes = object::string_bytes(literal)?;
+        let Some((encrypt, handler)) = &self.encryption else {
+            return Some(bytes);
+        };
+        // The encryption dictionary itself and the trailer aren't encrypted.
+        if literal.syntax().ancestors().any(|it| &it == encrypt.syntax()) {
+            return Some(bytes);
+        }
+        let Some((id, object)) = self.containing_object(literal.syntax()) else {
+            return Some(bytes);
+        };
+        if object
+            .stream_expr()
+            .and_then(|it| it.dictionary_expr()?.get(b"/Type"))
+            .is_some_and(|it| it.is_name(b"/XRef"))
+        {
+            return Some(bytes);
+        }
+        handler
+            .as_ref()
+            .ok()?
+            .decrypt_string(id, &bytes)
+            .map_err(|err| tracing::warn!("failed to decrypt string of object {id}: {err}"))
+            .ok()
+    }
+
+    /// The encoded bytes of a stream, decrypted if the file is encrypted.
+    ///
+    /// Returns `None` if the stream has no data, or the file is encrypted and
+    /// no correct password has been given.
+    // FIXME: streams with their own `/Crypt` filter are decrypted like all
+    // other streams.
+    pub fn stream_data(&self, stream: &ast::StreamExpr) -> Option<Vec<u8>> {
+        let data = stream.raw_data()?;
+        let Some((_, handler)) = &self.encryption else {
+            return Some(data);
+        };
+        let handler = handler.as_ref().ok()?;
+        let kind = stream.dictionary_expr().and_then(|it| it.get(b"/Type"));
+        // Cross-reference streams are never encrypted.
+        if kind.as_ref().is_some_and(|it| it.is_name(b"/XRef")) || (!handler.encrypts_metadata() && kind.is_some_and(|it| it.is_name(b"/Metadata"))) {
+            return Some(data);
+        }
+        let Some((id, _)) = self.containing_object(stream.syntax()) else {
+            return Some(data);
+        };
+        handler
+            .decrypt_stream(id, &data)
+            .map_err(|err| tracing::warn!("failed to decrypt stream of object {id}: {err}"))
+            .ok()
+    }
+
+    /// The decoded data of a stream, decrypted and with all of its filters
+    /// applied.
+    ///
+    /// Fails with [`DecodeError::MissingData`] if the stream has no data or
+    /// can't be decrypted.
+    pub fn decode_stream(&self, stream: &ast::StreamExpr) -> Result<Vec<u8>, DecodeError> {
+        let data = self.stream_data(stream).ok_or(DecodeError::MissingData)?;
+        let resolve = |reference: &ast::IndirectReferenceExpr| self.resolve(reference)?.expr();
+        let filters = match stream.dictionary_expr() {
+            Some(dictionary) => filters::stream_filters(&dictionary, &resolve)?,
+            None => Vec::new(),
+        };
+        filters::decode(&data, &filters)
+    }
+
+    /// The current definition of the object `id`.
+    pub fn object(&self, id: ObjectId) -> Option<&ast::IndirectObjectExpr> {
+        self.objects.get(&id)
+    }
+
+    /// Iterates over the current definitions of all objects, ordered by id.
+    pub fn objects(&self) -> impl Iterator<Item = (ObjectId, &ast::IndirectObjectExpr)> {
+        self.objects.iter().map(|(&id, object)| (id, object))
+    }
+
+    /// The object `reference` refers to, or `None` if no such object is
+    /// defined.
+    pub fn resolve(&self, reference: &ast::IndirectReferenceExpr) -> Option<&ast::IndirectObjectExpr> {
+        self.object(ObjectId::of_reference(reference)?)
+    }
+
+    /// The value of the object `id`.
+    ///
+    /// A reference to an undefined object is a reference to the null object,
+    /// so this is `Some(PdfObject::Null)` for unknown ids.
+    pub fn get(&self, id: ObjectId) -> Option<PdfObject> {
+        self.get_with_depth(id, 0)
+    }
+
+    /// The value of `expr`, following indirect references.
+    ///
+    /// Returns `None` if `expr` (or the value it refers to) is malformed.
+    pub fn value(&self, expr: ast::Expr) -> Option<PdfObject> {
+        self.value_with_depth(expr, 0)
+    }
+
+    /// The indirect object `node` belongs to, if it's the current definition of
+    /// that object.
+    fn containing_object(&self, node: &syntax::SyntaxNode) -> Option<(ObjectId, &ast::IndirectObjectExpr)> {
+        let object = node.ancestors().find_map(ast::IndirectObjectExpr::cast)?;
+        let id = ObjectId::of_object(&object)?;
+        Some((id, self.object(id)?)).filter(|(_, it)| *it == &object)
+    }
+
+    fn get_with_depth(&self, id: ObjectId, depth: usize) -> Option<PdfObject> {
+        let Some(object) = self.object(id) else {
+            return Some(PdfObject::Null);
+        };
+        if let Some(stream) = object.stream_expr() {
+            return Some(PdfObject::Stream(stream));
+        }
+        match object.expr() {
+            Some(expr) => self.value_with_depth(expr, depth),
+            None if object.syntax().children().nth(1).is_none() => Some(PdfObject::Null),
+            None => None,
+        }
+    }
+
+    fn value_with_depth(&self, expr: ast::Expr, depth: usize) -> Option<PdfObject> {
+        match expr {
+            ast::Expr::IndirectReferenceExpr(reference) => {
+                if depth >= MAX_REFERENCE_CHAIN {
+                    tracing::warn!("indirect reference chain is too long or cyclic");
+                    return None;
+                }
+                self.get_with_depth(ObjectId::of_reference(&reference)?, depth + 1)
+            }
+            expr => PdfObject::from_expr(expr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syntax::AstToken;
+
+    fn document(text: &str) -> Document {
+        Document::parse(text.as_bytes(), Edition::CURRENT)
+    }
+
+    fn reference(document: &Document, text: &str) -> ast::IndirectReferenceExpr {
+        document
+            .syntax()
+            .syntax_node()
+            .descendants()
+            .filter_map(ast::IndirectReferenceExpr::cast)
+            .find(|it| it.syntax().text().to_string() == text)
+            .unwrap()
+    }
+
+    #[test]
+    fn indexes_objects_by_id() {
+        let document = document("1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n2 3 obj\n[1 2]\nendobj\n");
+
+        let ids: Vec<_> = document.objects().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![ObjectId::new(1, 0), ObjectId::new(2, 3)]);
+
+        // The generation number is part of the id.
+        assert!(document.resolve(&reference(&document, "2 0 R")).is_none());
+        assert!(document.object(ObjectId::new(2, 3)).is_some());
+    }
+
+    #[test]
+    fn resolves_references() {
+        let document = document("1 0 obj\n<< /Pages 2 0 R >>\nendobj\n2 0 obj\n<< /Type /Pages /Count 0 >>\nendobj\n");
+
+        let target = document.resolve(&reference(&document, "2 0 R")).unwrap();
+        assert_eq!(ObjectId::of_object(target), Some(ObjectId::new(2, 0)));
+
+        let Some(PdfObject::Dict(catalog)) = document.get(ObjectId::new(1, 0)) else {
+            panic!()
+        };
+        let Some(PdfObject::Dict(pages)) = document.value(catalog.get(b"/Pages").unwrap()) else {
+            panic!()
+        };
+        assert_eq!(document.value(pages.get(b"/Count").unwrap()), Some(PdfObject::Int(0)));
+    }
+
+    #[test]
+    fn typed_values() {
+        let document = document(
+            "1 0 obj null endobj\n2 0 obj true endobj\n3 0 obj -12 endobj\n4 0 obj 1.5 endobj\n5 0 obj /Name endobj\n\
+             6 0 obj (text) endobj\n7 0 obj <ABCD> endobj\n8 0 obj [1 0 R] endobj\n9 0 obj << >> endobj\n\
+             10 0 obj << /Length 2 >>\nstream\nhi\nendstream\nendobj\n11 0 obj endobj\n12 0 obj 3 0 R endobj\n",
+        );
+        let get = |number| document.get(ObjectId::new(number, 0)).unwrap();
+
+        assert_eq!(get(1), PdfObject::Null);
+        assert_eq!(get(2), PdfObject::Bool(true));
+        assert_eq!(get(3), PdfObject::Int(-12));
+        assert_eq!(get(4), PdfObject::Real(1.5));
+        assert!(matches!(get(5), PdfObject::Name(it) if it.text() == b"/Name"));
+        assert!(matches!(get(6), PdfObject::String(it) if it.syntax().text().to_string() == "(text)"));
+        assert!(matches!(get(7), PdfObject::String(it) if it.syntax().text().to_string() == "<ABCD>"));
+        assert!(matches!(get(8), PdfObject::Array(_)));
+        assert!(matches!(get(9), PdfObject::Dict(_)));
+        assert!(matches!(get(10), PdfObject::Stream(it) if it.raw_data().unwrap() == b"hi"));
+        assert_eq!(get(11), PdfObject::Null);
+        assert_eq!(get(12), PdfObject::Int(-12));
+        // References to undefined objects are references to the null object.
+        assert_eq!(get(99), PdfObject::Null);
+    }
+
+    #[test]
+    fn later_definitions_override_earlier_ones() {
+        let document = document(
+            "1 0 obj (original) endobj\n2 0 obj 1 endobj\nxref\n0 1\n0000000000 65535 f\ntrailer\n<< /Size 3 >>\nstartxref\n0\n\
+             1 0 obj (updated) endobj\n",
+        );
+
+        assert_eq!(document.objects().count(), 2);
+        let Some(PdfObject::String(string)) = document.get(ObjectId::new(1, 0)) else {
+            panic!()
+        };
+        assert_eq!(string.syntax().text().to_string(), "(updated)");
+        assert_eq!(document.get(ObjectId::new(2, 0)), Some(PdfObject::Int(1)));
+    }
+
+    #[test]
+    fn edition_from_header_and_catalog() {
+        let text = b"%PDF-1.4\n1 0 obj << /Type /Catalog >> endobj\ntrailer << /Root 1 0 R >>\nstartxref 0\n%%EOF\n";
+        assert_eq!(PdfDocument::detect_edition(text), Some(Edition::Pdf14));
+        assert_eq!(Document::detect_and_parse(text).edition(), Some(Edition::Pdf14));
+
+        let text = b"%PDF-1.4\n1 0 obj << /Type /Catalog /Version /1.7 >> endobj\ntrailer << /Root 1 0 R >>\nstartxref 0\n%%EOF\n";
+        assert_eq!(Document::detect_and_parse(text).edition(), Some(Edition::Pdf17));
+
+        // A `/Version` older than the header doesn't lower it.
+        let text = b"%PDF-2.0\n1 0 obj << /Version /1.3 >> endobj\ntrailer << /Root 1 0 R >>\nstartxref 0\n%%EOF\n";
+        assert_eq!(Document::detect_and_parse(text).edition(), Some(Edition::Pdf20));
+
+        // Garbage before the header is skipped.
+        assert_eq!(PdfDocument::detect_edition(b"\x00\x00junk\r\n%PDF-1.6\r\n"), Some(Edition::Pdf16));
+        assert_eq!(PdfDocument::detect_edition(b"%PDF-7.0\n"), None);
+        assert_eq!(Document::detect_and_parse(b"1 0 obj null endobj").edition(), None);
+    }
+
+    #[test]
+    fn reference_cycles_are_not_followed_forever() {
+        let document = document("1 0 obj 2 0 R endobj\n2 0 obj 1 0 R endobj\n");
+        assert_eq!(document.get(ObjectId::new(1, 0)), None);
+    }
+}
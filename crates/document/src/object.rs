@@ -0,0 +1,97 @@
+//! Typed views of the values of PDF objects.
+//!
+//! See ISO `32000-2:2020`, Section 7.3 Objects.
+
+use std::fmt;
+
+use syntax::ast;
+
+/// Identifies an indirect object by its object number and generation number.
+///
+/// See ISO `32000-2:2020`, Section 7.3.10 Indirect Objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObjectId {
+    pub number: u32,
+    pub generation: u16,
+}
+
+impl ObjectId {
+    pub fn new(number: u32, generation: u16) -> ObjectId {
+        ObjectId { number, generation }
+    }
+
+    /// The id declared by `N G obj`, or `None` if either number is missing or
+    /// out of range.
+    pub fn of_object(object: &ast::IndirectObjectExpr) -> Option<ObjectId> {
+        let id = object.indirect_object_id()?;
+        ObjectId::from_literals(id.object_number()?, id.generation_number()?)
+    }
+
+    /// The id of the object referred to by `N G R`, or `None` if either number
+    /// is missing or out of range.
+    pub fn of_reference(reference: &ast::IndirectReferenceExpr) -> Option<ObjectId> {
+        ObjectId::from_literals(reference.object_number()?, reference.generation_number()?)
+    }
+
+    fn from_literals(number: ast::Literal, generation: ast::Literal) -> Option<ObjectId> {
+        let number = ast::Expr::Literal(number).as_int().and_then(|it| u32::try_from(it).ok())?;
+        let generation = ast::Expr::Literal(generation).as_int().and_then(|it| u16::try_from(it).ok())?;
+        Some(ObjectId { number, generation })
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.number, self.generation)
+    }
+}
+
+/// The value of an object, with indirect references already resolved.
+///
+/// Numbers and booleans are decoded, everything else is a view into the
+/// syntax tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdfObject {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Real(f64),
+    Name(ast::Name),
+    /// A literal or hexadecimal string. Its bytes may be encrypted, see
+    /// [`Document::string`](crate::Document::string).
+    String(ast::Literal),
+    Array(ast::ArrayExpr),
+    Dict(ast::DictionaryExpr),
+    Stream(ast::StreamExpr),
+}
+
+impl PdfObject {
+    /// The value of the direct object `expr`, or `None` if `expr` is an
+    /// indirect reference or is malformed.
+    pub fn from_expr(expr: ast::Expr) -> Option<PdfObject> {
+        let object = match expr {
+            ast::Expr::ArrayExpr(it) => PdfObject::Array(it),
+            ast::Expr::DictionaryExpr(it) => PdfObject::Dict(it),
+            ast::Expr::IndirectReferenceExpr(_) => return None,
+            ast::Expr::Literal(it) => match it.kind() {
+                ast::LiteralKind::IntNumber(number) => PdfObject::Int(number.value().ok()?),
+                ast::LiteralKind::RealNumber(number) => PdfObject::Real(number.value().ok()?),
+                ast::LiteralKind::Name(name) => PdfObject::Name(name),
+                ast::LiteralKind::LiteralString(_) | ast::LiteralKind::HexString(_) => PdfObject::String(it),
+                ast::LiteralKind::Bool(value) => PdfObject::Bool(value),
+                ast::LiteralKind::Null => PdfObject::Null,
+            },
+        };
+        Some(object)
+    }
+}
+
+/// The bytes of a literal or hexadecimal string as written in the file, i.e.
+/// before decryption.
+pub(crate) fn string_bytes(literal: &ast::Literal) -> Option<Vec<u8>> {
+    match literal.kind() {
+        ast::LiteralKind::LiteralString(it) => it.value().ok(),
+        ast::LiteralKind::HexString(it) => it.value().ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,321 @@
+//! The page tree.
+//!
+//! The pages of a document are the leaves of a tree of page tree nodes,
+//! rooted at the `/Pages` entry of the catalog. Some attributes of a page may
+//! be left out and inherited from its ancestors instead.
+//!
+//! See ISO `32000-2:2020`, Sections 7.7.3 Page Tree and 7.7.3.4 Inheritance of
+//! Page Attributes.
+
+use std::collections::HashSet;
+
+use syntax::ast;
+
+use crate::{Document, ObjectId, PdfObject};
+
+/// A rectangle in default user space, normalized so that `(llx, lly)` is its
+/// lower-left and `(urx, ury)` its upper-right corner.
+///
+/// See ISO `32000-2:2020`, Section 7.9.5 Rectangles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rectangle {
+    pub llx: f64,
+    pub lly: f64,
+    pub urx: f64,
+    pub ury: f64,
+}
+
+impl Rectangle {
+    pub fn width(&self) -> f64 {
+        self.urx - self.llx
+    }
+
+    pub fn height(&self) -> f64 {
+        self.ury - self.lly
+    }
+
+    /// The rectangle described by an array of four numbers, which may be the
+    /// coordinates of any pair of opposite corners.
+    fn from_value(document: &Document, expr: ast::Expr) -> Option<Rectangle> {
+        let PdfObject::Array(array) = document.value(expr)? else {
+            return None;
+        };
+        let numbers = array
+            .exprs()
+            .map(|it| match document.value(it)? {
+                PdfObject::Int(it) => Some(it as f64),
+                PdfObject::Real(it) => Some(it),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let &[x1, y1, x2, y2] = numbers.as_slice() else {
+            return None;
+        };
+        Some(Rectangle {
+            llx: x1.min(x2),
+            lly: y1.min(y2),
+            urx: x1.max(x2),
+            ury: y1.max(y2),
+        })
+    }
+}
+
+/// A page of the document, with the attributes it inherits from the page tree
+/// already resolved.
+#[derive(Debug, Clone)]
+pub struct Page {
+    id: ObjectId,
+    dictionary: ast::DictionaryExpr,
+    attributes: Attributes,
+}
+
+impl Page {
+    /// The id of the page object.
+    pub fn id(&self) -> ObjectId {
+        self.id
+    }
+
+    /// The page dictionary itself, without any inherited entries.
+    pub fn dictionary(&self) -> &ast::DictionaryExpr {
+        &self.dictionary
+    }
+
+    /// The resources the page's content streams refer to by name
+    /// (`/Resources`).
+    pub fn resources(&self) -> Option<&ast::DictionaryExpr> {
+        self.attributes.resources.as_ref()
+    }
+
+    /// The boundaries of the physical medium the page is displayed or printed
+    /// on (`/MediaBox`).
+    pub fn media_box(&self) -> Option<Rectangle> {
+        self.attributes.media_box
+    }
+
+    /// The region the page is clipped to when displayed or printed
+    /// (`/CropBox`). Defaults to the media box.
+    pub fn crop_box(&self) -> Option<Rectangle> {
+        self.attributes.crop_box.or(self.attributes.media_box)
+    }
+
+    /// The clockwise rotation of the page when displayed or printed
+    /// (`/Rotate`), in degrees between 0 and 359. Should be a multiple of 90.
+    pub fn rotate(&self) -> i64 {
+        self.attributes.rotate.unwrap_or(0).rem_euclid(360)
+    }
+}
+
+/// The inheritable attributes of a page tree node. Entries missing from a node
+/// are taken from its parent.
+#[derive(Debug, Clone, Default)]
+struct Attributes {
+    resources: Option<ast::DictionaryExpr>,
+    media_box: Option<Rectangle>,
+    crop_box: Option<Rectangle>,
+    rotate: Option<i64>,
+}
+
+impl Attributes {
+    fn inherit(&self, document: &Document, node: &ast::DictionaryExpr) -> Attributes {
+        let resources = node.get(b"/Resources").and_then(|it| match document.value(it)? {
+            PdfObject::Dict(it) => Some(it),
+            _ => None,
+        });
+        let rotate = node.get(b"/Rotate").and_then(|it| match document.value(it)? {
+            PdfObject::Int(it) => Some(it),
+            _ => None,
+        });
+        Attributes {
+            resources: resources.or_else(|| self.resources.clone()),
+            media_box: node.get(b"/MediaBox").and_then(|it| Rectangle::from_value(document, it)).or(self.media_box),
+            crop_box: node.get(b"/CropBox").and_then(|it| Rectangle::from_value(document, it)).or(self.crop_box),
+            rotate: rotate.or(self.rotate),
+        }
+    }
+}
+
+impl Document {
+    /// Iterates over the pages of the document, in order.
+    ///
+    /// Each node of the page tree is visited at most once, so nodes which are
+    /// their own ancestors, or appear in the tree several times, are only
+    /// walked into the first time. Kids which aren't references to
+    /// dictionaries are skipped.
+    pub fn pages(&self) -> impl Iterator<Item = Page> + '_ {
+        let root = self.catalog().and_then(|it| it.get(b"/Pages"));
+        // The kids left to visit at each level, with the attributes of their
+        // parent.
+        let mut stack = vec![(Vec::from_iter(root).into_iter(), Attributes::default())];
+        let mut visited = HashSet::new();
+
+        std::iter::from_fn(move || {
+            loop {
+                let (kids, parent) = stack.last_mut()?;
+                let Some(kid) = kids.next() else {
+                    stack.pop();
+                    continue;
+                };
+                let ast::Expr::IndirectReferenceExpr(reference) = kid else {
+                    tracing::warn!("page tree node is not an indirect reference");
+                    continue;
+                };
+                let Some(id) = ObjectId::of_reference(&reference) else { continue };
+                if !visited.insert(id) {
+                    tracing::warn!("page tree node {id} is visited more than once");
+                    continue;
+                }
+                let Some(PdfObject::Dict(node)) = self.get(id) else {
+                    tracing::warn!("page tree node {id} is not a dictionary");
+                    continue;
+                };
+
+                let attributes = parent.inherit(self, &node);
+                // Intermediate nodes without a `/Type` are recognized by their
+                // `/Kids`.
+                let is_page = match node.get(b"/Type") {
+                    Some(kind) if kind.is_name(b"/Page") => true,
+                    Some(kind) if kind.is_name(b"/Pages") => false,
+                    _ => node.get(b"/Kids").is_none(),
+                };
+                if is_page {
+                    return Some(Page {
+                        id,
+                        dictionary: node,
+                        attributes,
+                    });
+                }
+                let kids = match node.get(b"/Kids").and_then(|it| self.value(it)) {
+                    Some(PdfObject::Array(kids)) => kids.exprs().collect(),
+                    _ => Vec::new(),
+                };
+                stack.push((kids.into_iter(), attributes));
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syntax::{AstNode, Edition};
+
+    use super::*;
+
+    fn document(objects: &str) -> Document {
+        let text = format!("%PDF-1.7\n1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n{objects}trailer << /Root 1 0 R >>\n");
+        Document::parse(text.as_bytes(), Edition::CURRENT)
+    }
+
+    fn page_numbers(document: &Document) -> Vec<u32> {
+        document.pages().map(|it| it.id().number).collect()
+    }
+
+    #[test]
+    fn walks_the_page_tree_in_order() {
+        let document = document(
+            "2 0 obj << /Type /Pages /Kids [3 0 R 4 0 R 7 0 R] /Count 4 >> endobj\n\
+             3 0 obj << /Type /Page /Parent 2 0 R >> endobj\n\
+             4 0 obj << /Type /Pages /Parent 2 0 R /Kids 5 0 R /Count 2 >> endobj\n\
+             5 0 obj [6 0 R 8 0 R] endobj\n\
+             6 0 obj << /Type /Page /Parent 4 0 R >> endobj\n\
+             7 0 obj << /Type /Page /Parent 2 0 R >> endobj\n\
+             8 0 obj << /Parent 4 0 R >> endobj\n",
+        );
+        assert_eq!(page_numbers(&document), vec![3, 6, 8, 7]);
+        let page = document.pages().next().unwrap();
+        assert_eq!(page.dictionary().syntax().text().to_string(), "<< /Type /Page /Parent 2 0 R >>");
+    }
+
+    #[test]
+    fn inherits_attributes() {
+        let document = document(
+            "2 0 obj << /Type /Pages /Kids [3 0 R 4 0 R] /Resources 9 0 R /MediaBox [0 0 612 792] /Rotate 90 >> endobj\n\
+             3 0 obj << /Type /Pages /Kids [5 0 R 6 0 R] /CropBox [10 10 600.5 780] >> endobj\n\
+             4 0 obj << /Type /Page /Resources << /ProcSet [/PDF] >> /MediaBox [595 842 0 0] /Rotate -90 >> endobj\n\
+             5 0 obj << /Type /Page >> endobj\n\
+             6 0 obj << /Type /Page /CropBox [0 0 100 100] /Rotate 0 >> endobj\n\
+             9 0 obj << /Font << >> >> endobj\n",
+        );
+        let pages: Vec<_> = document.pages().collect();
+        assert_eq!(pages.iter().map(|it| it.id().number).collect::<Vec<_>>(), vec![5, 6, 4]);
+        let resources = |page: &Page| page.resources().unwrap().syntax().text().to_string();
+        let letter = Rectangle {
+            llx: 0.0,
+            lly: 0.0,
+            urx: 612.0,
+            ury: 792.0,
+        };
+
+        assert_eq!(resources(&pages[0]), "<< /Font << >> >>");
+        assert_eq!(pages[0].media_box(), Some(letter));
+        assert_eq!(
+            pages[0].crop_box(),
+            Some(Rectangle {
+                llx: 10.0,
+                lly: 10.0,
+                urx: 600.5,
+                ury: 780.0
+            })
+        );
+        assert_eq!(pages[0].rotate(), 90);
+
+        assert_eq!(
+            pages[1].crop_box(),
+            Some(Rectangle {
+                llx: 0.0,
+                lly: 0.0,
+                urx: 100.0,
+                ury: 100.0
+            })
+        );
+        assert_eq!(pages[1].rotate(), 0);
+
+        assert_eq!(resources(&pages[2]), "<< /ProcSet [/PDF] >>");
+        // Corners are normalized, and the crop box defaults to the media box.
+        let a4 = Rectangle {
+            llx: 0.0,
+            lly: 0.0,
+            urx: 595.0,
+            ury: 842.0,
+        };
+        assert_eq!((pages[2].media_box(), pages[2].crop_box()), (Some(a4), Some(a4)));
+        assert_eq!((a4.width(), a4.height()), (595.0, 842.0));
+        assert_eq!(pages[2].rotate(), 270);
+    }
+
+    #[test]
+    fn missing_attributes() {
+        let document = document(
+            "2 0 obj << /Type /Pages /Kids [3 0 R] /MediaBox [0 0 612] /Rotate /Ninety >> endobj\n\
+             3 0 obj << /Type /Page /Resources 4 0 R >> endobj\n",
+        );
+        let page = document.pages().next().unwrap();
+        assert!(page.resources().is_none());
+        assert_eq!((page.media_box(), page.crop_box(), page.rotate()), (None, None, 0));
+    }
+
+    #[test]
+    fn cycles_and_shared_nodes_are_visited_once() {
+        let document = document(
+            "2 0 obj << /Type /Pages /Kids [3 0 R 4 0 R 3 0 R] >> endobj\n\
+             3 0 obj << /Type /Pages /Kids [2 0 R 5 0 R 3 0 R] >> endobj\n\
+             4 0 obj << /Type /Pages /Kids [4 0 R 5 0 R 6 0 R] >> endobj\n\
+             5 0 obj << /Type /Page >> endobj\n\
+             6 0 obj << /Type /Page >> endobj\n",
+        );
+        assert_eq!(page_numbers(&document), vec![5, 6]);
+    }
+
+    #[test]
+    fn malformed_trees() {
+        // Kids which are direct objects, undefined or not dictionaries.
+        let document = document(
+            "2 0 obj << /Type /Pages /Kids [<< /Type /Page >> 9 0 R 3 0 R 4 0 R] >> endobj\n\
+             3 0 obj [5 0 R] endobj\n\
+             4 0 obj << /Type /Page >> endobj\n",
+        );
+        assert_eq!(page_numbers(&document), vec![4]);
+
+        let document = Document::parse(b"1 0 obj << /Type /Catalog >> endobj\ntrailer << /Root 1 0 R >>\n", Edition::CURRENT);
+        assert_eq!(document.pages().count(), 0);
+    }
+}
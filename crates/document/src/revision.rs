@@ -0,0 +1,355 @@
+//! The revisions of an incrementally updated file.
+//!
+//! Every incremental update appends a body, a cross-reference section and a
+//! trailer whose `/Prev` entry points back to the previous cross-reference
+//! section. Following that chain from the last `startxref` gives the order in
+//! which the revisions were written.
+//!
+//! See ISO `32000-2:2020`, Section 7.5.6 Incremental Updates.
+
+use std::collections::{BTreeMap, HashSet};
+
+use syntax::{AstNode, XRefEntryKind, XRefStream, ast};
+
+use crate::{Document, ObjectId};
+
+/// How a revision affected an object, compared to the revisions before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectChange {
+    /// The object didn't exist before, or had been freed.
+    Added,
+    /// The object existed before and was redefined.
+    Changed,
+    /// The object existed before and was marked as free.
+    Freed,
+}
+
+/// A single revision of the file, with the objects it touched.
+#[derive(Debug, Clone)]
+pub struct Revision {
+    syntax: ast::Revision,
+    xref_offset: Option<u64>,
+    prev: Option<u64>,
+    changes: BTreeMap<ObjectId, ObjectChange>,
+}
+
+impl Revision {
+    pub fn syntax(&self) -> &ast::Revision {
+        &self.syntax
+    }
+
+    /// The byte offset of the cross-reference section of this revision, i.e.
+    /// the value of `startxref`.
+    pub fn xref_offset(&self) -> Option<u64> {
+        self.xref_offset
+    }
+
+    /// The byte offset of the cross-reference section of the previous
+    /// revision, i.e. the value of `/Prev`.
+    pub fn prev(&self) -> Option<u64> {
+        self.prev
+    }
+
+    /// Iterates over the objects touched by this revision, ordered by id.
+    ///
+    /// The id of a freed object is the one it had before it was freed.
+    pub fn changes(&self) -> impl Iterator<Item = (ObjectId, ObjectChange)> + '_ {
+        self.changes.iter().map(|(&id, &change)| (id, change))
+    }
+
+    pub fn added(&self) -> impl Iterator<Item = ObjectId> + '_ {
+        self.changes_of_kind(ObjectChange::Added)
+    }
+
+    pub fn changed(&self) -> impl Iterator<Item = ObjectId> + '_ {
+        self.changes_of_kind(ObjectChange::Changed)
+    }
+
+    pub fn freed(&self) -> impl Iterator<Item = ObjectId> + '_ {
+        self.changes_of_kind(ObjectChange::Freed)
+    }
+
+    fn changes_of_kind(&self, kind: ObjectChange) -> impl Iterator<Item = ObjectId> + '_ {
+        self.changes().filter(move |&(_, change)| change == kind).map(|(id, _)| id)
+    }
+}
+
+impl Document {
+    /// The revisions of the file, oldest first.
+    ///
+    /// The order is given by the `/Prev` chain. If the chain is broken (an
+    /// offset doesn't point to the cross-reference section of a revision, or
+    /// some revisions aren't part of it), the revisions are listed in file
+    /// order instead.
+    pub fn revisions(&self) -> Vec<Revision> {
+        let _p = tracing::info_span!("Document::revisions").entered();
+        let nodes: Vec<ast::Revision> = self.syntax().tree().revisions().collect();
+        let sections: Vec<XRefSection> = nodes.iter().map(|it| XRefSection::new(self, it)).collect();
+
+        // Object numbers in use, and their generation.
+        let mut live: BTreeMap<u32, u16> = BTreeMap::new();
+        revision_order(&nodes, &sections)
+            .into_iter()
+            .map(|i| {
+                let (syntax, section) = (nodes[i].clone(), &sections[i]);
+                let mut changes = BTreeMap::new();
+
+                let defined = syntax
+                    .body()
+                    .into_iter()
+                    .flat_map(|it| it.indirect_object_exprs())
+                    .filter_map(|it| ObjectId::of_object(&it));
+                // Objects stored in object streams are only listed by the
+                // cross-reference stream, they always have generation 0.
+                let compressed = section.entries.iter().filter_map(|&(number, kind)| match kind {
+                    XRefEntryKind::Compressed { .. } => Some(ObjectId::new(number, 0)),
+                    _ => None,
+                });
+                for id in defined.chain(compressed).collect::<Vec<_>>() {
+                    let change = match live.insert(id.number, id.generation) {
+                        Some(generation) if generation == id.generation => ObjectChange::Changed,
+                        _ => ObjectChange::Added,
+                    };
+                    changes.entry(id).or_insert(change);
+                }
+
+                for &(number, kind) in &section.entries {
+                    // Object 0 is the head of the list of free objects.
+                    if number == 0 || !matches!(kind, XRefEntryKind::Free { .. }) {
+                        continue;
+                    }
+                    let redefined = changes.keys().any(|it: &ObjectId| it.number == number);
+                    if let Some(generation) = live.get(&number).copied().filter(|_| !redefined) {
+                        live.remove(&number);
+                        changes.insert(ObjectId::new(number, generation), ObjectChange::Freed);
+                    }
+                }
+
+                Revision {
+                    syntax,
+                    xref_offset: section.startxref,
+                    prev: section.prev,
+                    changes,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The cross-reference information of a revision, from either its table and
+/// trailer or its cross-reference stream.
+#[derive(Debug, Default)]
+struct XRefSection {
+    /// Where the section starts in the file.
+    offset: Option<u64>,
+    startxref: Option<u64>,
+    prev: Option<u64>,
+    entries: Vec<(u32, XRefEntryKind)>,
+}
+
+impl XRefSection {
+    fn new(document: &Document, revision: &ast::Revision) -> XRefSection {
+        let trailer = revision.trailer();
+        let startxref = trailer.as_ref().and_then(|it| it.literal()).and_then(|it| non_negative(ast::Expr::Literal(it)));
+
+        if let Some(table) = revision.x_ref_table() {
+            let prev = trailer
+                .and_then(|it| it.dictionary_expr())
+                .and_then(|it| it.get(b"/Prev"))
+                .and_then(non_negative);
+            return XRefSection {
+                offset: Some(u32::from(table.syntax().text_range().start()).into()),
+                startxref,
+                prev,
+                entries: table.entries().collect(),
+            };
+        }
+
+        let Some((object, stream)) = xref_stream(revision) else {
+            return XRefSection {
+                startxref,
+                ..XRefSection::default()
+            };
+        };
+
+        let resolve = |reference: &ast::IndirectReferenceExpr| document.resolve(reference)?.expr();
+        let xref_stream = filters::decode_stream(&stream, &resolve)
+            .map_err(|err| tracing::warn!("failed to decode cross-reference stream: {err}"))
+            .ok()
+            .and_then(|data| XRefStream::parse(&stream, &data).map_err(|err| tracing::warn!("{err}")).ok());
+        XRefSection {
+            offset: Some(u32::from(object.syntax().text_range().start()).into()),
+            startxref,
+            prev: xref_stream.as_ref().and_then(|it| it.prev()),
+            entries: xref_stream.map(|it| it.entries().collect()).unwrap_or_default(),
+        }
+    }
+}
+
+/// The cross-reference stream of `revision`, which replaces the table and the
+/// trailer dictionary.
+pub(crate) fn xref_stream(revision: &ast::Revision) -> Option<(ast::IndirectObjectExpr, ast::StreamExpr)> {
+    let objects = revision.body().into_iter().flat_map(|it| it.indirect_object_exprs());
+    objects
+        .filter_map(|object| {
+            let stream = object.stream_expr()?;
+            let is_xref = stream.dictionary_expr()?.get(b"/Type")?.is_name(b"/XRef");
+            is_xref.then_some((object, stream))
+        })
+        .last()
+}
+
+/// The indices of the revisions, oldest first.
+fn revision_order(revisions: &[ast::Revision], sections: &[XRefSection]) -> Vec<usize> {
+    let file_order = (0..revisions.len()).collect();
+
+    let mut order = Vec::new();
+    let mut seen = HashSet::new();
+    let mut offset = sections.last().and_then(|it| it.startxref);
+    while let Some(current) = offset {
+        let Some(i) = sections.iter().position(|it| it.offset == Some(current)) else {
+            return file_order;
+        };
+        if !seen.insert(i) {
+            return file_order;
+        }
+        order.push(i);
+        offset = sections[i].prev;
+    }
+
+    if order.len() != revisions.len() {
+        return file_order;
+    }
+    order.reverse();
+    order
+}
+
+fn non_negative(expr: ast::Expr) -> Option<u64> {
+    expr.as_int().and_then(|it| u64::try_from(it).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use syntax::Edition;
+
+    use crate::PdfObject;
+
+    use super::*;
+
+    /// Builds a file from `parts`, replacing `{N}` with the offset of the
+    /// `N`th cross-reference section. Offsets are padded to three digits, so
+    /// that the replacement doesn't move anything.
+    fn document(parts: &[&str]) -> Document {
+        let mut text = String::new();
+        let mut offsets = Vec::new();
+        for part in parts {
+            if part.starts_with("xref") || part.contains("/Type /XRef") {
+                offsets.push(text.len());
+            }
+            text.push_str(part);
+        }
+        for (i, offset) in offsets.iter().enumerate() {
+            text = text.replace(&format!("{{{i}}}"), &format!("{offset:03}"));
+        }
+        Document::parse(text.as_bytes(), Edition::CURRENT)
+    }
+
+    fn changes(revision: &Revision) -> Vec<(u32, u16, ObjectChange)> {
+        revision.changes().map(|(id, change)| (id.number, id.generation, change)).collect()
+    }
+
+    #[test]
+    fn lists_changes_of_each_revision() {
+        let document = document(&[
+            "%PDF-1.7\n1 0 obj << /Type /Catalog >> endobj\n2 0 obj (a) endobj\n3 0 obj (b) endobj\n",
+            "xref\n0 4\n0000000000 65535 f \n0000000009 00000 n \n0000000045 00000 n \n0000000064 00000 n \n",
+            "trailer\n<< /Size 4 /Root 1 0 R >>\nstartxref\n{0}\n%%EOF\n",
+            "2 0 obj (changed) endobj\n4 0 obj (new) endobj\n",
+            "xref\n0 1\n0000000000 65535 f \n2 1\n0000000200 00000 n \n3 2\n0000000000 00001 f \n0000000230 00000 n \n",
+            "trailer\n<< /Size 5 /Root 1 0 R /Prev {0} >>\nstartxref\n{1}\n%%EOF\n",
+        ]);
+
+        let revisions = document.revisions();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].prev(), None);
+        assert_eq!(
+            changes(&revisions[0]),
+            vec![(1, 0, ObjectChange::Added), (2, 0, ObjectChange::Added), (3, 0, ObjectChange::Added)]
+        );
+
+        assert_eq!(revisions[1].prev(), revisions[0].xref_offset());
+        assert_eq!(
+            changes(&revisions[1]),
+            vec![(2, 0, ObjectChange::Changed), (3, 0, ObjectChange::Freed), (4, 0, ObjectChange::Added)]
+        );
+        assert_eq!(revisions[1].freed().collect::<Vec<_>>(), vec![ObjectId::new(3, 0)]);
+
+        // Freed objects are gone, even though their old definition is still in
+        // the file.
+        assert!(document.object(ObjectId::new(3, 0)).is_none());
+        assert_eq!(document.get(ObjectId::new(3, 0)), Some(PdfObject::Null));
+        let ids: Vec<_> = document.objects().map(|(id, _)| id.number).collect();
+        assert_eq!(ids, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn follows_prev_chain() {
+        // The second revision in the file was written first: its section is
+        // the one `/Prev` of the other revision points to.
+        let document = document(&[
+            "1 0 obj (newer) endobj\n",
+            "xref\n0 2\n0000000000 65535 f \n0000000000 00000 n \n",
+            "trailer\n<< /Size 2 /Prev {1} >>\nstartxref\n{0}\n%%EOF\n",
+            "1 0 obj (older) endobj\n",
+            "xref\n0 2\n0000000000 65535 f \n0000000000 00000 n \n",
+            "trailer\n<< /Size 2 >>\nstartxref\n{0}\n%%EOF\n",
+        ]);
+
+        let revisions = document.revisions();
+        let bodies: Vec<_> = revisions.iter().map(|it| it.syntax().body().unwrap().syntax().text().to_string()).collect();
+        assert_eq!(bodies, vec!["1 0 obj (older) endobj", "1 0 obj (newer) endobj"]);
+        assert_eq!(changes(&revisions[0]), vec![(1, 0, ObjectChange::Added)]);
+        assert_eq!(changes(&revisions[1]), vec![(1, 0, ObjectChange::Changed)]);
+
+        // The newest definition is the one of the last revision, not the last
+        // one in the file.
+        let Some(PdfObject::String(string)) = document.get(ObjectId::new(1, 0)) else {
+            panic!()
+        };
+        assert_eq!(string.syntax().text().to_string(), "(newer)");
+    }
+
+    #[test]
+    fn reads_xref_streams() {
+        let document = document(&[
+            "1 0 obj (a) endobj\n2 0 obj (b) endobj\n",
+            "xref\n0 3\n0000000000 65535 f \n0000000000 00000 n \n0000000000 00000 n \n",
+            "trailer\n<< /Size 3 >>\nstartxref\n{0}\n%%EOF\n",
+            "3 0 obj\n<< /Type /XRef /Size 5 /W [1 1 1] /Index [1 1 3 2] /Prev {0} /Length 9 >>\nstream\n\x00\x03\x01\x01\x00\x00\x02\x05\x00\nendstream\nendobj\n",
+            "startxref\n{1}\n%%EOF\n",
+        ]);
+
+        let revisions = document.revisions();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(
+            changes(&revisions[1]),
+            vec![(1, 0, ObjectChange::Freed), (3, 0, ObjectChange::Added), (4, 0, ObjectChange::Added)]
+        );
+    }
+
+    #[test]
+    fn broken_chain_falls_back_to_file_order() {
+        let document = document(&[
+            "1 0 obj (a) endobj\n",
+            "xref\n0 2\n0000000000 65535 f \n0000000000 00000 n \n",
+            "trailer\n<< /Size 2 >>\nstartxref\n{0}\n%%EOF\n",
+            "2 0 obj (b) endobj\n",
+            "xref\n0 1\n0000000000 65535 f \n",
+            "trailer\n<< /Size 3 /Prev 12345 >>\nstartxref\n{1}\n%%EOF\n",
+        ]);
+
+        let revisions = document.revisions();
+        assert_eq!(changes(&revisions[0]), vec![(1, 0, ObjectChange::Added)]);
+        assert_eq!(changes(&revisions[1]), vec![(2, 0, ObjectChange::Added)]);
+    }
+}
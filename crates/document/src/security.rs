@@ -0,0 +1,647 @@
+//! Decryption of documents protected by the standard security handler.
+//!
+//! When the trailer has an `/Encrypt` entry, the strings and streams of all
+//! indirect objects are encrypted with a key derived from the user password
+//! (which is often empty) or the owner password. [`SecurityHandler`] derives
+//! that key and decrypts individual strings and streams.
+//!
+//! See ISO `32000-2:2020`, Section 7.6 Encryption.
+
+mod rc4;
+
+use std::fmt;
+
+use aes::cipher::{
+    BlockDecryptMut, BlockEncryptMut, KeyIvInit,
+    block_padding::{NoPadding, Pkcs7},
+};
+use md5::{Digest, Md5};
+use sha2::{Sha256, Sha384, Sha512};
+use syntax::ast;
+
+use crate::{Document, ObjectId, PdfObject, object};
+
+/// The padding string of Algorithm 2, appended to passwords shorter than 32
+/// bytes.
+const PASSWORD_PADDING: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01, 0x08, 0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C,
+    0xA9, 0xFE, 0x64, 0x53, 0x69, 0x7A,
+];
+
+/// How strings or streams are encrypted.
+///
+/// See ISO `32000-2:2020`, Table 25 Entries common to all crypt filter
+/// dictionaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CryptMethod {
+    /// Not encrypted (`/Identity` or `/None`).
+    Identity,
+    /// RC4 with a key derived per object (`/V2`).
+    Rc4,
+    /// AES-128 in CBC mode with a key derived per object (`/AESV2`).
+    AesV2,
+    /// AES-256 in CBC mode with the file key (`/AESV3`).
+    AesV3,
+}
+
+/// The reason why a document couldn't be decrypted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecurityError {
+    /// The document is encrypted by a security handler other than
+    /// `/Standard`.
+    UnsupportedHandler(String),
+    /// The encryption algorithm (`/V`) or the revision of the standard
+    /// security handler (`/R`) is not supported.
+    UnsupportedRevision { version: i64, revision: i64 },
+    /// A crypt filter uses a method other than `/None`, `/V2`, `/AESV2` or
+    /// `/AESV3`.
+    UnsupportedCryptMethod(String),
+    /// A required entry of the encryption dictionary is missing or has the
+    /// wrong type.
+    MalformedEntry(&'static str),
+    /// The password is neither the user nor the owner password.
+    IncorrectPassword,
+    /// Encrypted data is truncated or not padded correctly.
+    MalformedData(&'static str),
+}
+
+impl fmt::Display for SecurityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecurityError::UnsupportedHandler(name) => write!(f, "unsupported security handler {name}"),
+            SecurityError::UnsupportedRevision { version, revision } => {
+                write!(f, "unsupported encryption algorithm /V {version} /R {revision}")
+            }
+            SecurityError::UnsupportedCryptMethod(name) => write!(f, "unsupported crypt filter method {name}"),
+            SecurityError::MalformedEntry(key) => write!(f, "malformed {key} entry in the encryption dictionary"),
+            SecurityError::IncorrectPassword => write!(f, "incorrect password"),
+            SecurityError::MalformedData(reason) => write!(f, "malformed encrypted data: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for SecurityError {}
+
+/// The standard security handler, authenticated with a password.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecurityHandler {
+    revision: i64,
+    key: Vec<u8>,
+    strings: CryptMethod,
+    streams: CryptMethod,
+    encrypt_metadata: bool,
+    owner: bool,
+}
+
+impl SecurityHandler {
+    /// Authenticates `password` as either the user or the owner password of
+    /// the encryption dictionary `encrypt` of `document`.
+    pub(crate) fn new(document: &Document, encrypt: &ast::DictionaryExpr, password: &[u8]) -> Result<SecurityHandler, SecurityError> {
+        let _p = tracing::info_span!("SecurityHandler::new").entered();
+        let dictionary = EncryptDictionary::new(document, encrypt)?;
+        if dictionary.filter != b"Standard" {
+            return Err(SecurityError::UnsupportedHandler(String::from_utf8_lossy(&dictionary.filter).into_owned()));
+        }
+
+        let (key, owner) = match dictionary.revision {
+            2..=4 => dictionary.legacy_key(password)?,
+            5 | 6 => dictionary.aes256_key(password)?,
+            _ => unreachable!(),
+        };
+        Ok(SecurityHandler {
+            revision: dictionary.revision,
+            key,
+            strings: dictionary.strings,
+            streams: dictionary.streams,
+            encrypt_metadata: dictionary.encrypt_metadata,
+            owner,
+        })
+    }
+
+    /// The revision of the standard security handler (`/R`).
+    pub fn revision(&self) -> i64 {
+        self.revision
+    }
+
+    /// Whether the handler was authenticated with the owner password, which
+    /// grants all permissions.
+    pub fn is_owner(&self) -> bool {
+        self.owner
+    }
+
+    pub fn string_method(&self) -> CryptMethod {
+        self.strings
+    }
+
+    pub fn stream_method(&self) -> CryptMethod {
+        self.streams
+    }
+
+    /// Whether metadata streams (`/Type /Metadata`) are encrypted.
+    pub fn encrypts_metadata(&self) -> bool {
+        self.encrypt_metadata
+    }
+
+    /// Decrypts a string of the object `id`.
+    pub fn decrypt_string(&self, id: ObjectId, data: &[u8]) -> Result<Vec<u8>, SecurityError> {
+        self.decrypt(self.strings, id, data)
+    }
+
+    /// Decrypts the data of the stream object `id`.
+    pub fn decrypt_stream(&self, id: ObjectId, data: &[u8]) -> Result<Vec<u8>, SecurityError> {
+        self.decrypt(self.streams, id, data)
+    }
+
+    fn decrypt(&self, method: CryptMethod, id: ObjectId, data: &[u8]) -> Result<Vec<u8>, SecurityError> {
+        match method {
+            CryptMethod::Identity => Ok(data.to_vec()),
+            CryptMethod::Rc4 => Ok(rc4::apply(&self.object_key(id, false), data)),
+            CryptMethod::AesV2 => aes_decrypt(method, &self.object_key(id, true), data),
+            CryptMethod::AesV3 => aes_decrypt(method, &self.key, data),
+        }
+    }
+
+    /// The key for the strings and streams of the object `id`.
+    /// See ISO `32000-2:2020`, Section 7.6.3.2 Algorithm 1.
+    fn object_key(&self, id: ObjectId, aes: bool) -> Vec<u8> {
+        let mut hash = Md5::new();
+        hash.update(&self.key);
+        hash.update(&id.number.to_le_bytes()[..3]);
+        hash.update(id.generation.to_le_bytes());
+        if aes {
+            hash.update(b"sAlT");
+        }
+        let len = (self.key.len() + 5).min(16);
+        hash.finalize()[..len].to_vec()
+    }
+}
+
+/// The entries of an encryption dictionary which are needed to authenticate.
+/// See ISO `32000-2:2020`, Tables 20 and 21.
+struct EncryptDictionary {
+    filter: Vec<u8>,
+    revision: i64,
+    /// The length of the file key in bytes.
+    key_length: usize,
+    owner: Vec<u8>,
+    user: Vec<u8>,
+    owner_encrypted_key: Vec<u8>,
+    user_encrypted_key: Vec<u8>,
+    permissions: i32,
+    encrypt_metadata: bool,
+    strings: CryptMethod,
+    streams: CryptMethod,
+    /// The first element of the file identifier (`/ID`) in the trailer.
+    id: Vec<u8>,
+}
+
+impl EncryptDictionary {
+    fn new(document: &Document, encrypt: &ast::DictionaryExpr) -> Result<EncryptDictionary, SecurityError> {
+        let value = |key: &[u8]| encrypt.get(key).and_then(|it| document.value(it));
+        let int = |key: &'static str| match value(key.as_bytes()) {
+            Some(PdfObject::Int(it)) => Ok(Some(it)),
+            None => Ok(None),
+            Some(_) => Err(SecurityError::MalformedEntry(key)),
+        };
+        let string = |key: &'static str| match value(key.as_bytes()) {
+            Some(PdfObject::String(it)) => object::string_bytes(&it).ok_or(SecurityError::MalformedEntry(key)),
+            _ => Err(SecurityError::MalformedEntry(key)),
+        };
+        let name = |object: Option<PdfObject>, key: &'static str| match object {
+            Some(PdfObject::Name(it)) => it.value().map(Some).map_err(|_| SecurityError::MalformedEntry(key)),
+            None => Ok(None),
+            Some(_) => Err(SecurityError::MalformedEntry(key)),
+        };
+
+        let filter = name(value(b"/Filter"), "/Filter")?.ok_or(SecurityError::MalformedEntry("/Filter"))?;
+        let version = int("/V")?.unwrap_or(0);
+        let revision = int("/R")?.ok_or(SecurityError::MalformedEntry("/R"))?;
+        match (version, revision) {
+            (1 | 2, 2 | 3) | (4, 4) | (5, 5 | 6) => (),
+            _ => return Err(SecurityError::UnsupportedRevision { version, revision }),
+        }
+
+        // Revisions 4 and later select the methods by crypt filters, which
+        // are named by `/StmF` and `/StrF` and defined in `/CF`.
+        let crypt_filters = match value(b"/CF") {
+            Some(PdfObject::Dict(it)) => Some(it),
+            None => None,
+            Some(_) => return Err(SecurityError::MalformedEntry("/CF")),
+        };
+        let crypt_filter = |key: &'static str| -> Result<(CryptMethod, Option<i64>), SecurityError> {
+            if version < 4 {
+                return Ok((CryptMethod::Rc4, None));
+            }
+            let filter_name = name(value(key.as_bytes()), key)?.unwrap_or_else(|| b"Identity".to_vec());
+            if filter_name == b"Identity" {
+                return Ok((CryptMethod::Identity, None));
+            }
+            let filter = crypt_filters
+                .as_ref()
+                .and_then(|it| it.get(&[b"/".as_slice(), &filter_name].concat()))
+                .and_then(|it| document.value(it));
+            let Some(PdfObject::Dict(filter)) = filter else {
+                return Err(SecurityError::MalformedEntry(key));
+            };
+            let method = name(filter.get(b"/CFM").and_then(|it| document.value(it)), "/CFM")?;
+            let method = match method.as_deref() {
+                None | Some(b"None") => CryptMethod::Identity,
+                Some(b"V2") => CryptMethod::Rc4,
+                Some(b"AESV2") => CryptMethod::AesV2,
+                Some(b"AESV3") => CryptMethod::AesV3,
+                Some(other) => return Err(SecurityError::UnsupportedCryptMethod(String::from_utf8_lossy(other).into_owned())),
+            };
+            let length = match filter.get(b"/Length").and_then(|it| document.value(it)) {
+                Some(PdfObject::Int(it)) => Some(it),
+                _ => None,
+            };
+            Ok((method, length))
+        };
+        let (streams, stream_key_length) = crypt_filter("/StmF")?;
+        let (strings, string_key_length) = crypt_filter("/StrF")?;
+
+        let key_length = match version {
+            1 => 5,
+            5 => 32,
+            // The length of a crypt filter is meant to be in bytes, but is
+            // often given in bits, like `/Length` of the encryption dictionary.
+            4 => match stream_key_length.or(string_key_length) {
+                Some(length @ 5..=16) => length as usize,
+                Some(length) => (length / 8).clamp(5, 16) as usize,
+                None => 16,
+            },
+            _ => (int("/Length")?.unwrap_or(40) / 8).clamp(5, 16) as usize,
+        };
+
+        let id = match document.trailer().and_then(|it| it.get(b"/ID")).and_then(|it| document.value(it)) {
+            Some(PdfObject::Array(array)) => match array.exprs().next().and_then(|it| document.value(it)) {
+                Some(PdfObject::String(it)) => object::string_bytes(&it).unwrap_or_default(),
+                _ => Vec::new(),
+            },
+            // The identifier is required for encrypted documents, but it's
+            // only an input to the key derivation, so try without it.
+            _ => Vec::new(),
+        };
+
+        let aes256 = revision >= 5;
+        Ok(EncryptDictionary {
+            filter,
+            revision,
+            key_length,
+            owner: string("/O")?,
+            user: string("/U")?,
+            owner_encrypted_key: if aes256 { string("/OE")? } else { Vec::new() },
+            user_encrypted_key: if aes256 { string("/UE")? } else { Vec::new() },
+            // `/P` is a 32-bit two's complement number, but is sometimes
+            // written unsigned.
+            permissions: int("/P")?.ok_or(SecurityError::MalformedEntry("/P"))? as i32,
+            encrypt_metadata: !matches!(value(b"/EncryptMetadata"), Some(PdfObject::Bool(false))),
+            strings,
+            streams,
+            id,
+        })
+    }
+
+    /// Authenticates `password` for revisions 2 to 4, returning the file key
+    /// and whether `password` is the owner password.
+    /// See ISO `32000-2:2020`, Section 7.6.4.4 Algorithms 6 and 7.
+    fn legacy_key(&self, password: &[u8]) -> Result<(Vec<u8>, bool), SecurityError> {
+        if let Some(key) = self.authenticate_user(password) {
+            return Ok((key, false));
+        }
+
+        // The owner password decrypts `/O` to the user password.
+        let key = &self.owner_password_key(password);
+        let mut user_password = self.owner[..self.owner.len().min(32)].to_vec();
+        let rounds = if self.revision == 2 { 0..=0 } else { 0..=19 };
+        for i in rounds.rev() {
+            let key: Vec<u8> = key.iter().map(|b| b ^ i).collect();
+            user_password = rc4::apply(&key, &user_password);
+        }
+        match self.authenticate_user(&user_password) {
+            Some(key) => Ok((key, true)),
+            None => Err(SecurityError::IncorrectPassword),
+        }
+    }
+
+    /// Returns the file key if `password` is the user password.
+    /// See ISO `32000-2:2020`, Section 7.6.4.4 Algorithms 4, 5 and 6.
+    fn authenticate_user(&self, password: &[u8]) -> Option<Vec<u8>> {
+        let key = self.file_key(password);
+        let user = if self.revision == 2 {
+            rc4::apply(&key, &PASSWORD_PADDING)
+        } else {
+            let mut hash = Md5::new();
+            hash.update(PASSWORD_PADDING);
+            hash.update(&self.id);
+            let mut user = rc4::apply(&key, &hash.finalize());
+            for i in 1..=19 {
+                let key: Vec<u8> = key.iter().map(|b| b ^ i).collect();
+                user = rc4::apply(&key, &user);
+            }
+            user
+        };
+        // From revision 3 on, only the first 16 bytes are significant.
+        let len = if self.revision == 2 { 32 } else { 16 };
+        (self.user.get(..len)? == &user[..len]).then_some(key)
+    }
+
+    /// Computes the file key from the user password.
+    /// See ISO `32000-2:2020`, Section 7.6.4.3.2 Algorithm 2.
+    fn file_key(&self, password: &[u8]) -> Vec<u8> {
+        let mut hash = Md5::new();
+        hash.update(pad_password(password));
+        hash.update(&self.owner[..self.owner.len().min(32)]);
+        hash.update(self.permissions.to_le_bytes());
+        hash.update(&self.id);
+        if self.revision >= 4 && !self.encrypt_metadata {
+            hash.update([0xFF; 4]);
+        }
+        let mut key = hash.finalize();
+        if self.revision >= 3 {
+            for _ in 0..50 {
+                key = Md5::digest(&key[..self.key_length]);
+            }
+        }
+        key[..self.key_length].to_vec()
+    }
+
+    /// The RC4 key which encrypts the user password in `/O`.
+    /// See ISO `32000-2:2020`, Section 7.6.4.4 Algorithm 3.
+    fn owner_password_key(&self, password: &[u8]) -> Vec<u8> {
+        let mut key = Md5::digest(pad_password(password));
+        if self.revision >= 3 {
+            for _ in 0..50 {
+                key = Md5::digest(key);
+            }
+        }
+        key[..self.key_length].to_vec()
+    }
+
+    /// Authenticates `password` for revisions 5 and 6, returning the file key
+    /// and whether `password` is the owner password.
+    /// See ISO `32000-2:2020`, Section 7.6.4.3.3 Algorithm 2.A.
+    fn aes256_key(&self, password: &[u8]) -> Result<(Vec<u8>, bool), SecurityError> {
+        // Passwords are UTF-8, of which only the first 127 bytes are used.
+        let password = &password[..password.len().min(127)];
+        if self.owner.len() < 48 || self.user.len() < 48 {
+            return Err(SecurityError::MalformedEntry(if self.owner.len() < 48 { "/O" } else { "/U" }));
+        }
+        let user = &self.user[..48];
+
+        let (key_hash, encrypted_key, owner) = if self.hash(password, &self.owner[32..40], user) == self.owner[..32] {
+            (self.hash(password, &self.owner[40..48], user), &self.owner_encrypted_key, true)
+        } else if self.hash(password, &self.user[32..40], &[]) == self.user[..32] {
+            (self.hash(password, &self.user[40..48], &[]), &self.user_encrypted_key, false)
+        } else {
+            return Err(SecurityError::IncorrectPassword);
+        };
+
+        let encrypted_key = encrypted_key
+            .get(..32)
+            .ok_or(SecurityError::MalformedEntry(if owner { "/OE" } else { "/UE" }))?;
+        let mut key = encrypted_key.to_vec();
+        cbc::Decryptor::<aes::Aes256>::new_from_slices(&key_hash, &[0; 16])
+            .unwrap()
+            .decrypt_padded_mut::<NoPadding>(&mut key)
+            .unwrap();
+        Ok((key, owner))
+    }
+
+    /// Hashes a password with a salt, and the user key when checking the
+    /// owner password.
+    /// See ISO `32000-2:2020`, Section 7.6.4.3.4 Algorithm 2.B.
+    fn hash(&self, password: &[u8], salt: &[u8], user_key: &[u8]) -> Vec<u8> {
+        let mut k = Sha256::new()
+            .chain_update(password)
+            .chain_update(salt)
+            .chain_update(user_key)
+            .finalize()
+            .to_vec();
+        if self.revision == 5 {
+            return k;
+        }
+
+        let mut round = 0;
+        loop {
+            let k1 = [password, &k, user_key].concat().repeat(64);
+            let mut e = k1.clone();
+            cbc::Encryptor::<aes::Aes128>::new_from_slices(&k[..16], &k[16..32])
+                .unwrap()
+                .encrypt_padded_mut::<NoPadding>(&mut e, k1.len())
+                .unwrap();
+            // The first 16 bytes of `e` as a big-endian number modulo 3 is
+            // the same as the sum of those bytes modulo 3.
+            k = match e[..16].iter().map(|&b| u32::from(b)).sum::<u32>() % 3 {
+                0 => Sha256::digest(&e).to_vec(),
+                1 => Sha384::digest(&e).to_vec(),
+                _ => Sha512::digest(&e).to_vec(),
+            };
+            round += 1;
+            if round >= 64 && u32::from(*e.last().unwrap()) <= round - 32 {
+                break;
+            }
+        }
+        k.truncate(32);
+        k
+    }
+}
+
+fn pad_password(password: &[u8]) -> [u8; 32] {
+    let mut padded = PASSWORD_PADDING;
+    let len = password.len().min(32);
+    padded[..len].copy_from_slice(&password[..len]);
+    padded[len..].copy_from_slice(&PASSWORD_PADDING[..32 - len]);
+    padded
+}
+
+/// Decrypts AES-CBC data, which is prefixed by the initialization vector and
+/// padded as described in RFC 8018.
+///
+/// `/AESV2` is AES-128 and `/AESV3` is AES-256, so a key of another length
+/// (from a crypt filter with a short `/Length`) is rejected.
+fn aes_decrypt(method: CryptMethod, key: &[u8], data: &[u8]) -> Result<Vec<u8>, SecurityError> {
+    // An empty string may be encrypted to nothing at all.
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+    if data.len() < 32 || data.len() % 16 != 0 {
+        return Err(SecurityError::MalformedData("AES data must be a multiple of 16 bytes"));
+    }
+    let (iv, data) = data.split_at(16);
+    let mut buffer = data.to_vec();
+    let invalid_key = |_| SecurityError::MalformedEntry("/Length");
+    let len = match method {
+        CryptMethod::AesV2 => cbc::Decryptor::<aes::Aes128>::new_from_slices(key, iv)
+            .map_err(invalid_key)?
+            .decrypt_padded_mut::<Pkcs7>(&mut buffer),
+        CryptMethod::AesV3 => cbc::Decryptor::<aes::Aes256>::new_from_slices(key, iv)
+            .map_err(invalid_key)?
+            .decrypt_padded_mut::<Pkcs7>(&mut buffer),
+        CryptMethod::Identity | CryptMethod::Rc4 => unreachable!(),
+    }
+    .map_err(|_| SecurityError::MalformedData("invalid AES padding"))?
+    .len();
+    buffer.truncate(len);
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &[u8] = b"BT /F1 12 Tf 20 100 Td (Hello, encrypted world) Tj ET";
+    const TITLE: &[u8] = b"Secret (title) with \\ and \r bytes";
+
+    fn info_string(document: &Document, key: &[u8]) -> ast::Literal {
+        let Some(PdfObject::Dict(info)) = document.value(document.trailer().unwrap().get(b"/Info").unwrap()) else {
+            panic!()
+        };
+        let Some(PdfObject::String(string)) = document.value(info.get(key).unwrap()) else {
+            panic!()
+        };
+        string
+    }
+
+    fn stream(document: &Document, number: u32) -> ast::StreamExpr {
+        match document.get(ObjectId::new(number, 0)) {
+            Some(PdfObject::Stream(it)) => it,
+            _ => panic!(),
+        }
+    }
+
+    /// Checks the decrypted content stream, information dictionary and
+    /// metadata stream of a fixture.
+    #[track_caller]
+    fn check_decrypted(document: &Document) {
+        assert_eq!(document.decode_stream(&stream(document, 4)).unwrap(), CONTENT);
+        assert_eq!(document.string(&info_string(document, b"/Title")).unwrap(), TITLE);
+        assert_eq!(document.string(&info_string(document, b"/Producer")).unwrap(), b"pdf-analyzer fixtures");
+        assert_eq!(document.stream_data(&stream(document, 7)).unwrap(), b"<x:xmpmeta xmlns:x='adobe:ns:meta/'/>");
+    }
+
+    #[test]
+    fn empty_user_password() {
+        for (text, revision, method) in [
+            (&include_bytes!("../test_data/encryption/rc4_40.pdf")[..], 2, CryptMethod::Rc4),
+            (include_bytes!("../test_data/encryption/aes_128.pdf"), 4, CryptMethod::AesV2),
+            (include_bytes!("../test_data/encryption/aes_256_r5.pdf"), 5, CryptMethod::AesV3),
+        ] {
+            let document = Document::detect_and_parse(text);
+            assert!(document.is_encrypted());
+            let handler = document.security_handler().unwrap();
+            assert_eq!((handler.revision(), handler.stream_method(), handler.is_owner()), (revision, method, false));
+            check_decrypted(&document);
+        }
+    }
+
+    #[test]
+    fn user_and_owner_passwords() {
+        for text in [
+            &include_bytes!("../test_data/encryption/rc4_128.pdf")[..],
+            include_bytes!("../test_data/encryption/aes_256.pdf"),
+        ] {
+            let mut document = Document::detect_and_parse(text);
+            assert!(document.is_encrypted());
+            assert!(document.security_handler().is_none());
+            assert_eq!(document.string(&info_string(&document, b"/Title")), None);
+            assert_eq!(document.stream_data(&stream(&document, 4)), None);
+
+            assert_eq!(document.authenticate(b"wrong"), Err(SecurityError::IncorrectPassword));
+            assert_eq!(document.authenticate(b"user"), Ok(()));
+            assert!(!document.security_handler().unwrap().is_owner());
+            check_decrypted(&document);
+
+            assert_eq!(document.authenticate(b"owner"), Ok(()));
+            assert!(document.security_handler().unwrap().is_owner());
+            check_decrypted(&document);
+        }
+    }
+
+    #[test]
+    fn owner_password_of_legacy_revisions() {
+        let mut document = Document::detect_and_parse(include_bytes!("../test_data/encryption/rc4_40.pdf"));
+        assert_eq!(document.authenticate(b"owner"), Ok(()));
+        assert!(document.security_handler().unwrap().is_owner());
+        check_decrypted(&document);
+    }
+
+    #[test]
+    fn unencrypted_parts() {
+        let document = Document::detect_and_parse(include_bytes!("../test_data/encryption/aes_128.pdf"));
+        // The file identifier in the trailer and the strings of the encryption
+        // dictionary are not encrypted.
+        let Some(PdfObject::Array(id)) = document.value(document.trailer().unwrap().get(b"/ID").unwrap()) else {
+            panic!()
+        };
+        let Some(PdfObject::String(id)) = document.value(id.exprs().next().unwrap()) else {
+            panic!()
+        };
+        assert_eq!(document.string(&id).unwrap()[..4], [0x5A, 0x1C, 0x6D, 0x2E]);
+
+        let Some(PdfObject::Dict(encrypt)) = document.get(ObjectId::new(6, 0)) else {
+            panic!()
+        };
+        let Some(PdfObject::String(owner)) = document.value(encrypt.get(b"/O").unwrap()) else {
+            panic!()
+        };
+        assert_eq!(document.string(&owner), object::string_bytes(&owner));
+
+        let unencrypted = Document::parse(b"1 0 obj (plain) endobj\n", syntax::Edition::CURRENT);
+        assert!(!unencrypted.is_encrypted());
+        let Some(PdfObject::String(it)) = unencrypted.get(ObjectId::new(1, 0)) else {
+            panic!()
+        };
+        assert_eq!(unencrypted.string(&it).unwrap(), b"plain");
+    }
+
+    #[test]
+    fn password_padding() {
+        assert_eq!(pad_password(b""), PASSWORD_PADDING);
+        assert_eq!(&pad_password(b"user")[..6], &[b'u', b's', b'e', b'r', 0x28, 0xBF]);
+        assert_eq!(pad_password(&[b'x'; 40]), [b'x'; 32]);
+    }
+
+    #[test]
+    fn aes_data_must_be_whole_blocks() {
+        let key = [0; 16];
+        assert_eq!(aes_decrypt(CryptMethod::AesV2, &key, b""), Ok(Vec::new()));
+        assert_eq!(
+            aes_decrypt(CryptMethod::AesV2, &key, &[0; 20]),
+            Err(SecurityError::MalformedData("AES data must be a multiple of 16 bytes"))
+        );
+        assert_eq!(
+            aes_decrypt(CryptMethod::AesV2, &key, &[0; 16]),
+            Err(SecurityError::MalformedData("AES data must be a multiple of 16 bytes"))
+        );
+    }
+
+    #[test]
+    fn aes_round_trip() {
+        let (key, iv) = ([7; 16], [9; 16]);
+        let mut data = [0; 32];
+        data[..5].copy_from_slice(b"hello");
+        let encrypted = cbc::Encryptor::<aes::Aes128>::new_from_slices(&key, &iv)
+            .unwrap()
+            .encrypt_padded_mut::<Pkcs7>(&mut data, 5)
+            .unwrap();
+        assert_eq!(aes_decrypt(CryptMethod::AesV2, &key, &[&iv[..], encrypted].concat()), Ok(b"hello".to_vec()));
+    }
+
+    #[test]
+    fn short_aes_128_key() {
+        // A crypt filter with `/CFM /AESV2 /Length 40` gives a 5-byte file
+        // key, and a 10-byte object key which isn't an AES key.
+        let handler = SecurityHandler {
+            revision: 4,
+            key: vec![1; 5],
+            strings: CryptMethod::AesV2,
+            streams: CryptMethod::AesV2,
+            encrypt_metadata: true,
+            owner: false,
+        };
+        let id = ObjectId::new(1, 0);
+        assert_eq!(handler.decrypt_string(id, &[0; 32]), Err(SecurityError::MalformedEntry("/Length")));
+        assert_eq!(handler.decrypt_stream(id, &[0; 48]), Err(SecurityError::MalformedEntry("/Length")));
+    }
+}
@@ -0,0 +1,157 @@
+#!/usr/bin/env python3
+"""Generates the encrypted test files of this directory, see README.md.
+
+Keys, salts and initialization vectors come from a seeded generator, so the
+output is the same on every run. Requires the `cryptography` package.
+
+Usage: python3 generate.py (writes the files to the current directory)
+"""
+
+import hashlib
+import random
+import struct
+
+from cryptography.hazmat.primitives.ciphers import Cipher, algorithms, modes
+
+rng = random.Random(13)
+
+# Standard security handler, ISO 32000-2:2020, Section 7.6.4.
+PAD = bytes.fromhex("28BF4E5E4E758A4164004E56FFFA01082E2E00B6D0683E802F0CA9FE6453697A")
+
+def rc4(key, data):
+    S = list(range(256)); j = 0
+    for i in range(256):
+        j = (j + S[i] + key[i % len(key)]) % 256; S[i], S[j] = S[j], S[i]
+    out = bytearray(); i = j = 0
+    for b in data:
+        i = (i + 1) % 256; j = (j + S[i]) % 256; S[i], S[j] = S[j], S[i]
+        out.append(b ^ S[(S[i] + S[j]) % 256])
+    return bytes(out)
+
+def aes_cbc(key, iv, data, enc):
+    c = Cipher(algorithms.AES(key), modes.CBC(iv))
+    x = c.encryptor() if enc else c.decryptor()
+    return x.update(data) + x.finalize()
+
+def pad(pw): return (pw + PAD)[:32]
+
+def file_key(pw, O, P, id0, R, n, encrypt_metadata=True):
+    h = hashlib.md5(pad(pw) + O + struct.pack('<i', P) + id0 + (b'\xff\xff\xff\xff' if R >= 4 and not encrypt_metadata else b'')).digest()
+    if R >= 3:
+        for _ in range(50): h = hashlib.md5(h[:n]).digest()
+    return h[:n]
+
+def compute_O(owner, user, R, n):
+    h = hashlib.md5(pad(owner)).digest()
+    if R >= 3:
+        for _ in range(50): h = hashlib.md5(h).digest()
+    k = h[:n]
+    o = rc4(k, pad(user))
+    if R >= 3:
+        for i in range(1, 20): o = rc4(bytes(b ^ i for b in k), o)
+    return o
+
+def compute_U(key, id0, R):
+    if R == 2: return rc4(key, PAD)
+    u = rc4(key, hashlib.md5(PAD + id0).digest())
+    for i in range(1, 20): u = rc4(bytes(b ^ i for b in key), u)
+    return u + b'\0' * 16
+
+def hash_2b(pw, salt, udata, R):
+    k = hashlib.sha256(pw + salt + udata).digest()
+    if R == 5: return k
+    i = 0
+    while True:
+        k1 = (pw + k + udata) * 64
+        e = aes_cbc(k[:16], k[16:32], k1, True)
+        m = sum(e[:16]) % 3
+        k = [hashlib.sha256, hashlib.sha384, hashlib.sha512][m](e).digest()
+        i += 1
+        if i >= 64 and e[-1] <= i - 32: break
+    return k[:32]
+
+def object_key(key, num, gen, aes):
+    if len(key) == 32: return key
+    h = hashlib.md5(key + struct.pack('<I', num)[:3] + struct.pack('<H', gen) + (b'sAlT' if aes else b'')).digest()
+    return h[:min(len(key) + 5, 16)]
+
+def encrypt(method, key, num, gen, data):
+    if method == 'rc4': return rc4(object_key(key, num, gen, False), data)
+    k = object_key(key, num, gen, True)
+    iv = rng.randbytes(16); p = 16 - len(data) % 16
+    return iv + aes_cbc(k, iv, data + bytes([p]) * p, True)
+
+def decrypt(method, key, num, gen, data):
+    if method == 'rc4': return rc4(object_key(key, num, gen, False), data)
+    k = object_key(key, num, gen, True)
+    d = aes_cbc(k, data[:16], data[16:], False)
+    return d[:-d[-1]]
+
+
+# The test files.
+ID0 = bytes.fromhex("5A1C6D2E9F3B47C08E21D4B6A9F0C3E7")
+ID1 = bytes.fromhex("0F1E2D3C4B5A69788796A5B4C3D2E1F0")
+CONTENT = b"BT /F1 12 Tf 20 100 Td (Hello, encrypted world) Tj ET"
+METADATA = b"<x:xmpmeta xmlns:x='adobe:ns:meta/'/>"
+TITLE = b"Secret (title) with \\ and \r bytes"
+PRODUCER = b"pdf-analyzer fixtures"
+
+def lit(b):
+    return b"(" + b.replace(b"\\", b"\\\\").replace(b"(", b"\\(").replace(b")", b"\\)").replace(b"\r", b"\\r") + b")"
+
+def hexs(b): return b"<" + b.hex().upper().encode() + b">"
+
+def build(name, R, user, owner, method, n, encrypt_metadata=True):
+    P = -3904
+    if R <= 4:
+        O = compute_O(owner, user, R, n)
+        key = file_key(user, O, P, ID0, R, n, encrypt_metadata)
+        U = compute_U(key, ID0, R)
+        assert file_key(user, O, P, ID0, R, n, encrypt_metadata) == key
+        enc = {1: b"<< /Filter /Standard /V 1 /R 2 /O %s /U %s /P %d >>",
+               3: b"<< /Filter /Standard /V 2 /R 3 /Length 128 /O %s /U %s /P %d >>",
+               4: b"<< /Filter /Standard /V 4 /R 4 /Length 128 /CF << /StdCF << /AuthEvent /DocOpen /CFM /AESV2 /Length 16 >> >> /StmF /StdCF /StrF /StdCF /O %s /U %s /P %d" + (b"" if encrypt_metadata else b" /EncryptMetadata false") + b" >>"}
+        enc = enc[1 if R == 2 else R] % (hexs(O), hexs(U), P)
+    else:
+        key = rng.randbytes(32)
+        uvs, uks, ovs, oks = (rng.randbytes(8) for _ in range(4))
+        U = hash_2b(user, uvs, b"", R) + uvs + uks
+        UE = aes_cbc(hash_2b(user, uks, b"", R), b"\0" * 16, key, True)
+        O = hash_2b(owner, ovs, U, R) + ovs + oks
+        OE = aes_cbc(hash_2b(owner, oks, U, R), b"\0" * 16, key, True)
+        perms = struct.pack('<i', P) + b"\xff\xff\xff\xff" + b"Tadb" + rng.randbytes(4)
+        c = Cipher(algorithms.AES(key), modes.ECB()).encryptor()
+        Perms = c.update(perms) + c.finalize()
+        enc = b"<< /Filter /Standard /V 5 /R %d /Length 256 /CF << /StdCF << /AuthEvent /DocOpen /CFM /AESV3 /Length 32 >> >> /StmF /StdCF /StrF /StdCF /O %s /U %s /OE %s /UE %s /P %d /Perms %s >>" % (R, hexs(O), hexs(U), hexs(OE), hexs(UE), P, hexs(Perms))
+
+    e = lambda num, data: encrypt(method, key, num, 0, data)
+    content = e(4, CONTENT)
+    metadata = METADATA if not encrypt_metadata else e(7, METADATA)
+    objects = [
+        b"<< /Type /Catalog /Pages 2 0 R /Metadata 7 0 R >>",
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>",
+        b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 200 200] /Contents 4 0 R >>",
+        b"<< /Length %d >>\nstream\n" % len(content) + content + b"\nendstream",
+        b"<< /Title " + lit(e(5, TITLE)) + b" /Producer " + hexs(e(5, PRODUCER)) + b" >>",
+        enc,
+        b"<< /Type /Metadata /Subtype /XML /Length %d >>\nstream\n" % len(metadata) + metadata + b"\nendstream",
+    ]
+    out = bytearray(b"%PDF-" + (b"2.0" if R >= 5 else b"1.7") + b"\n%\xe2\xe3\xcf\xd3\n")
+    offsets = []
+    for i, body in enumerate(objects, 1):
+        offsets.append(len(out))
+        out += b"%d 0 obj\n" % i + body + b"\nendobj\n"
+    xref = len(out)
+    out += b"xref\n0 %d\n0000000000 65535 f \n" % (len(objects) + 1)
+    for o in offsets:
+        out += b"%010d 00000 n \n" % o
+    out += b"trailer\n<< /Size %d /Root 1 0 R /Info 5 0 R /Encrypt 6 0 R /ID [%s %s] >>\nstartxref\n%d\n%%%%EOF\n" % (len(objects) + 1, hexs(ID0), hexs(ID1), xref)
+    open(name, "wb").write(out)
+    # Round trip.
+    assert decrypt(method, key, 4, 0, content) == CONTENT
+
+build("rc4_40.pdf", 2, b"", b"owner", "rc4", 5)
+build("rc4_128.pdf", 3, b"user", b"owner", "rc4", 16)
+build("aes_128.pdf", 4, b"", b"owner", "aes", 16, encrypt_metadata=False)
+build("aes_256_r5.pdf", 5, b"", b"owner", "aes", 32)
+build("aes_256.pdf", 6, b"user", b"owner", "aes", 32)
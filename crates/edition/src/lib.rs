@@ -20,6 +20,27 @@ pub enum Edition {
 impl Edition {
     pub const CURRENT: Edition = Edition::Pdf20;
     pub const LATEST: Edition = Edition::Pdf20;
+
+    /// The version as written in the file header, e.g. `"1.7"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Edition::Pdf10 => "1.0",
+            Edition::Pdf11 => "1.1",
+            Edition::Pdf12 => "1.2",
+            Edition::Pdf13 => "1.3",
+            Edition::Pdf14 => "1.4",
+            Edition::Pdf15 => "1.5",
+            Edition::Pdf16 => "1.6",
+            Edition::Pdf17 => "1.7",
+            Edition::Pdf20 => "2.0",
+        }
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 #[derive(Debug)]
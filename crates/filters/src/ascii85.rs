@@ -0,0 +1,69 @@
+//! `ASCII85Decode`: groups of five base-85 digits (`!` to `u`) per four bytes,
+//! terminated by `~>`.
+
+pub(crate) fn decode(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let mut out = Vec::with_capacity(data.len() / 5 * 4);
+    let mut group = [0u8; 5];
+    let mut len = 0;
+    let mut bytes = data.iter().copied();
+    while let Some(b) = bytes.next() {
+        match b {
+            b'~' => {
+                if bytes.next() != Some(b'>') {
+                    return Err("invalid end-of-data marker");
+                }
+                break;
+            }
+            b'z' if len == 0 => out.extend_from_slice(&[0; 4]),
+            b'!'..=b'u' => {
+                group[len] = b - b'!';
+                len += 1;
+                if len == 5 {
+                    out.extend_from_slice(&group_value(&group)?.to_be_bytes());
+                    len = 0;
+                }
+            }
+            b if crate::is_whitespace(b) => {}
+            _ => return Err("invalid base-85 digit"),
+        }
+    }
+
+    // A final partial group of n digits encodes n - 1 bytes; it's padded with
+    // the highest digit before decoding.
+    match len {
+        0 => {}
+        1 => return Err("incomplete final group"),
+        _ => {
+            group[len..].fill(b'u' - b'!');
+            out.extend_from_slice(&group_value(&group)?.to_be_bytes()[..len - 1]);
+        }
+    }
+    Ok(out)
+}
+
+fn group_value(group: &[u8; 5]) -> Result<u32, &'static str> {
+    let value = group.iter().fold(0u64, |acc, &digit| acc * 85 + u64::from(digit));
+    u32::try_from(value).map_err(|_| "group value out of range")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_groups() {
+        assert_eq!(decode(b"87cURD]i,\"Ebo80~>").unwrap(), b"Hello World!");
+        assert_eq!(decode(b"87cUR\nD]i,\"Ebo8~>").unwrap(), b"Hello World");
+        assert_eq!(decode(b"z!!~>").unwrap(), b"\0\0\0\0\0");
+        assert_eq!(decode(b"~>").unwrap(), b"");
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(decode(b"87c{~>"), Err("invalid base-85 digit"));
+        assert_eq!(decode(b"87cURD~x"), Err("invalid end-of-data marker"));
+        assert_eq!(decode(b"8~>"), Err("incomplete final group"));
+        assert_eq!(decode(b"uuuuu~>"), Err("group value out of range"));
+        assert_eq!(decode(b"8z~>"), Err("invalid base-85 digit"));
+    }
+}
@@ -0,0 +1,41 @@
+//! `ASCIIHexDecode`: two hexadecimal digits per byte, terminated by `>`.
+
+pub(crate) fn decode(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let mut out = Vec::with_capacity(data.len() / 2);
+    let mut high = None;
+    for &b in data {
+        if b == b'>' {
+            break;
+        }
+        if crate::is_whitespace(b) {
+            continue;
+        }
+        let digit = (b as char).to_digit(16).ok_or("invalid hex digit")? as u8;
+        match high.take() {
+            Some(high) => out.push(high << 4 | digit),
+            None => high = Some(digit),
+        }
+    }
+    // An odd number of digits behaves as if a final 0 followed.
+    if let Some(high) = high {
+        out.push(high << 4);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_digits() {
+        assert_eq!(decode(b"48 65\n6c6C 6f>").unwrap(), b"Hello");
+        assert_eq!(decode(b"414>ignored").unwrap(), b"A@");
+        assert_eq!(decode(b"").unwrap(), b"");
+    }
+
+    #[test]
+    fn rejects_non_hex() {
+        assert_eq!(decode(b"4x>"), Err("invalid hex digit"));
+    }
+}
@@ -0,0 +1,51 @@
+//! `FlateDecode`: zlib/deflate compressed data.
+
+use miniz_oxide::inflate::{self, TINFLStatus};
+
+use crate::{DecodeError, Filter};
+
+/// Stops once the output reaches `limit` bytes, as a few kilobytes of
+/// compressed data can expand to gigabytes.
+pub(crate) fn decode(data: &[u8], limit: usize) -> Result<Vec<u8>, DecodeError> {
+    inflate::decompress_to_vec_zlib_with_limit(data, limit).map_err(|err| match err.status {
+        TINFLStatus::HasMoreOutput => DecodeError::TooLarge {
+            filter: Filter::FlateDecode,
+            limit,
+        },
+        _ => DecodeError::Filter {
+            filter: Filter::FlateDecode,
+            reason: "invalid zlib data",
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(b"BT /F1 12 Tf (Hello) Tj ET", 6);
+        assert_eq!(decode(&compressed, usize::MAX).unwrap(), b"BT /F1 12 Tf (Hello) Tj ET");
+    }
+
+    #[test]
+    fn invalid_data() {
+        let err = DecodeError::Filter {
+            filter: Filter::FlateDecode,
+            reason: "invalid zlib data",
+        };
+        assert_eq!(decode(b"not zlib", usize::MAX), Err(err));
+    }
+
+    #[test]
+    fn output_is_limited() {
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&[0; 10_000], 6);
+        assert_eq!(decode(&compressed, 10_000).unwrap().len(), 10_000);
+        let err = DecodeError::TooLarge {
+            filter: Filter::FlateDecode,
+            limit: 9_999,
+        };
+        assert_eq!(decode(&compressed, 9_999), Err(err));
+    }
+}
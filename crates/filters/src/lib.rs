@@ -0,0 +1,410 @@
+//! Decoding of stream data.
+//!
+//! The data of a stream is encoded by the filters listed in the `/Filter`
+//! entry of its dictionary, each of them optionally configured by the
+//! corresponding `/DecodeParms` entry. To get the original bytes, the filters
+//! are applied in order.
+//!
+//! See ISO `32000-2:2020`, Section 7.4 Filters.
+
+mod ascii85;
+mod ascii_hex;
+mod flate;
+mod lzw;
+mod predictor;
+mod run_length;
+
+use std::fmt;
+
+use syntax::ast;
+
+/// The largest decoded stream, in bytes. Filters which expand their input
+/// stop there instead of exhausting memory, and so does every step of a chain
+/// of filters.
+pub const MAX_DECODED_LEN: usize = 256 << 20;
+
+/// Resolves an indirect reference to the value of the referenced object.
+///
+/// `/Filter` and `/DecodeParms` (and the values inside of them) may be stored
+/// in separate objects, so looking them up requires knowing the whole file.
+pub type Resolver<'a> = &'a dyn Fn(&ast::IndirectReferenceExpr) -> Option<ast::Expr>;
+
+/// A standard filter that can be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Filter {
+    AsciiHexDecode,
+    Ascii85Decode,
+    LzwDecode,
+    FlateDecode,
+    RunLengthDecode,
+}
+
+impl Filter {
+    /// Looks up a filter by its name, including the leading solidus.
+    ///
+    /// The abbreviations allowed in inline images are accepted as well.
+    pub fn from_name(name: &[u8]) -> Option<Filter> {
+        let filter = match name {
+            b"/ASCIIHexDecode" | b"/AHx" => Filter::AsciiHexDecode,
+            b"/ASCII85Decode" | b"/A85" => Filter::Ascii85Decode,
+            b"/LZWDecode" | b"/LZW" => Filter::LzwDecode,
+            b"/FlateDecode" | b"/Fl" => Filter::FlateDecode,
+            b"/RunLengthDecode" | b"/RL" => Filter::RunLengthDecode,
+            _ => return None,
+        };
+        Some(filter)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Filter::AsciiHexDecode => "ASCIIHexDecode",
+            Filter::Ascii85Decode => "ASCII85Decode",
+            Filter::LzwDecode => "LZWDecode",
+            Filter::FlateDecode => "FlateDecode",
+            Filter::RunLengthDecode => "RunLengthDecode",
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The parameters of the `LZWDecode` and `FlateDecode` filters.
+///
+/// See ISO `32000-2:2020`, Table 8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeParms {
+    pub predictor: i64,
+    pub colors: i64,
+    pub bits_per_component: i64,
+    pub columns: i64,
+    pub early_change: i64,
+}
+
+impl Default for DecodeParms {
+    fn default() -> DecodeParms {
+        DecodeParms {
+            predictor: 1,
+            colors: 1,
+            bits_per_component: 8,
+            columns: 1,
+            early_change: 1,
+        }
+    }
+}
+
+/// The reason why stream data couldn't be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The stream has no data.
+    MissingData,
+    /// `/Filter` or `/DecodeParms` is not a name, a dictionary or an array of
+    /// those, or refers to an object that can't be resolved.
+    MalformedEntry(&'static str),
+    /// The filter is not one of the supported standard filters.
+    UnsupportedFilter(String),
+    /// A filter failed to decode its input.
+    Filter { filter: Filter, reason: &'static str },
+    /// A filter would decode to more than `limit` bytes, see
+    /// [`MAX_DECODED_LEN`].
+    TooLarge { filter: Filter, limit: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::MissingData => write!(f, "stream has no data"),
+            DecodeError::MalformedEntry(key) => write!(f, "malformed {key} entry"),
+            DecodeError::UnsupportedFilter(name) => write!(f, "unsupported filter {name}"),
+            DecodeError::Filter { filter, reason } => write!(f, "{filter}: {reason}"),
+            DecodeError::TooLarge { filter, limit } => write!(f, "{filter}: decoded data is larger than {limit} bytes"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Decodes the data of `stream` by applying all of its filters.
+///
+/// The data isn't decrypted, so this only works for streams of unencrypted
+/// files; `Document::decode_stream` decrypts the data first.
+pub fn decode_stream(stream: &ast::StreamExpr, resolve: Resolver<'_>) -> Result<Vec<u8>, DecodeError> {
+    let _p = tracing::info_span!("decode_stream").entered();
+    let data = stream.raw_data().ok_or(DecodeError::MissingData)?;
+    let filters = match stream.dictionary_expr() {
+        Some(dictionary) => stream_filters(&dictionary, resolve)?,
+        None => Vec::new(),
+    };
+    decode(&data, &filters)
+}
+
+/// Reads the filters and their parameters from a stream dictionary, in the
+/// order they have to be applied.
+pub fn stream_filters(dictionary: &ast::DictionaryExpr, resolve: Resolver<'_>) -> Result<Vec<(Filter, DecodeParms)>, DecodeError> {
+    let filters = match dictionary.get(b"/Filter").map(|it| resolved(it, resolve)) {
+        None => return Ok(Vec::new()),
+        Some(Some(it)) => one_or_many(it, resolve).ok_or(DecodeError::MalformedEntry("/Filter"))?,
+        Some(None) => return Err(DecodeError::MalformedEntry("/Filter")),
+    };
+    let parms = match dictionary.get(b"/DecodeParms").map(|it| resolved(it, resolve)) {
+        None => Vec::new(),
+        Some(Some(it)) => one_or_many(it, resolve).ok_or(DecodeError::MalformedEntry("/DecodeParms"))?,
+        Some(None) => return Err(DecodeError::MalformedEntry("/DecodeParms")),
+    };
+
+    filters
+        .iter()
+        .enumerate()
+        .map(|(i, filter)| {
+            let name = match filter {
+                ast::Expr::Literal(it) => match it.kind() {
+                    ast::LiteralKind::Name(name) => [b"/".as_slice(), &name.value().map_err(|_| DecodeError::MalformedEntry("/Filter"))?].concat(),
+                    _ => return Err(DecodeError::MalformedEntry("/Filter")),
+                },
+                _ => return Err(DecodeError::MalformedEntry("/Filter")),
+            };
+            let filter = Filter::from_name(&name).ok_or_else(|| DecodeError::UnsupportedFilter(String::from_utf8_lossy(&name).into_owned()))?;
+            let parms = match parms.get(i) {
+                Some(ast::Expr::DictionaryExpr(it)) => decode_parms(it, resolve)?,
+                // `null` stands for the default parameters.
+                Some(ast::Expr::Literal(it)) if it.token().kind() == syntax::T![null] => DecodeParms::default(),
+                Some(_) => return Err(DecodeError::MalformedEntry("/DecodeParms")),
+                None => DecodeParms::default(),
+            };
+            Ok((filter, parms))
+        })
+        .collect()
+}
+
+/// Applies `filters` to `data` in order.
+///
+/// Fails with [`DecodeError::TooLarge`] if any of the filters decodes to more
+/// than [`MAX_DECODED_LEN`] bytes.
+pub fn decode(data: &[u8], filters: &[(Filter, DecodeParms)]) -> Result<Vec<u8>, DecodeError> {
+    decode_with_limit(data, filters, MAX_DECODED_LEN)
+}
+
+fn decode_with_limit(data: &[u8], filters: &[(Filter, DecodeParms)], limit: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut data = data.to_vec();
+    for &(filter, parms) in filters {
+        data = decode_one(&data, filter, &parms, limit)?;
+        // The other filters can't expand their input much, but a chain of
+        // them could.
+        if data.len() > limit {
+            return Err(DecodeError::TooLarge { filter, limit });
+        }
+    }
+    Ok(data)
+}
+
+fn decode_one(data: &[u8], filter: Filter, parms: &DecodeParms, limit: usize) -> Result<Vec<u8>, DecodeError> {
+    let failed = |reason| DecodeError::Filter { filter, reason };
+    match filter {
+        Filter::AsciiHexDecode => ascii_hex::decode(data).map_err(failed),
+        Filter::Ascii85Decode => ascii85::decode(data).map_err(failed),
+        Filter::LzwDecode => predictor::apply(lzw::decode(data, parms.early_change != 0, limit)?, parms).map_err(failed),
+        Filter::FlateDecode => predictor::apply(flate::decode(data, limit)?, parms).map_err(failed),
+        Filter::RunLengthDecode => run_length::decode(data, limit),
+    }
+}
+
+fn decode_parms(dictionary: &ast::DictionaryExpr, resolve: Resolver<'_>) -> Result<DecodeParms, DecodeError> {
+    let mut parms = DecodeParms::default();
+    let entries = [
+        (&b"/Predictor"[..], &mut parms.predictor),
+        (b"/Colors", &mut parms.colors),
+        (b"/BitsPerComponent", &mut parms.bits_per_component),
+        (b"/Columns", &mut parms.columns),
+        (b"/EarlyChange", &mut parms.early_change),
+    ];
+    for (key, value) in entries {
+        if let Some(it) = dictionary.get(key) {
+            *value = resolved(it, resolve)
+                .and_then(|it| it.as_int())
+                .ok_or(DecodeError::MalformedEntry("/DecodeParms"))?;
+        }
+    }
+    Ok(parms)
+}
+
+/// The white-space characters of PDF, which most filters ignore.
+pub(crate) fn is_whitespace(b: u8) -> bool {
+    matches!(b, b'\0' | b'\t' | b'\n' | b'\x0C' | b'\r' | b' ')
+}
+
+/// Follows indirect references until a direct value is found.
+fn resolved(mut expr: ast::Expr, resolve: Resolver<'_>) -> Option<ast::Expr> {
+    // Guard against reference cycles.
+    for _ in 0..32 {
+        match expr {
+            ast::Expr::IndirectReferenceExpr(it) => expr = resolve(&it)?,
+            _ => return Some(expr),
+        }
+    }
+    None
+}
+
+/// Filters and their parameters may be given as a single value or as an
+/// array of values.
+fn one_or_many(expr: ast::Expr, resolve: Resolver<'_>) -> Option<Vec<ast::Expr>> {
+    match expr {
+        ast::Expr::ArrayExpr(array) => array.exprs().map(|it| resolved(it, resolve)).collect(),
+        it => Some(vec![it]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syntax::{AstNode, Edition, PdfDocument};
+
+    use super::*;
+
+    fn stream(text: &[u8]) -> (PdfDocument, ast::StreamExpr) {
+        let parse = PdfDocument::parse(text, Edition::CURRENT);
+        let stream = parse.syntax_node().descendants().find_map(ast::StreamExpr::cast).unwrap();
+        (parse.tree(), stream)
+    }
+
+    fn no_references(_: &ast::IndirectReferenceExpr) -> Option<ast::Expr> {
+        None
+    }
+
+    /// Resolves references to the objects defined in `document`.
+    fn check_decode(text: &[u8], expected: Result<&[u8], DecodeError>) {
+        let (document, stream) = stream(text);
+        let resolve = |reference: &ast::IndirectReferenceExpr| {
+            let number = reference.object_number()?.token().text().to_vec();
+            document.syntax().descendants().filter_map(ast::IndirectObjectExpr::cast).find_map(|object| {
+                let id = object.indirect_object_id()?;
+                (id.object_number()?.token().text() == number).then(|| object.syntax().children().find_map(ast::Expr::cast))?
+            })
+        };
+        assert_eq!(decode_stream(&stream, &resolve).as_deref(), expected.as_deref());
+    }
+
+    #[test]
+    fn unfiltered_stream() {
+        check_decode(b"1 0 obj\n<< /Length 5 >>\nstream\nHello\nendstream\nendobj\n", Ok(b"Hello"));
+    }
+
+    #[test]
+    fn filter_chain() {
+        // `Hello` run-length encoded, then hex encoded.
+        check_decode(
+            b"1 0 obj\n<< /Filter [/AHx /RunLengthDecode] >>\nstream\n0448656C6C6F80>\nendstream\nendobj\n",
+            Ok(b"Hello"),
+        );
+    }
+
+    #[test]
+    fn indirect_filter_and_parms() {
+        check_decode(
+            b"1 0 obj\n<< /Filter 2 0 R /DecodeParms [3 0 R] >>\nstream\n48 65 6c 6C 6f>\nendstream\nendobj\n2 0 obj\n[/ASCIIHexDecode]\nendobj\n3 0 obj\nnull\nendobj\n",
+            Ok(b"Hello"),
+        );
+    }
+
+    #[test]
+    fn flate_with_png_predictor() {
+        let rows = [2, 1, 1, 1, 2, 0, 1, 2];
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&rows, 6);
+        let mut text = format!(
+            "1 0 obj\n<< /Length {} /Filter /FlateDecode /DecodeParms << /Predictor 12 /Columns 3 >> >>\nstream\n",
+            compressed.len()
+        )
+        .into_bytes();
+        text.extend_from_slice(&compressed);
+        text.extend_from_slice(b"\nendstream\nendobj\n");
+
+        check_decode(&text, Ok(&[1, 1, 1, 1, 2, 3]));
+    }
+
+    #[test]
+    fn errors_name_the_failing_filter() {
+        check_decode(
+            b"1 0 obj\n<< /Filter [/ASCII85Decode /FlateDecode] >>\nstream\n87cURD]i,\"Ebo80~>\nendstream\nendobj\n",
+            Err(DecodeError::Filter {
+                filter: Filter::FlateDecode,
+                reason: "invalid zlib data",
+            }),
+        );
+        check_decode(
+            b"1 0 obj\n<< /Filter /ASCIIHexDecode >>\nstream\n4G>\nendstream\nendobj\n",
+            Err(DecodeError::Filter {
+                filter: Filter::AsciiHexDecode,
+                reason: "invalid hex digit",
+            }),
+        );
+    }
+
+    #[test]
+    fn unsupported_and_malformed_filters() {
+        check_decode(
+            b"1 0 obj\n<< /Filter /DCTDecode >>\nstream\nxx\nendstream\nendobj\n",
+            Err(DecodeError::UnsupportedFilter("/DCTDecode".to_owned())),
+        );
+        check_decode(
+            b"1 0 obj\n<< /Filter 42 >>\nstream\nxx\nendstream\nendobj\n",
+            Err(DecodeError::MalformedEntry("/Filter")),
+        );
+        check_decode(
+            b"1 0 obj\n<< /Filter 9 0 R >>\nstream\nxx\nendstream\nendobj\n",
+            Err(DecodeError::MalformedEntry("/Filter")),
+        );
+    }
+
+    #[test]
+    fn reads_decode_parms() {
+        let (_, stream) = stream(
+            b"1 0 obj\n<< /Filter [/LZWDecode /FlateDecode] /DecodeParms [<< /EarlyChange 0 >> << /Predictor 2 /Colors 3 /BitsPerComponent 16 /Columns 4 >>] >>\nstream\nx\nendstream\nendobj\n",
+        );
+        let filters = stream_filters(&stream.dictionary_expr().unwrap(), &no_references).unwrap();
+        assert_eq!(
+            filters,
+            vec![
+                (
+                    Filter::LzwDecode,
+                    DecodeParms {
+                        early_change: 0,
+                        ..DecodeParms::default()
+                    }
+                ),
+                (
+                    Filter::FlateDecode,
+                    DecodeParms {
+                        predictor: 2,
+                        colors: 3,
+                        bits_per_component: 16,
+                        columns: 4,
+                        early_change: 1
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_filter_of_a_chain_is_limited() {
+        // 20 bytes of run-length data inflate to 1280 bytes.
+        let flate = (Filter::FlateDecode, DecodeParms::default());
+        let run_length = (Filter::RunLengthDecode, DecodeParms::default());
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&[129, b'x'].repeat(10), 6);
+        assert_eq!(decode_with_limit(&compressed, &[flate, run_length], 1280).unwrap().len(), 1280);
+        let err = DecodeError::TooLarge {
+            filter: Filter::RunLengthDecode,
+            limit: 1000,
+        };
+        assert_eq!(decode_with_limit(&compressed, &[flate, run_length], 1000), Err(err));
+
+        // Filters which don't expand their input are checked as well.
+        let ascii_hex = (Filter::AsciiHexDecode, DecodeParms::default());
+        let err = DecodeError::TooLarge {
+            filter: Filter::AsciiHexDecode,
+            limit: 10,
+        };
+        assert_eq!(decode_with_limit(&b"41".repeat(11), &[ascii_hex], 10), Err(err));
+    }
+}
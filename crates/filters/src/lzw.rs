@@ -0,0 +1,174 @@
+//! `LZWDecode`: variable-width (9 to 12 bits) Lempel-Ziv-Welch codes.
+
+use crate::{DecodeError, Filter};
+
+const CLEAR_TABLE: u16 = 256;
+const EOD: u16 = 257;
+const MAX_TABLE_LEN: usize = 4096;
+
+/// Decodes `data`. With `early_change`, code widths increase one code earlier
+/// than strictly necessary, as most encoders do (`/EarlyChange 1`).
+///
+/// Stops once the output would exceed `limit` bytes: every code of a few bits
+/// can stand for thousands of bytes.
+pub(crate) fn decode(data: &[u8], early_change: bool, limit: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut out = Vec::new();
+    let mut table: Vec<Vec<u8>> = Vec::with_capacity(MAX_TABLE_LEN);
+    reset(&mut table);
+    let mut width = 9;
+    let mut previous: Option<Vec<u8>> = None;
+
+    let mut bits = Bits { data, pos: 0 };
+    while let Some(code) = bits.read(width) {
+        match code {
+            CLEAR_TABLE => {
+                reset(&mut table);
+                width = 9;
+                previous = None;
+                continue;
+            }
+            EOD => break,
+            _ => {}
+        }
+
+        let entry = match (table.get(usize::from(code)), &previous) {
+            (Some(entry), _) => entry.clone(),
+            // The code that is about to be added: the previous entry followed
+            // by its own first byte.
+            (None, Some(previous)) if usize::from(code) == table.len() => {
+                let mut entry = previous.clone();
+                entry.push(previous[0]);
+                entry
+            }
+            _ => {
+                return Err(DecodeError::Filter {
+                    filter: Filter::LzwDecode,
+                    reason: "invalid code",
+                });
+            }
+        };
+        if out.len() + entry.len() > limit {
+            return Err(DecodeError::TooLarge {
+                filter: Filter::LzwDecode,
+                limit,
+            });
+        }
+        out.extend_from_slice(&entry);
+
+        if let Some(mut previous) = previous.take()
+            && table.len() < MAX_TABLE_LEN
+        {
+            previous.push(entry[0]);
+            table.push(previous);
+        }
+        previous = Some(entry);
+
+        let next = table.len() + usize::from(early_change);
+        width = match next {
+            ..512 => 9,
+            512..1024 => 10,
+            1024..2048 => 11,
+            _ => 12,
+        };
+    }
+    Ok(out)
+}
+
+fn reset(table: &mut Vec<Vec<u8>>) {
+    table.clear();
+    table.extend((0..=255).map(|b| vec![b]));
+    // Placeholders for the clear-table and end-of-data codes.
+    table.extend([Vec::new(), Vec::new()]);
+}
+
+/// Reads most significant bit first.
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Bits<'_> {
+    fn read(&mut self, width: usize) -> Option<u16> {
+        if self.pos + width > self.data.len() * 8 {
+            return None;
+        }
+        let mut code = 0u16;
+        for _ in 0..width {
+            let bit = self.data[self.pos / 8] >> (7 - self.pos % 8) & 1;
+            code = code << 1 | u16::from(bit);
+            self.pos += 1;
+        }
+        Some(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Packs codes of the given widths, most significant bit first.
+    fn pack(codes: &[(u16, usize)]) -> Vec<u8> {
+        let mut bits = Vec::new();
+        for &(code, width) in codes {
+            bits.extend((0..width).rev().map(|i| (code >> i) & 1 == 1));
+        }
+        bits.chunks(8)
+            .map(|chunk| chunk.iter().enumerate().fold(0u8, |acc, (i, &bit)| acc | u8::from(bit) << (7 - i)))
+            .collect()
+    }
+
+    #[test]
+    fn decodes_specification_example() {
+        // ISO 32000-2:2020, Section 7.4.4.2: "-----A---B" is encoded as
+        // 256 45 258 258 65 259 66 257.
+        let data = [0x80, 0x0B, 0x60, 0x50, 0x22, 0x0C, 0x0C, 0x85, 0x01];
+        assert_eq!(decode(&data, true, usize::MAX).unwrap(), b"-----A---B");
+    }
+
+    #[test]
+    fn output_is_limited() {
+        let data = [0x80, 0x0B, 0x60, 0x50, 0x22, 0x0C, 0x0C, 0x85, 0x01];
+        assert_eq!(decode(&data, true, 10).unwrap(), b"-----A---B");
+        let err = DecodeError::TooLarge {
+            filter: Filter::LzwDecode,
+            limit: 9,
+        };
+        assert_eq!(decode(&data, true, 9), Err(err));
+    }
+
+    #[test]
+    fn grows_code_width() {
+        // Every code but the first adds a table entry; after 254 codes the
+        // next entry is 511, so with early change codes are 10 bits wide.
+        let mut codes: Vec<(u16, usize)> = (0..254).map(|b| (b, 9)).collect();
+        codes.push((b'x'.into(), 10));
+        codes.push((EOD, 10));
+        let mut expected: Vec<u8> = (0..254).map(|b| b as u8).collect();
+        expected.push(b'x');
+        assert_eq!(decode(&pack(&codes), true, usize::MAX).unwrap(), expected);
+
+        // Without early change, the width only grows once code 511 is taken.
+        let mut codes: Vec<(u16, usize)> = (0..255).map(|b| (b, 9)).collect();
+        codes.push((EOD, 10));
+        let expected: Vec<u8> = (0..255).map(|b| b as u8).collect();
+        assert_eq!(decode(&pack(&codes), false, usize::MAX).unwrap(), expected);
+    }
+
+    #[test]
+    fn clear_table_resets_width() {
+        let mut codes: Vec<(u16, usize)> = (0..254).map(|b| (b, 9)).collect();
+        codes.push((CLEAR_TABLE, 10));
+        codes.push((b'a'.into(), 9));
+        codes.push((EOD, 9));
+        assert_eq!(decode(&pack(&codes), true, usize::MAX).unwrap().last(), Some(&b'a'));
+    }
+
+    #[test]
+    fn rejects_unknown_codes() {
+        let err = DecodeError::Filter {
+            filter: Filter::LzwDecode,
+            reason: "invalid code",
+        };
+        assert_eq!(decode(&pack(&[(300, 9)]), true, usize::MAX), Err(err));
+    }
+}
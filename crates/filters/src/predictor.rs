@@ -0,0 +1,204 @@
+//! Predictors, which `LZWDecode` and `FlateDecode` optionally apply to their
+//! output.
+//!
+//! See ISO `32000-2:2020`, Section 7.4.4.4 LZW and Flate predictor functions.
+
+use crate::DecodeParms;
+
+/// Undoes the prediction selected by `/Predictor`.
+pub(crate) fn apply(data: Vec<u8>, parms: &DecodeParms) -> Result<Vec<u8>, &'static str> {
+    match parms.predictor {
+        1 => Ok(data),
+        2 => {
+            let layout = Layout::new(parms, data.len())?;
+            Ok(tiff(data, &layout))
+        }
+        10..=15 => png(&data, &Layout::new(parms, data.len())?),
+        _ => Err("unsupported predictor"),
+    }
+}
+
+struct Layout {
+    colors: usize,
+    bits_per_component: usize,
+    /// Bytes per complete pixel, at least 1.
+    pixel_len: usize,
+    /// Bytes per row, without the PNG filter type byte.
+    row_len: usize,
+}
+
+impl Layout {
+    /// Rows longer than `data_len` are rejected: the data would be shorter
+    /// than a single row, and their length comes straight from the file.
+    fn new(parms: &DecodeParms, data_len: usize) -> Result<Layout, &'static str> {
+        let colors = usize::try_from(parms.colors).ok().filter(|it| (1..=32).contains(it)).ok_or("invalid /Colors")?;
+        let bits_per_component = usize::try_from(parms.bits_per_component)
+            .ok()
+            .filter(|it| [1, 2, 4, 8, 16].contains(it))
+            .ok_or("invalid /BitsPerComponent")?;
+        let columns = usize::try_from(parms.columns).ok().filter(|&it| it >= 1).ok_or("invalid /Columns")?;
+        let row_bits = colors
+            .checked_mul(bits_per_component)
+            .and_then(|it| it.checked_mul(columns))
+            .ok_or("invalid /Columns")?;
+        let row_len = row_bits.div_ceil(8);
+        if row_len > data_len {
+            return Err("rows are longer than the data");
+        }
+        Ok(Layout {
+            colors,
+            bits_per_component,
+            pixel_len: colors.checked_mul(bits_per_component).ok_or("invalid /Colors")?.div_ceil(8),
+            row_len,
+        })
+    }
+}
+
+/// TIFF predictor 2: each component is stored as the difference to the same
+/// component of the pixel to its left.
+fn tiff(mut data: Vec<u8>, layout: &Layout) -> Vec<u8> {
+    let bpc = layout.bits_per_component;
+    for row in data.chunks_mut(layout.row_len) {
+        match bpc {
+            8 => {
+                for i in layout.colors..row.len() {
+                    row[i] = row[i].wrapping_add(row[i - layout.colors]);
+                }
+            }
+            16 => {
+                let stride = 2 * layout.colors;
+                for i in (stride..row.len().saturating_sub(1)).step_by(2) {
+                    let left = u16::from_be_bytes([row[i - stride], row[i - stride + 1]]);
+                    let value = u16::from_be_bytes([row[i], row[i + 1]]).wrapping_add(left);
+                    row[i..i + 2].copy_from_slice(&value.to_be_bytes());
+                }
+            }
+            // Components of 1, 2 or 4 bits, packed most significant bit first.
+            _ => {
+                let mask = (1u16 << bpc) - 1;
+                let components = row.len() * 8 / bpc;
+                let get = |row: &[u8], i: usize| u16::from(row[i * bpc / 8]) >> (8 - bpc - i * bpc % 8) & mask;
+                for i in layout.colors..components {
+                    let value = (get(row, i) + get(row, i - layout.colors)) & mask;
+                    let shift = 8 - bpc - i * bpc % 8;
+                    let byte = &mut row[i * bpc / 8];
+                    *byte = (*byte & !((mask as u8) << shift)) | (value as u8) << shift;
+                }
+            }
+        }
+    }
+    data
+}
+
+/// PNG predictors: every row starts with a byte selecting the filter type
+/// used for that row.
+fn png(data: &[u8], layout: &Layout) -> Result<Vec<u8>, &'static str> {
+    let bpp = layout.pixel_len;
+    let mut out = Vec::with_capacity(data.len());
+    let mut previous = vec![0u8; layout.row_len];
+    for chunk in data.chunks(layout.row_len + 1) {
+        let (&filter_type, encoded) = chunk.split_first().ok_or("missing filter type")?;
+        let mut row = encoded.to_vec();
+        for i in 0..row.len() {
+            let left = if i >= bpp { row[i - bpp] } else { 0 };
+            let up = previous[i];
+            let up_left = if i >= bpp { previous[i - bpp] } else { 0 };
+            let prediction = match filter_type {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((u16::from(left) + u16::from(up)) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                _ => return Err("invalid PNG filter type"),
+            };
+            row[i] = row[i].wrapping_add(prediction);
+        }
+        out.extend_from_slice(&row);
+        previous[..row.len()].copy_from_slice(&row);
+    }
+    Ok(out)
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let p = i16::from(left) + i16::from(up) - i16::from(up_left);
+    let (pa, pb, pc) = ((p - i16::from(left)).abs(), (p - i16::from(up)).abs(), (p - i16::from(up_left)).abs());
+    if pa <= pb && pa <= pc {
+        left
+    } else if pb <= pc {
+        up
+    } else {
+        up_left
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parms(predictor: i64, colors: i64, bits_per_component: i64, columns: i64) -> DecodeParms {
+        DecodeParms {
+            predictor,
+            colors,
+            bits_per_component,
+            columns,
+            ..DecodeParms::default()
+        }
+    }
+
+    #[test]
+    fn no_prediction() {
+        assert_eq!(apply(vec![1, 2, 3], &parms(1, 1, 8, 1)).unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn png_filter_types() {
+        #[rustfmt::skip]
+        let data = vec![
+            0, 10, 20, 30, 40,
+            1, 10, 20, 20, 20,
+            2, 1, 1, 1, 1,
+            3, 5, 10, 10, 10,
+            4, 1, 1, 1, 1,
+        ];
+        #[rustfmt::skip]
+        let expected = [
+            10, 20, 30, 40,
+            10, 20, 30, 40,
+            11, 21, 31, 41,
+            10, 20, 30, 40,
+            11, 21, 31, 41,
+        ];
+        // Two pixels of two colors per row.
+        assert_eq!(apply(data, &parms(15, 2, 8, 2)).unwrap(), expected);
+    }
+
+    #[test]
+    fn png_invalid_filter_type() {
+        assert_eq!(apply(vec![5, 1, 2], &parms(10, 1, 8, 2)), Err("invalid PNG filter type"));
+    }
+
+    #[test]
+    fn tiff_components() {
+        assert_eq!(apply(vec![1, 2, 1, 2, 1, 2], &parms(2, 2, 8, 3)).unwrap(), [1, 2, 2, 4, 3, 6]);
+        assert_eq!(apply(vec![0, 1, 0, 1, 0xff, 0xff], &parms(2, 1, 16, 3)).unwrap(), [0, 1, 0, 2, 0, 1]);
+        // 4-bit components: 1, 1, 1, 1 become 1, 2, 3, 4.
+        assert_eq!(apply(vec![0x11, 0x11], &parms(2, 1, 4, 4)).unwrap(), [0x12, 0x34]);
+        // 1-bit components flip on every set bit.
+        assert_eq!(apply(vec![0b1000_1000], &parms(2, 1, 1, 8)).unwrap(), [0b1111_0000]);
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert_eq!(apply(vec![], &parms(2, 0, 8, 1)), Err("invalid /Colors"));
+        assert_eq!(apply(vec![], &parms(12, 1, 3, 1)), Err("invalid /BitsPerComponent"));
+        assert_eq!(apply(vec![], &parms(12, 1, 8, 0)), Err("invalid /Columns"));
+        assert_eq!(apply(vec![], &parms(7, 1, 8, 1)), Err("unsupported predictor"));
+    }
+
+    #[test]
+    fn rows_longer_than_the_data() {
+        assert_eq!(apply(vec![0, 1, 2], &parms(12, 1, 8, 1_000_000_000_000)), Err("rows are longer than the data"));
+        assert_eq!(apply(vec![1, 2], &parms(2, 3, 8, 1)), Err("rows are longer than the data"));
+        assert_eq!(apply(vec![1, 2], &parms(12, 1, 16, i64::MAX)), Err("invalid /Columns"));
+    }
+}
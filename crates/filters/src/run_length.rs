@@ -0,0 +1,71 @@
+//! `RunLengthDecode`: runs of literal bytes and repeated bytes, each prefixed
+//! by a length byte, terminated by 128.
+
+use crate::{DecodeError, Filter};
+
+/// Stops once the output would exceed `limit` bytes, as every two bytes of
+/// input can stand for 128 bytes of output.
+pub(crate) fn decode(data: &[u8], limit: usize) -> Result<Vec<u8>, DecodeError> {
+    let failed = |reason| DecodeError::Filter {
+        filter: Filter::RunLengthDecode,
+        reason,
+    };
+    let mut out = Vec::new();
+    let mut data = data;
+    while let Some((&length, rest)) = data.split_first() {
+        let run = match length {
+            128 => break,
+            0..=127 => usize::from(length) + 1,
+            _ => 257 - usize::from(length),
+        };
+        if out.len() + run > limit {
+            return Err(DecodeError::TooLarge {
+                filter: Filter::RunLengthDecode,
+                limit,
+            });
+        }
+        if length < 128 {
+            out.extend_from_slice(rest.get(..run).ok_or_else(|| failed("truncated literal run"))?);
+            data = &rest[run..];
+        } else {
+            let &b = rest.first().ok_or_else(|| failed("truncated repeated run"))?;
+            out.resize(out.len() + run, b);
+            data = &rest[1..];
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_runs() {
+        assert_eq!(decode(&[2, b'a', b'b', b'c', 254, b'x', 128, 0, b'z'], usize::MAX).unwrap(), b"abcxxx");
+        assert_eq!(decode(&[0, b'a'], usize::MAX).unwrap(), b"a");
+    }
+
+    #[test]
+    fn rejects_truncated_runs() {
+        let failed = |reason| {
+            Err(DecodeError::Filter {
+                filter: Filter::RunLengthDecode,
+                reason,
+            })
+        };
+        assert_eq!(decode(&[3, b'a'], usize::MAX), failed("truncated literal run"));
+        assert_eq!(decode(&[200], usize::MAX), failed("truncated repeated run"));
+    }
+
+    #[test]
+    fn output_is_limited() {
+        let data = [1, b'a', b'b', 254, b'x'];
+        assert_eq!(decode(&data, 5).unwrap(), b"abxxx");
+        let err = DecodeError::TooLarge {
+            filter: Filter::RunLengthDecode,
+            limit: 4,
+        };
+        assert_eq!(decode(&data, 4), Err(err));
+    }
+}
@@ -0,0 +1,163 @@
+//! The outline of a file: its indirect objects, cross-reference sections and
+//! trailers, in file order.
+
+use syntax::{AstNode, PdfDocument, SyntaxToken, TextRange, ast};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructureNode {
+    pub label: String,
+    pub kind: StructureNodeKind,
+    /// The range of the whole node.
+    pub node_range: TextRange,
+    /// The range to select when navigating to the node, e.g. `1 0 obj`.
+    pub navigation_range: TextRange,
+    pub detail: Option<String>,
+    pub children: Vec<StructureNode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructureNodeKind {
+    Object,
+    XRefSection,
+    XRefSubsection,
+    Trailer,
+}
+
+pub fn file_structure(file: &PdfDocument) -> Vec<StructureNode> {
+    let mut res = Vec::new();
+    for revision in file.revisions() {
+        let objects = revision.body().into_iter().flat_map(|it| it.indirect_object_exprs());
+        res.extend(objects.filter_map(|it| object(&it)));
+        let sections = revision.x_ref_table().into_iter().flat_map(|it| it.x_ref_sections());
+        res.extend(sections.filter_map(|it| xref_section(&it)));
+        res.extend(revision.trailer().and_then(|it| trailer(&it)));
+    }
+    res
+}
+
+fn object(object: &ast::IndirectObjectExpr) -> Option<StructureNode> {
+    let id = object.indirect_object_id()?;
+    let stream = object.stream_expr();
+    let dictionary = match (&stream, object.expr()) {
+        (Some(stream), _) => stream.dictionary_expr(),
+        (None, Some(ast::Expr::DictionaryExpr(it))) => Some(it),
+        (None, _) => None,
+    };
+    let kind = dictionary.and_then(|it| it.get(b"/Type")).map(|it| it.syntax().text().to_string());
+    let detail = match (kind, stream) {
+        (Some(kind), Some(_)) => Some(format!("{kind} stream")),
+        (None, Some(_)) => Some("stream".to_owned()),
+        (kind, None) => kind,
+    };
+    Some(StructureNode {
+        label: id.syntax().text().to_string(),
+        kind: StructureNodeKind::Object,
+        node_range: object.syntax().text_range(),
+        navigation_range: id.syntax().text_range(),
+        detail,
+        children: Vec::new(),
+    })
+}
+
+fn xref_section(section: &ast::XRefSection) -> Option<StructureNode> {
+    let keyword = section.xref_token()?;
+    let children = section
+        .x_ref_subsections()
+        .filter_map(|subsection| {
+            let (first, count) = (subsection.first_object()?, subsection.count()?);
+            Some(StructureNode {
+                label: format!("{} {}", first.syntax().text(), count.syntax().text()),
+                kind: StructureNodeKind::XRefSubsection,
+                node_range: subsection.syntax().text_range(),
+                navigation_range: first.syntax().text_range().cover(count.syntax().text_range()),
+                detail: None,
+                children: Vec::new(),
+            })
+        })
+        .collect();
+    Some(StructureNode {
+        label: "xref".to_owned(),
+        kind: StructureNodeKind::XRefSection,
+        node_range: section.syntax().text_range(),
+        navigation_range: keyword.text_range(),
+        detail: None,
+        children,
+    })
+}
+
+/// The trailer, or just `startxref` in files with cross-reference streams.
+fn trailer(trailer: &ast::Trailer) -> Option<StructureNode> {
+    let keyword: SyntaxToken = trailer.trailer_token().or_else(|| trailer.startxref_token())?;
+    Some(StructureNode {
+        label: String::from_utf8_lossy(keyword.text()).into_owned(),
+        kind: StructureNodeKind::Trailer,
+        node_range: trailer.syntax().text_range(),
+        navigation_range: keyword.text_range(),
+        detail: trailer.literal().map(|it| format!("startxref {}", it.syntax().text())),
+        children: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{Expect, expect};
+    use syntax::Edition;
+
+    use super::*;
+
+    fn check(text: &str, expect: Expect) {
+        let parse = PdfDocument::parse(text.as_bytes(), Edition::CURRENT);
+        let mut buf = String::new();
+        let mut stack: Vec<_> = file_structure(&parse.tree()).into_iter().rev().map(|it| (0, it)).collect();
+        while let Some((depth, node)) = stack.pop() {
+            let indent = "  ".repeat(depth);
+            let StructureNode {
+                label,
+                kind,
+                node_range,
+                navigation_range,
+                detail,
+                children,
+            } = node;
+            writeln!(buf, "{indent}{kind:?} {label:?} {node_range:?} {navigation_range:?} {detail:?}").unwrap();
+            stack.extend(children.into_iter().rev().map(|it| (depth + 1, it)));
+        }
+        expect.assert_eq(&buf);
+    }
+
+    #[test]
+    fn objects_xref_and_trailer() {
+        check(
+            "%PDF-1.7\n\
+             1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n\
+             2 0 obj << /Type /Pages /Kids [] /Count 0 >> endobj\n\
+             3 0 obj << /Length 2 >>\nstream\nhi\nendstream\nendobj\n\
+             4 0 obj [1 2] endobj\n\
+             xref\n0 1\n0000000000 65535 f \n3 2\n0000000114 00000 n \n0000000159 00000 n \n\
+             trailer << /Size 5 /Root 1 0 R >>\nstartxref\n186\n%%EOF\n",
+            expect![[r#"
+                Object "1 0 obj" 9..57 9..16 Some("/Catalog")
+                Object "2 0 obj" 58..109 58..65 Some("/Pages")
+                Object "3 0 obj" 110..160 110..117 Some("stream")
+                Object "4 0 obj" 161..181 161..168 None
+                XRefSection "xref" 182..254 182..186 None
+                  XRefSubsection "0 1" 187..210 187..190 None
+                  XRefSubsection "3 2" 211..254 211..214 None
+                Trailer "trailer" 255..302 255..262 Some("startxref 186")
+            "#]],
+        );
+    }
+
+    #[test]
+    fn xref_streams() {
+        check(
+            "1 0 obj << /Type /XRef /Size 2 /W [1 1 1] /Length 0 >>\nstream\n\nendstream\nendobj\nstartxref\n0\n%%EOF\n",
+            expect![[r#"
+                Object "1 0 obj" 0..79 0..7 Some("/XRef stream")
+                Trailer "startxref" 80..91 80..89 Some("startxref 0")
+            "#]],
+        );
+    }
+}
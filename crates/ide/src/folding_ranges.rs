@@ -0,0 +1,82 @@
+//! Folding ranges for dictionaries, arrays and streams which span several
+//! lines.
+
+use syntax::{AstNode, PdfDocument, SyntaxKind, SyntaxNode, TextRange, ast};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldKind {
+    Dictionary,
+    Array,
+    /// From `stream` to `endstream`.
+    Stream,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fold {
+    pub range: TextRange,
+    pub kind: FoldKind,
+}
+
+pub fn folding_ranges(file: &PdfDocument) -> Vec<Fold> {
+    file.syntax()
+        .descendants()
+        .filter_map(|node| {
+            let fold = match node.kind() {
+                SyntaxKind::DICTIONARY_EXPR => Fold {
+                    range: node.text_range(),
+                    kind: FoldKind::Dictionary,
+                },
+                SyntaxKind::ARRAY_EXPR => Fold {
+                    range: node.text_range(),
+                    kind: FoldKind::Array,
+                },
+                SyntaxKind::STREAM_EXPR => {
+                    let stream = ast::StreamExpr::cast(node.clone())?;
+                    let start = stream.stream_token()?.text_range().start();
+                    let end = stream.endstream_token().map_or(node.text_range().end(), |it| it.text_range().end());
+                    Fold {
+                        range: TextRange::new(start, end),
+                        kind: FoldKind::Stream,
+                    }
+                }
+                _ => return None,
+            };
+            contains_newline(&node, fold.range).then_some(fold)
+        })
+        .collect()
+}
+
+fn contains_newline(node: &SyntaxNode, range: TextRange) -> bool {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| range.contains_range(it.text_range()))
+        .any(|it| it.text().iter().any(|&b| b == b'\n' || b == b'\r'))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{Expect, expect};
+    use syntax::Edition;
+
+    use super::*;
+
+    fn check(text: &str, expect: Expect) {
+        let parse = PdfDocument::parse(text.as_bytes(), Edition::CURRENT);
+        let folds = folding_ranges(&parse.tree());
+        let actual: String = folds.iter().map(|it| format!("{:?} {:?}\n", it.kind, &text[it.range])).collect();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn multi_line_nodes_are_folded() {
+        check(
+            "1 0 obj\n<< /Type /Page\n   /MediaBox [0 0 612 792]\n   /Kids [\n     2 0 R\n   ]\n>>\nendobj\n\
+             2 0 obj << /Length 3 >>\nstream\nabc\nendstream\nendobj\n",
+            expect![[r#"
+                Dictionary "<< /Type /Page\n   /MediaBox [0 0 612 792]\n   /Kids [\n     2 0 R\n   ]\n>>"
+                Array "[\n     2 0 R\n   ]"
+                Stream "stream\nabc\nendstream"
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! Jumps from an indirect reference `N G R` to the object `N G obj`.
+
+use document::ObjectId;
+use syntax::{PdfDocument, TextSize};
+
+use crate::NavigationTarget;
+
+pub fn goto_definition(file: &PdfDocument, offset: TextSize) -> Option<NavigationTarget> {
+    let reference = crate::reference_at(file, offset)?;
+    let id = ObjectId::of_reference(&reference)?;
+    NavigationTarget::from_object(&crate::definition(file, id)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    use super::*;
+
+    /// Checks that the reference at `$0` goes to the object between the `$1`
+    /// markers, or nowhere if there are none.
+    fn check(fixture: &str) {
+        let (file, offset, expected) = fixture::parse(fixture);
+        let actual = goto_definition(&file, offset);
+        assert_eq!(actual.map(|it| it.full_range), expected.first().copied());
+    }
+
+    #[test]
+    fn goto_object_of_reference() {
+        check("$11 0 obj << /Pages 2 0 R >> endobj$1\n2 0 obj << /Parent 1 0$0 R >> endobj\n");
+        check("$11 0 obj << /Pages 2 0 R >> endobj$1\n2 0 obj << /Parent $01 0 R >> endobj\n");
+        check("$11 0 obj << /Pages 2 0 R >> endobj$1\n2 0 obj << /Parent 1 0 R$0 >> endobj\n");
+    }
+
+    #[test]
+    fn generation_must_match() {
+        check("1 0 obj << >> endobj\n$11 1 obj << >> endobj$1\n2 0 obj [1 1 R$0] endobj\n");
+        check("1 0 obj << >> endobj\n2 0 obj [1 1 R$0] endobj\n");
+    }
+
+    #[test]
+    fn last_definition_wins() {
+        check(
+            "1 0 obj << /Root 2 0 R >> endobj\ntrailer << /Root 1 0 R$0 >>\n\
+             $11 0 obj << /Root 3 0 R >> endobj$1\ntrailer << /Prev 0 >>\n",
+        );
+    }
+
+    #[test]
+    fn nothing_outside_of_references() {
+        check("1 0 obj << /Pages 2 0 R >> $0endobj\n");
+        check("1 0 obj << /Pa$0ges 1 0 R >> endobj\n");
+    }
+}
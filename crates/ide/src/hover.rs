@@ -0,0 +1,158 @@
+//! Describes the object an indirect reference refers to: its `/Type` and the
+//! first lines of its dictionary.
+
+use document::ObjectId;
+use syntax::{AstNode, PdfDocument, TextRange, TextSize, ast};
+
+/// Previews are cut after this many lines, and lines after this many
+/// characters.
+const PREVIEW_LINES: usize = 10;
+const PREVIEW_LINE_LENGTH: usize = 80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HoverResult {
+    /// The range of the reference.
+    pub range: TextRange,
+    /// The description, in Markdown.
+    pub markup: String,
+}
+
+pub fn hover(file: &PdfDocument, offset: TextSize) -> Option<HoverResult> {
+    let reference = crate::reference_at(file, offset)?;
+    let id = ObjectId::of_reference(&reference)?;
+    let range = reference.syntax().text_range();
+    let Some(object) = crate::definition(file, id) else {
+        let markup = format!("`{id} obj` is not defined in this file, and is treated as `null`.");
+        return Some(HoverResult { range, markup });
+    };
+
+    let stream = object.stream_expr();
+    let value = match &stream {
+        Some(stream) => stream.dictionary_expr().map(ast::Expr::DictionaryExpr),
+        None => object.expr(),
+    };
+    let kind = match &value {
+        Some(ast::Expr::DictionaryExpr(it)) => it.get(b"/Type").map(|it| it.syntax().text().to_string()),
+        _ => None,
+    };
+    let mut markup = format!("`{id} obj`");
+    match (kind, &stream) {
+        (Some(kind), Some(_)) => markup += &format!(" `{kind}` stream"),
+        (Some(kind), None) => markup += &format!(" `{kind}`"),
+        (None, Some(_)) => markup += " stream",
+        (None, None) => (),
+    }
+    if let Some(value) = value {
+        markup += &format!("\n\n```pdf\n{}\n```", preview(&value.syntax().text().to_string()));
+    }
+    Some(HoverResult { range, markup })
+}
+
+fn preview(text: &str) -> String {
+    let mut lines: Vec<String> = text
+        .lines()
+        .take(PREVIEW_LINES)
+        .map(|line| match line.char_indices().nth(PREVIEW_LINE_LENGTH) {
+            Some((end, _)) => format!("{}…", &line[..end]),
+            None => line.to_owned(),
+        })
+        .collect();
+    if text.lines().nth(PREVIEW_LINES).is_some() {
+        lines.push("…".to_owned());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{Expect, expect};
+
+    use crate::fixture;
+
+    use super::*;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (file, offset, _) = fixture::parse(fixture);
+        let HoverResult { range, markup } = hover(&file, offset).unwrap();
+        expect.assert_eq(&format!("{range:?}\n{markup}"));
+    }
+
+    #[test]
+    fn dictionary() {
+        check(
+            "1 0 obj\n<< /Type /Page\n   /MediaBox [0 0 612 792]\n>>\nendobj\ntrailer << /Root 1 0 R$0 >>\n",
+            expect![[r#"
+                77..82
+                `1 0 obj` `/Page`
+
+                ```pdf
+                << /Type /Page
+                   /MediaBox [0 0 612 792]
+                >>
+                ```"#]],
+        );
+    }
+
+    #[test]
+    fn stream() {
+        check(
+            "1 0 obj << /Length 2 >>\nstream\nhi\nendstream\nendobj\n2 0 obj << /Contents $01 0 R >> endobj\n",
+            expect![[r#"
+                72..77
+                `1 0 obj` stream
+
+                ```pdf
+                << /Length 2 >>
+                ```"#]],
+        );
+    }
+
+    #[test]
+    fn other_values() {
+        check(
+            "1 0 obj 42 endobj\n2 0 obj << /Length 1 0 R$0 >> endobj\n",
+            expect![[r#"
+                37..42
+                `1 0 obj`
+
+                ```pdf
+                42
+                ```"#]],
+        );
+    }
+
+    #[test]
+    fn long_dictionaries_are_cut() {
+        let items: String = (1..13).map(|i| format!("/Key{i} {}\n", "x".repeat(i * 10))).collect();
+        check(
+            &format!("1 0 obj <<\n{items}>> endobj\n2 0 obj [1 0 R$0] endobj\n"),
+            expect![[r#"
+                897..902
+                `1 0 obj`
+
+                ```pdf
+                <<
+                /Key1 xxxxxxxxxx
+                /Key2 xxxxxxxxxxxxxxxxxxxx
+                /Key3 xxxxxxxxxxxxxxxxxxxxxxxxxxxxxx
+                /Key4 xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx
+                /Key5 xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx
+                /Key6 xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx
+                /Key7 xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx
+                /Key8 xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx…
+                /Key9 xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx…
+                …
+                ```"#]],
+        );
+    }
+
+    #[test]
+    fn undefined_object() {
+        check(
+            "1 0 obj [7 0 R$0] endobj\n",
+            expect![[r#"
+                9..14
+                `7 0 obj` is not defined in this file, and is treated as `null`."#]],
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! Editor features for PDF files: the outline, folding, highlighting, the
+//! navigation between indirect references and the objects they refer to, and
+//! the renumbering of objects.
+//!
+//! The features work on a single parsed file, and know nothing about the
+//! language server protocol; positions are byte offsets into the file.
+
+mod file_structure;
+mod folding_ranges;
+mod goto_definition;
+mod hover;
+mod navigation_target;
+mod references;
+mod renumber;
+mod syntax_highlighting;
+
+use document::ObjectId;
+use syntax::{AstNode, PdfDocument, TextSize, ast};
+
+pub use crate::{
+    file_structure::{StructureNode, StructureNodeKind, file_structure},
+    folding_ranges::{Fold, FoldKind, folding_ranges},
+    goto_definition::goto_definition,
+    hover::{HoverResult, hover},
+    navigation_target::NavigationTarget,
+    references::{ReferenceSearchResult, find_all_refs},
+    renumber::{RenumberError, compact_object_numbers, renumber_object},
+    syntax_highlighting::{HlRange, HlTag, highlight},
+};
+
+/// The indirect reference `N G R` under the cursor, if any.
+fn reference_at(file: &PdfDocument, offset: TextSize) -> Option<ast::IndirectReferenceExpr> {
+    file.syntax()
+        .token_at_offset(offset)
+        .find_map(|token| token.parent_ancestors().find_map(ast::IndirectReferenceExpr::cast))
+}
+
+/// The definition of the object `id`. When an incremental update redefines an
+/// object, the last definition is the one in effect.
+fn definition(file: &PdfDocument, id: ObjectId) -> Option<ast::IndirectObjectExpr> {
+    file.syntax()
+        .descendants()
+        .filter_map(ast::IndirectObjectExpr::cast)
+        .filter(|it| ObjectId::of_object(it) == Some(id))
+        .last()
+}
+
+#[cfg(test)]
+mod fixture {
+    use syntax::{Edition, PdfDocument, TextRange, TextSize};
+
+    /// Parses `text` without its markers, and returns the offset of the cursor
+    /// `$0` and the ranges between pairs of `$1`.
+    pub(crate) fn parse(text: &str) -> (PdfDocument, TextSize, Vec<TextRange>) {
+        let mut clean = String::new();
+        let (mut cursor, mut starts, mut ranges) = (None, Vec::new(), Vec::new());
+        let mut rest = text;
+        while let Some(i) = rest.find('$') {
+            clean.push_str(&rest[..i]);
+            let offset = TextSize::of(clean.as_str());
+            match &rest[i..i + 2] {
+                "$0" => cursor = Some(offset),
+                "$1" if starts.len() > ranges.len() => ranges.push(TextRange::new(starts[ranges.len()], offset)),
+                "$1" => starts.push(offset),
+                marker => panic!("unknown marker {marker}"),
+            }
+            rest = &rest[i + 2..];
+        }
+        clean.push_str(rest);
+        let parse = PdfDocument::parse(clean.as_bytes(), Edition::CURRENT);
+        (parse.tree(), cursor.expect("no cursor marker"), ranges)
+    }
+}
@@ -0,0 +1,23 @@
+//! A place in a file the editor can navigate to.
+
+use syntax::{AstNode, TextRange, ast};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavigationTarget {
+    pub name: String,
+    /// The range of the whole target, e.g. an object from `obj` to `endobj`.
+    pub full_range: TextRange,
+    /// The range to select, e.g. `1 0 obj`.
+    pub focus_range: TextRange,
+}
+
+impl NavigationTarget {
+    pub(crate) fn from_object(object: &ast::IndirectObjectExpr) -> Option<NavigationTarget> {
+        let id = object.indirect_object_id()?;
+        Some(NavigationTarget {
+            name: id.syntax().text().to_string(),
+            full_range: object.syntax().text_range(),
+            focus_range: id.syntax().text_range(),
+        })
+    }
+}
@@ -0,0 +1,87 @@
+//! Finds every indirect reference `N G R` to an object, from one of those
+//! references or from the object itself.
+
+use document::ObjectId;
+use syntax::{AstNode, PdfDocument, TextRange, TextSize, ast};
+
+use crate::NavigationTarget;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceSearchResult {
+    pub declaration: Option<NavigationTarget>,
+    /// The ranges of the references, in file order.
+    pub references: Vec<TextRange>,
+}
+
+pub fn find_all_refs(file: &PdfDocument, offset: TextSize) -> Option<ReferenceSearchResult> {
+    let id = match crate::reference_at(file, offset) {
+        Some(reference) => ObjectId::of_reference(&reference)?,
+        None => {
+            let object = file
+                .syntax()
+                .token_at_offset(offset)
+                .find_map(|token| token.parent_ancestors().find_map(ast::IndirectObjectId::cast))?;
+            ObjectId::of_object(&ast::IndirectObjectExpr::cast(object.syntax().parent()?)?)?
+        }
+    };
+    let references = file
+        .syntax()
+        .descendants()
+        .filter_map(ast::IndirectReferenceExpr::cast)
+        .filter(|it| ObjectId::of_reference(it) == Some(id))
+        .map(|it| it.syntax().text_range())
+        .collect();
+    let declaration = crate::definition(file, id).and_then(|it| NavigationTarget::from_object(&it));
+    Some(ReferenceSearchResult { declaration, references })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    use super::*;
+
+    /// Checks that the references found from `$0` are the ranges between the
+    /// `$1` markers.
+    fn check(fixture: &str, declaration: Option<&str>) {
+        let (file, offset, expected) = fixture::parse(fixture);
+        let ReferenceSearchResult {
+            declaration: actual_declaration,
+            references,
+        } = find_all_refs(&file, offset).unwrap();
+        assert_eq!(references, expected);
+        assert_eq!(actual_declaration.map(|it| it.name).as_deref(), declaration);
+    }
+
+    #[test]
+    fn from_reference() {
+        check(
+            "1 0 obj << /Type /Catalog /Pages $12 0 R$1 >> endobj\n\
+             2 0 obj << /Type /Pages /Kids [3 0 R 4 0 R] >> endobj\n\
+             3 0 obj << /Parent $12 0 R$1 >> endobj\n\
+             4 0 obj << /Parent $12 0$0 R$1 >> endobj\n\
+             trailer << /Root 1 0 R /Info 2 1 R >>\n",
+            Some("2 0 obj"),
+        );
+    }
+
+    #[test]
+    fn from_object() {
+        check(
+            "$01 0 obj << /Type /Catalog >> endobj\n2 0 obj << /Parent $11 0 R$1 >> endobj\ntrailer << /Root $11 0 R$1 >>\n",
+            Some("1 0 obj"),
+        );
+        check("1 0 o$0bj << /Type /Catalog >> endobj\ntrailer << /Root $11 0 R$1 >>\n", Some("1 0 obj"));
+    }
+
+    #[test]
+    fn undefined_object() {
+        check("1 0 obj [$15 0 R$1 $15 0 R$0$1] endobj\n", None);
+    }
+
+    #[test]
+    fn nothing_inside_of_objects() {
+        let (file, offset, _) = fixture::parse("1 0 obj << /Type $0/Catalog >> endobj\n");
+        assert_eq!(find_all_refs(&file, offset), None);
+    }
+}
@@ -0,0 +1,403 @@
+//! Renumbering of indirect objects, either one object at a time or compacting
+//! all of them into `1..=n`.
+//!
+//! Every `N G obj` and `N G R` whose number changes is rewritten in place.
+//! Then the cross-reference table of each revision is regenerated, together
+//! with the `/Size` and `/Prev` entries of its trailer and the `startxref`
+//! offset. The rest of the file is kept byte for byte.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+};
+
+use document::ObjectId;
+use syntax::{AstNode, PdfDocument, SyntaxElement, XRefEntryKind, ast, ast::make, ted};
+
+/// The generation number of the head of the free list, object 0.
+const FREE_LIST_HEAD_GENERATION: u64 = 65535;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenumberError {
+    /// No object has this number.
+    UnknownObject(u32),
+    /// An object or a reference already uses this number.
+    NumberInUse(u32),
+    /// The file has object streams or cross-reference streams, whose data
+    /// isn't rewritten.
+    CompressedObjects,
+}
+
+impl fmt::Display for RenumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenumberError::UnknownObject(number) => write!(f, "there is no object {number}"),
+            RenumberError::NumberInUse(number) => write!(f, "object number {number} is already in use"),
+            RenumberError::CompressedObjects => write!(f, "objects in object streams or cross-reference streams can't be renumbered"),
+        }
+    }
+}
+
+impl std::error::Error for RenumberError {}
+
+/// Gives the object `from` the number `to`, in every revision, and updates
+/// the references to it.
+pub fn renumber_object(file: &PdfDocument, from: u32, to: u32) -> Result<PdfDocument, RenumberError> {
+    check_uncompressed(file)?;
+    let numbers = Numbers::of(file);
+    if !numbers.defined.contains(&from) {
+        return Err(RenumberError::UnknownObject(from));
+    }
+    if from != to && (numbers.defined.contains(&to) || numbers.referenced.contains(&to)) {
+        return Err(RenumberError::NumberInUse(to));
+    }
+    Ok(renumber(file, &numbers.defined, &HashMap::from([(from, to)])))
+}
+
+/// Numbers the objects `1..=n` in the order of their current numbers.
+///
+/// References to objects which don't exist keep pointing to nothing: they're
+/// numbered after the objects.
+pub fn compact_object_numbers(file: &PdfDocument) -> Result<PdfDocument, RenumberError> {
+    check_uncompressed(file)?;
+    let numbers = Numbers::of(file);
+    let dangling = numbers.referenced.difference(&numbers.defined);
+    let mapping = numbers.defined.iter().chain(dangling).zip(1..).map(|(&old, new)| (old, new)).collect();
+    Ok(renumber(file, &numbers.defined, &mapping))
+}
+
+/// The object numbers used by a file.
+struct Numbers {
+    defined: BTreeSet<u32>,
+    referenced: BTreeSet<u32>,
+}
+
+impl Numbers {
+    fn of(file: &PdfDocument) -> Numbers {
+        let defined = objects(file).filter_map(|it| ObjectId::of_object(&it)).map(|it| it.number).collect();
+        let referenced = references(file).filter_map(|it| ObjectId::of_reference(&it)).map(|it| it.number).collect();
+        Numbers { defined, referenced }
+    }
+}
+
+fn check_uncompressed(file: &PdfDocument) -> Result<(), RenumberError> {
+    let is_object_stream = |object: &ast::IndirectObjectExpr| {
+        object
+            .stream_expr()
+            .and_then(|it| it.dictionary_expr()?.get(b"/Type"))
+            .is_some_and(|it| it.is_name(b"/ObjStm"))
+    };
+    let is_hybrid = |revision: ast::Revision| revision.trailer().and_then(|it| it.dictionary_expr()?.get(b"/XRefStm")).is_some();
+    if objects(file).any(|it| it.is_x_ref_stream() || is_object_stream(&it)) || file.revisions().any(is_hybrid) {
+        return Err(RenumberError::CompressedObjects);
+    }
+    Ok(())
+}
+
+fn renumber(file: &PdfDocument, defined: &BTreeSet<u32>, mapping: &HashMap<u32, u32>) -> PdfDocument {
+    let file = file.clone_for_update();
+    let ids = objects(&file).filter_map(|it| it.indirect_object_id()?.object_number());
+    let references = references(&file).filter_map(|it| it.object_number());
+    let literals: Vec<_> = ids.chain(references).collect();
+    for literal in literals {
+        let number = ast::Expr::Literal(literal.clone()).as_int().and_then(|it| u32::try_from(it).ok());
+        if let Some(&new) = number.and_then(|it| mapping.get(&it)) {
+            ted::replace(literal.syntax().clone(), make::int_number(new.into()).syntax().clone());
+        }
+    }
+    regenerate_x_ref_tables(&file, defined, mapping);
+    file
+}
+
+/// Rebuilds the table of each revision from the objects in its body, in file
+/// order, so the offsets of earlier revisions are final when a table is made.
+///
+/// Free entries of the old tables are kept when they refer to an object of
+/// the file, which an update deleted; the first table also lists the unused
+/// numbers below its largest object number as free.
+fn regenerate_x_ref_tables(file: &PdfDocument, defined: &BTreeSet<u32>, mapping: &HashMap<u32, u32>) {
+    let mut size = 0;
+    let mut prev = None;
+    for (i, revision) in file.revisions().enumerate() {
+        let objects: Vec<_> = revision.body().into_iter().flat_map(|it| it.indirect_object_exprs()).collect();
+        let Some(table) = revision.x_ref_table() else {
+            size = objects.iter().filter_map(ObjectId::of_object).map(|it| it.number + 1).fold(size, u32::max);
+            continue;
+        };
+
+        let mut entries = BTreeMap::new();
+        for (number, entry) in table.entries() {
+            if let XRefEntryKind::Free { generation, .. } = entry {
+                if number == 0 || defined.contains(&number) {
+                    entries.insert(mapping.get(&number).copied().unwrap_or(number), free(generation));
+                }
+            }
+        }
+        for object in &objects {
+            let Some(id) = ObjectId::of_object(object) else { continue };
+            let offset = u64::from(u32::from(object.syntax().text_range().start()));
+            entries.insert(
+                id.number,
+                XRefEntryKind::InUse {
+                    offset,
+                    generation: id.generation.into(),
+                },
+            );
+        }
+        if i == 0 {
+            entries.entry(0).or_insert(free(FREE_LIST_HEAD_GENERATION));
+            let last = entries.last_key_value().map_or(0, |(&it, _)| it);
+            for number in 1..last {
+                entries.entry(number).or_insert(free(0));
+            }
+        }
+        link_free_entries(&mut entries);
+        size = entries.last_key_value().map_or(0, |(&it, _)| it + 1).max(size);
+
+        let new_table = make::x_ref_table(&subsections(entries));
+        ted::replace(table.syntax().clone(), new_table.syntax().clone());
+        let offset = u64::from(u32::from(new_table.syntax().text_range().start()));
+
+        if let Some(trailer) = revision.trailer() {
+            if let Some(dictionary) = trailer.dictionary_expr() {
+                set_int(&dictionary, "/Size", size.into());
+                if let Some(prev) = prev.filter(|_| dictionary.get(b"/Prev").is_some()) {
+                    set_int(&dictionary, "/Prev", prev);
+                }
+            }
+            if let Some(literal) = trailer.literal() {
+                ted::replace(literal.syntax().clone(), make::int_number(offset).syntax().clone());
+            }
+        }
+        prev = Some(offset);
+    }
+}
+
+fn free(generation: u64) -> XRefEntryKind {
+    XRefEntryKind::Free {
+        next_free_object: 0,
+        generation,
+    }
+}
+
+/// Chains the free entries into a list, in ascending order and back to 0.
+fn link_free_entries(entries: &mut BTreeMap<u32, XRefEntryKind>) {
+    let free: Vec<u32> = entries
+        .iter()
+        .filter(|(_, it)| matches!(it, XRefEntryKind::Free { .. }))
+        .map(|(&it, _)| it)
+        .collect();
+    for (i, number) in free.iter().enumerate() {
+        if let Some(XRefEntryKind::Free { next_free_object, .. }) = entries.get_mut(number) {
+            *next_free_object = free.get(i + 1).map_or(0, |&it| it.into());
+        }
+    }
+}
+
+/// Splits the entries into runs of consecutive object numbers.
+fn subsections(entries: BTreeMap<u32, XRefEntryKind>) -> Vec<(u32, Vec<XRefEntryKind>)> {
+    let mut subsections: Vec<(u32, Vec<XRefEntryKind>)> = Vec::new();
+    for (number, entry) in entries {
+        match subsections.last_mut() {
+            Some((first, run)) if *first as usize + run.len() == number as usize => run.push(entry),
+            _ => subsections.push((number, vec![entry])),
+        }
+    }
+    subsections
+}
+
+/// Sets the value of `key` in `dictionary`, adding the key first if needed.
+fn set_int(dictionary: &ast::DictionaryExpr, key: &str, value: u64) {
+    let item = dictionary
+        .dictionary_item_exprs()
+        .find(|it| it.key_name().is_some_and(|it| it.is(key.as_bytes())));
+    match item.and_then(|it| it.value()) {
+        Some(old) => ted::replace(old.syntax().clone(), make::int_number(value).syntax().clone()),
+        None => {
+            let Some(l_dict) = dictionary.l_dict_token() else { return };
+            let item = make::dictionary_item_int(key, value);
+            ted::insert_all_after(l_dict, vec![make::whitespace(" ").into(), SyntaxElement::from(item.syntax().clone())]);
+        }
+    }
+}
+
+fn objects(file: &PdfDocument) -> impl Iterator<Item = ast::IndirectObjectExpr> {
+    file.revisions().filter_map(|it| it.body()).flat_map(|it| it.indirect_object_exprs())
+}
+
+fn references(file: &PdfDocument) -> impl Iterator<Item = ast::IndirectReferenceExpr> {
+    file.syntax().descendants().filter_map(ast::IndirectReferenceExpr::cast)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{Expect, expect};
+    use syntax::{Edition, Parse};
+
+    use super::*;
+
+    fn parse(text: &str) -> Parse<PdfDocument> {
+        PdfDocument::parse(text.as_bytes(), Edition::CURRENT)
+    }
+
+    /// Checks the renumbered file, and that both the fixture and the result
+    /// are valid.
+    fn check(text: &str, f: impl FnOnce(&PdfDocument) -> Result<PdfDocument, RenumberError>, expect: Expect) {
+        let parse = parse(text);
+        assert_eq!(parse.errors(), Vec::new(), "invalid fixture");
+        let mut actual = Vec::new();
+        f(&parse.tree()).unwrap().syntax().text().for_each_chunk(|it| actual.extend_from_slice(it));
+        let actual = String::from_utf8(actual).unwrap();
+        expect.assert_eq(&actual);
+        assert_eq!(self::parse(&actual).errors(), Vec::new(), "invalid result");
+    }
+
+    const PAGES: &str = "%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R >>\nendobj\n\
+        xref\n0 4\n0000000000 65535 f \n0000000009 00000 n \n0000000058 00000 n \n0000000115 00000 n \n\
+        trailer\n<< /Size 4 /Root 1 0 R >>\nstartxref\n162\n%%EOF\n";
+
+    #[test]
+    fn renumber_one_object() {
+        check(
+            PAGES,
+            |file| renumber_object(file, 2, 12),
+            expect![[r#"
+                %PDF-1.7
+                1 0 obj
+                << /Type /Catalog /Pages 12 0 R >>
+                endobj
+                12 0 obj
+                << /Type /Pages /Kids [3 0 R] /Count 1 >>
+                endobj
+                3 0 obj
+                << /Type /Page /Parent 12 0 R >>
+                endobj
+                xref
+                0 13
+                0000000002 65535 f 
+                0000000009 00000 n 
+                0000000004 00000 f 
+                0000000117 00000 n 
+                0000000005 00000 f 
+                0000000006 00000 f 
+                0000000007 00000 f 
+                0000000008 00000 f 
+                0000000009 00000 f 
+                0000000010 00000 f 
+                0000000011 00000 f 
+                0000000000 00000 f 
+                0000000059 00000 n 
+                trailer
+                << /Size 13 /Root 1 0 R >>
+                startxref
+                165
+                %%EOF
+            "#]],
+        );
+    }
+
+    #[test]
+    fn compact_objects() {
+        check(
+            "%PDF-1.7\n\
+             3 0 obj\n<< /Type /Catalog /Pages 7 0 R >>\nendobj\n\
+             7 0 obj\n<< /Type /Pages /Kids [] /Count 0 /Extra 1 0 R >>\nendobj\n\
+             xref\n0 8\n0000000000 65535 f \n0000000000 65535 f \n0000000000 65535 f \n0000000009 00000 n \n\
+             0000000000 65535 f \n0000000000 65535 f \n0000000000 65535 f \n0000000058 00000 n \n\
+             trailer\n<< /Root 3 0 R >>\nstartxref\n123\n%%EOF\n",
+            compact_object_numbers,
+            expect![[r#"
+                %PDF-1.7
+                1 0 obj
+                << /Type /Catalog /Pages 2 0 R >>
+                endobj
+                2 0 obj
+                << /Type /Pages /Kids [] /Count 0 /Extra 3 0 R >>
+                endobj
+                xref
+                0 3
+                0000000000 65535 f 
+                0000000009 00000 n 
+                0000000058 00000 n 
+                trailer
+                << /Size 3 /Root 1 0 R >>
+                startxref
+                123
+                %%EOF
+            "#]],
+        );
+    }
+
+    #[test]
+    fn incremental_updates() {
+        check(
+            "%PDF-1.7\n\
+             1 0 obj\n<< /Type /Catalog >>\nendobj\n\
+             2 0 obj\n(old)\nendobj\n\
+             xref\n0 3\n0000000000 65535 f \n0000000009 00000 n \n0000000045 00000 n \n\
+             trailer\n<< /Size 3 /Root 1 0 R >>\nstartxref\n66\n%%EOF\n\
+             1 0 obj\n<< /Type /Catalog /Lang (en) >>\nendobj\n\
+             xref\n0 1\n0000000000 65535 f \n1 2\n0000000188 00000 n \n0000000000 00001 f \n\
+             trailer\n<< /Size 3 /Root 1 0 R /Prev 66 >>\nstartxref\n235\n%%EOF\n",
+            |file| renumber_object(file, 2, 10),
+            expect![[r#"
+                %PDF-1.7
+                1 0 obj
+                << /Type /Catalog >>
+                endobj
+                10 0 obj
+                (old)
+                endobj
+                xref
+                0 11
+                0000000002 65535 f 
+                0000000009 00000 n 
+                0000000003 00000 f 
+                0000000004 00000 f 
+                0000000005 00000 f 
+                0000000006 00000 f 
+                0000000007 00000 f 
+                0000000008 00000 f 
+                0000000009 00000 f 
+                0000000000 00000 f 
+                0000000045 00000 n 
+                trailer
+                << /Size 11 /Root 1 0 R >>
+                startxref
+                67
+                %%EOF
+                1 0 obj
+                << /Type /Catalog /Lang (en) >>
+                endobj
+                xref
+                0 2
+                0000000010 65535 f 
+                0000000351 00000 n 
+                10 1
+                0000000000 00001 f 
+                trailer
+                << /Size 11 /Root 1 0 R /Prev 67 >>
+                startxref
+                398
+                %%EOF
+            "#]],
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let file = parse(PAGES).tree();
+        assert_eq!(renumber_object(&file, 4, 5), Err(RenumberError::UnknownObject(4)));
+        assert_eq!(renumber_object(&file, 1, 3), Err(RenumberError::NumberInUse(3)));
+
+        let file = parse(
+            "%PDF-1.7\n1 0 obj\n<< /Type /ObjStm /N 0 /First 0 /Length 0 >>\nstream\n\nendstream\nendobj\n\
+             trailer\n<< /Root 1 0 R >>\nstartxref\n0\n%%EOF\n",
+        )
+        .tree();
+        assert_eq!(compact_object_numbers(&file), Err(RenumberError::CompressedObjects));
+    }
+}
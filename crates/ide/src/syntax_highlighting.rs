@@ -0,0 +1,109 @@
+//! Semantic highlighting, derived from the kinds of the tokens and of their
+//! parents.
+
+use syntax::{AstNode, PdfDocument, SyntaxKind, SyntaxToken, TextRange};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HlTag {
+    Comment,
+    Keyword,
+    Number,
+    String,
+    /// A name used as the key of a dictionary item.
+    DictionaryKey,
+    /// Any other name.
+    Name,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HlRange {
+    pub range: TextRange,
+    pub tag: HlTag,
+}
+
+pub fn highlight(file: &PdfDocument) -> Vec<HlRange> {
+    file.syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter_map(|token| {
+            Some(HlRange {
+                range: token.text_range(),
+                tag: tag(&token)?,
+            })
+        })
+        .collect()
+}
+
+fn tag(token: &SyntaxToken) -> Option<HlTag> {
+    let tag = match token.kind() {
+        SyntaxKind::COMMENT | SyntaxKind::HEADER_COMMENT | SyntaxKind::BINARY_COMMENT | SyntaxKind::EOF_COMMENT => HlTag::Comment,
+        SyntaxKind::INT_NUMBER | SyntaxKind::REAL_NUMBER => HlTag::Number,
+        SyntaxKind::LITERAL_STRING | SyntaxKind::HEX_STRING => HlTag::String,
+        SyntaxKind::NAME => {
+            let is_key = token
+                .parent()
+                .and_then(|it| it.parent())
+                .is_some_and(|it| it.kind() == SyntaxKind::DICTIONARY_ITEM_KEY_EXPR);
+            if is_key { HlTag::DictionaryKey } else { HlTag::Name }
+        }
+        kind if kind.is_keyword(syntax::Edition::LATEST) => HlTag::Keyword,
+        _ => return None,
+    };
+    Some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{Expect, expect};
+    use syntax::Edition;
+
+    use super::*;
+
+    fn check(text: &str, expect: Expect) {
+        let parse = PdfDocument::parse(text.as_bytes(), Edition::CURRENT);
+        let actual: String = highlight(&parse.tree())
+            .iter()
+            .map(|it| format!("{:?} {:?}\n", it.tag, &text[it.range]))
+            .collect();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn highlights_tokens() {
+        check(
+            "%PDF-1.7\n% comment\n1 0 obj << /Type /Page /Parent 2 0 R /Rect [0 1.5] /T (a) /H <61> /B true >> endobj\n\
+             trailer << /Root 1 0 R >>\nstartxref\n0\n%%EOF\n",
+            expect![[r#"
+                Comment "%PDF-1.7"
+                Comment "% comment"
+                Number "1"
+                Number "0"
+                Keyword "obj"
+                DictionaryKey "/Type"
+                Name "/Page"
+                DictionaryKey "/Parent"
+                Number "2"
+                Number "0"
+                Keyword "R"
+                DictionaryKey "/Rect"
+                Number "0"
+                Number "1.5"
+                DictionaryKey "/T"
+                String "(a)"
+                DictionaryKey "/H"
+                String "<61>"
+                DictionaryKey "/B"
+                Keyword "true"
+                Keyword "endobj"
+                Keyword "trailer"
+                DictionaryKey "/Root"
+                Number "1"
+                Number "0"
+                Keyword "R"
+                Keyword "startxref"
+                Number "0"
+                Comment "%%EOF"
+            "#]],
+        );
+    }
+}
@@ -10,6 +10,11 @@ pub struct Cursor<'a> {
     len_remaining: usize,
     iter: slice::Iter<'a, u8>,
     is_prev_token_stream: bool,
+    is_prev_token_inline_image: bool,
+    /// Whether `ID` starts inline image data, which is only the case in
+    /// content streams.
+    inline_images: bool,
+    stream_length: Option<usize>,
 }
 
 impl<'a> Cursor<'a> {
@@ -18,6 +23,18 @@ impl<'a> Cursor<'a> {
             len_remaining: input.len(),
             iter: input.iter(),
             is_prev_token_stream: false,
+            is_prev_token_inline_image: false,
+            inline_images: false,
+            stream_length: None,
+        }
+    }
+
+    /// Creates a cursor for the decoded data of a content stream, where the
+    /// `ID` operator is followed by the data of an inline image.
+    pub fn new_content_stream(input: &'a [u8]) -> Cursor<'a> {
+        Cursor {
+            inline_images: true,
+            ..Cursor::new(input)
         }
     }
 
@@ -92,4 +109,38 @@ impl<'a> Cursor<'a> {
     pub(crate) fn set_prev_token_stream(&mut self, is_stream: bool) {
         self.is_prev_token_stream = is_stream;
     }
+
+    pub(crate) fn has_inline_images(&self) -> bool {
+        self.inline_images
+    }
+
+    pub(crate) fn is_prev_token_inline_image(&self) -> bool {
+        self.is_prev_token_inline_image
+    }
+
+    pub(crate) fn set_prev_token_inline_image(&mut self, is_inline_image: bool) {
+        self.is_prev_token_inline_image = is_inline_image;
+    }
+
+    /// Sets the declared length (the `/Length` entry) of the data of the
+    /// stream whose `stream` keyword was just lexed.
+    ///
+    /// The hint is used by the next token only. If the data doesn't end with
+    /// `endstream` after that many bytes, the lexer falls back to scanning for
+    /// the `endstream` keyword.
+    pub fn set_stream_length(&mut self, length: Option<usize>) {
+        self.stream_length = length;
+    }
+
+    pub(crate) fn take_stream_length(&mut self) -> Option<usize> {
+        self.stream_length.take()
+    }
+
+    pub(crate) fn as_slice(&self) -> &'a [u8] {
+        self.iter.as_slice()
+    }
+
+    pub(crate) fn advance_by(&mut self, n: usize) {
+        self.iter = self.iter.as_slice()[n..].iter();
+    }
 }
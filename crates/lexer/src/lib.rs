@@ -22,10 +22,24 @@ impl Cursor<'_> {
                 // The stream content is skipped by the lexer and is decoded/parsed later by the parser.
                 // See ISO `32000-1:2008`, Section 7.3.8 Stream Objects.
                 self.set_prev_token_stream(false);
-                self.eat_while_word(|word| word.starts_with(b"endstream"));
+                match self.take_stream_length() {
+                    Some(length) if self.try_eat_stream_data(first_byte, length) => {}
+                    // Without a (correct) declared length, the data ends at the first `endstream`.
+                    _ => self.eat_stream_data_until_keyword(),
+                }
                 TokenKind::RawStreamData
             }
 
+            _ if self.is_prev_token_inline_image() => {
+                // Inline image data in a content stream.
+                // The data follows the `ID` operator and a single white-space character, and ends before the `EI` operator.
+                // Like stream data, it is skipped by the lexer.
+                // See ISO `32000-2:2020`, Section 8.9.7 Inline images.
+                self.set_prev_token_inline_image(false);
+                self.eat_inline_image_data(first_byte);
+                TokenKind::InlineImageData
+            }
+
             // End of line marker. Sometimes it is required by the PDF spec (e.g. in PDF object keywords).
             // See ISO `32000-1:2008`, Section 7.2.3 Character Set.
             b if is_eol(b) => {
@@ -51,30 +65,36 @@ impl Cursor<'_> {
                 if b == b's' && self.try_eat_word(b"tream") {
                     self.set_prev_token_stream(true);
                     TokenKind::Ident
+                } else if self.has_inline_images() && b == b'I' && self.peek_first() == b'D' && !is_regular(self.peek_second()) {
+                    self.next();
+                    self.set_prev_token_inline_image(true);
+                    TokenKind::Ident
                 } else {
-                    // PDF keyword or identifier.
-                    self.eat_while(|b| b.is_ascii_alphanumeric());
+                    // PDF keyword, identifier or content stream operator (e.g. `T*`).
+                    self.eat_while(|b| b.is_ascii_alphanumeric() || b == b'*');
                     TokenKind::Ident
                 }
             }
 
+            // Content stream operators which are not alphabetic.
+            // See ISO `32000-2:2020`, Section 9.4.3 Text-showing operators.
+            b'\'' | b'"' => TokenKind::Ident,
+
             // PDF Name.
             // See ISO `32000-1:2008`, Section 7.3.5 Name Objects.
             b'/' => {
-                // Consume the name until a delimiter or whitespace is encountered.
-                self.eat_while(|b| !is_whitespace(b) && !is_delimiter(b));
-                TokenKind::Literal { kind: LiteralKind::Name }
+                let terminated = self.eat_name();
+                TokenKind::Literal {
+                    kind: LiteralKind::Name { terminated },
+                }
             }
 
             // PDF literal string.
             // See ISO `32000-1:2008`, Section 7.3.4.2 Literal Strings.
             b'(' => {
-                if self.eat_literal_string() && self.pos_within_token() > 1 {
-                    TokenKind::Literal {
-                        kind: LiteralKind::LiteralString,
-                    }
-                } else {
-                    TokenKind::Unknown // If there is no byte following the opening parenthesis, it is an invalid token.
+                let terminated = self.eat_literal_string();
+                TokenKind::Literal {
+                    kind: LiteralKind::LiteralString { terminated },
                 }
             }
 
@@ -83,11 +103,14 @@ impl Cursor<'_> {
             b'<' if self.peek_first() != b'<' => {
                 self.eat_while(|b| b.is_ascii_hexdigit() || is_whitespace(b));
 
-                if self.peek_first() == b'>' {
+                // The string ends before the first byte which isn't a hex digit
+                // or whitespace, unless it's the closing `>`.
+                let terminated = self.peek_first() == b'>';
+                if terminated {
                     self.next();
-                    TokenKind::Literal { kind: LiteralKind::HexString }
-                } else {
-                    TokenKind::Unknown // If the hex string is not closed, it is an invalid token.
+                }
+                TokenKind::Literal {
+                    kind: LiteralKind::HexString { terminated },
                 }
             }
 
@@ -167,11 +190,86 @@ impl Cursor<'_> {
         }
     }
 
+    /// Eats the end-of-line marker following the `stream` keyword (whose first
+    /// byte is already consumed), `length` bytes of data and any whitespace
+    /// up to the `endstream` keyword.
+    ///
+    /// Nothing is eaten if `endstream` doesn't follow.
+    fn try_eat_stream_data(&mut self, first_byte: u8, length: usize) -> bool {
+        let rest = self.as_slice();
+        let data = match first_byte {
+            b'\r' if rest.first() == Some(&b'\n') => &rest[1..],
+            b if is_eol(b) => rest,
+            // No end-of-line marker, so the first byte is part of the data.
+            _ if length > 0 => return self.try_eat_stream_data_from(rest, length - 1),
+            _ => return false,
+        };
+        self.try_eat_stream_data_from(data, length)
+    }
+
+    fn try_eat_stream_data_from(&mut self, data: &[u8], length: usize) -> bool {
+        let Some(after_data) = data.get(length..) else {
+            return false;
+        };
+        let whitespace = after_data.iter().take_while(|&&b| is_whitespace(b)).count();
+        if !after_data[whitespace..].starts_with(b"endstream") {
+            return false;
+        }
+        // `data` is a suffix of the remaining input.
+        self.advance_by(self.as_slice().len() - after_data.len() + whitespace);
+        true
+    }
+
+    /// Eats bytes until the first `endstream`. If it's missing, the data ends at
+    /// the first `endobj` rather than at the end of the input, so that the
+    /// following objects are still lexed.
+    fn eat_stream_data_until_keyword(&mut self) {
+        let keyword: &[u8] = if self.as_slice().windows(9).any(|it| it == b"endstream") {
+            b"endstream"
+        } else {
+            b"endobj"
+        };
+        self.eat_while_word(|word| word.starts_with(keyword));
+    }
+
+    /// Eats bytes until `EI` surrounded by white-space follows.
+    fn eat_inline_image_data(&mut self, first_byte: u8) {
+        let mut prev = first_byte;
+        while !self.is_eof() {
+            let rest = self.as_slice();
+            if is_whitespace(prev) && rest.starts_with(b"EI") && rest.get(2).is_none_or(|&b| !is_regular(b)) {
+                break;
+            }
+            prev = rest[0];
+            self.next();
+        }
+    }
+
+    /// Eats the name until a delimiter or whitespace is encountered, and
+    /// returns whether its last `#xx` escape (if any) is complete.
+    fn eat_name(&mut self) -> bool {
+        let mut terminated = true;
+        while is_regular(self.peek_first()) {
+            if self.next() == Some(b'#') {
+                // Whether the escape is valid is up to the parser, here it
+                // only matters that it isn't cut off.
+                terminated = is_regular(self.peek_first()) && is_regular(self.peek_second());
+            }
+        }
+        terminated
+    }
+
+    /// Eats the string up to the matching closing parenthesis, and returns
+    /// whether there was one. Escaped parentheses don't count towards the
+    /// nesting, and neither does anything else following a backslash.
     fn eat_literal_string(&mut self) -> bool {
         let mut depth = 1;
 
         loop {
             match self.next() {
+                Some(b'\\') => {
+                    self.next();
+                }
                 Some(b'(') => depth += 1,
                 Some(b')') => {
                     depth -= 1;
@@ -216,6 +314,12 @@ fn is_whitespace(b: u8) -> bool {
     )
 }
 
+/// Regular characters are all characters except white-space and delimiters.
+/// See ISO `32000-1:2008`, Section 7.2.3 Character Set.
+fn is_regular(b: u8) -> bool {
+    !is_whitespace(b) && !is_delimiter(b)
+}
+
 /// See ISO `32000-1:2008`, Section 7.2.3 Character Set, Table 2 Delimiter characters.
 fn is_delimiter(b: u8) -> bool {
     matches!(
@@ -46,3 +46,127 @@ fn run_and_expect_no_errors(path: &str) {
     p.set_extension("rast");
     expect_file![p].assert_eq(&actual)
 }
+
+/// Lexes `text`, passing `length` as the declared length of its stream.
+fn lex_stream(text: &[u8], length: Option<usize>) -> Vec<(TokenKind, &[u8])> {
+    lex_with(Cursor::new(text), text, length)
+}
+
+fn lex_content_stream(text: &[u8]) -> Vec<(TokenKind, &[u8])> {
+    lex_with(Cursor::new_content_stream(text), text, None)
+}
+
+fn lex_with<'a>(mut cursor: Cursor<'_>, text: &'a [u8], length: Option<usize>) -> Vec<(TokenKind, &'a [u8])> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    loop {
+        let token = cursor.advance_token();
+        if token.kind == TokenKind::Eof {
+            break;
+        }
+        let token_text = &text[offset..offset + token.len as usize];
+        if token_text == b"stream" {
+            cursor.set_stream_length(length);
+        }
+        tokens.push((token.kind, token_text));
+        offset += token.len as usize;
+    }
+    tokens
+}
+
+fn stream_data(tokens: &[(TokenKind, &'static [u8])]) -> &'static [u8] {
+    tokens.iter().find(|(kind, _)| *kind == TokenKind::RawStreamData).unwrap().1
+}
+
+#[test]
+fn stream_data_uses_declared_length() {
+    let text = b"stream\r\nendstream\nendstream";
+    assert_eq!(stream_data(&lex_stream(text, Some(9))), b"\r\nendstream\n");
+    assert_eq!(stream_data(&lex_stream(text, None)), b"\r\n");
+
+    // Whitespace between the data and `endstream` belongs to the data token.
+    assert_eq!(stream_data(&lex_stream(b"stream\nabc \r\n endstream", Some(3))), b"\nabc \r\n ");
+    assert_eq!(stream_data(&lex_stream(b"stream\nendstream", Some(0))), b"\n");
+    // A missing end-of-line marker is tolerated.
+    assert_eq!(stream_data(&lex_stream(b"streamabc\nendstream", Some(3))), b"abc\n");
+}
+
+#[test]
+fn stream_data_falls_back_to_endstream() {
+    // Too long.
+    assert_eq!(stream_data(&lex_stream(b"stream\nabc\nendstream", Some(10))), b"\nabc\n");
+    // Too short.
+    assert_eq!(stream_data(&lex_stream(b"stream\nabc\nendstream", Some(1))), b"\nabc\n");
+    assert_eq!(stream_data(&lex_stream(b"stream\nabc\nendstream", Some(2))), b"\nabc\n");
+}
+
+#[test]
+fn stream_data_without_endstream_ends_at_endobj() {
+    assert_eq!(stream_data(&lex_stream(b"stream\nabc\nendobj\n1 0 obj", Some(3))), b"\nabc\n");
+    assert_eq!(stream_data(&lex_stream(b"stream\nabc\n", None)), b"\nabc\n");
+}
+
+#[test]
+fn content_stream_operators() {
+    let kinds: Vec<_> = lex_stream(b"T* ' \"d0", None)
+        .into_iter()
+        .filter(|(kind, _)| *kind != TokenKind::Whitespace)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (TokenKind::Ident, &b"T*"[..]),
+            (TokenKind::Ident, b"'"),
+            (TokenKind::Ident, b"\""),
+            (TokenKind::Ident, b"d0")
+        ]
+    );
+}
+
+#[test]
+fn inline_image_data_ends_before_ei() {
+    let tokens = lex_content_stream(b"BI /W 1 ID EI\x01EIx\nEI Q");
+    let data = tokens.iter().position(|(kind, _)| *kind == TokenKind::InlineImageData).unwrap();
+    assert_eq!(tokens[data - 1], (TokenKind::Ident, &b"ID"[..]));
+    assert_eq!(tokens[data].1, b" EI\x01EIx\n");
+    assert_eq!(tokens[data + 1], (TokenKind::Ident, &b"EI"[..]));
+
+    // `IDx` is not the `ID` operator.
+    assert!(lex_content_stream(b"IDx EI").iter().all(|(kind, _)| *kind != TokenKind::InlineImageData));
+}
+
+#[test]
+fn inline_images_are_only_lexed_in_content_streams() {
+    let tokens = lex_stream(b"1 0 obj [ID] endobj\n2 0 obj (EI) endobj", None);
+    assert!(tokens.iter().all(|(kind, _)| *kind != TokenKind::InlineImageData));
+    assert!(tokens.contains(&(TokenKind::Ident, &b"ID"[..])));
+}
+
+#[test]
+fn literal_string_escapes_dont_count_towards_nesting() {
+    let tokens = lex_stream(br"(a \) b) (c \\) d) (\", None);
+    let strings: Vec<_> = tokens.into_iter().filter(|(kind, _)| matches!(kind, TokenKind::Literal { .. })).collect();
+    assert_eq!(
+        strings,
+        vec![
+            (
+                TokenKind::Literal {
+                    kind: LiteralKind::LiteralString { terminated: true }
+                },
+                &br"(a \) b)"[..]
+            ),
+            (
+                TokenKind::Literal {
+                    kind: LiteralKind::LiteralString { terminated: true }
+                },
+                br"(c \\)"
+            ),
+            (
+                TokenKind::Literal {
+                    kind: LiteralKind::LiteralString { terminated: false }
+                },
+                br"(\"
+            ),
+        ]
+    );
+}
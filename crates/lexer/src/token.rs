@@ -36,6 +36,10 @@ pub enum TokenKind {
     /// The stream content is skipped by the lexer and is decoded/parsed later by the parser.
     RawStreamData,
 
+    /// The data of an inline image in a content stream, between the `ID` and
+    /// `EI` operators.
+    InlineImageData,
+
     /// A literal token, e.g. `123`, `3.14`.
     ///
     /// The [LiteralKind] enum contains information about the type of the literal.
@@ -63,12 +67,14 @@ pub enum LiteralKind {
     /// `3.14`, `+3.14`, `-3.14`, '3.', '.3'.
     Real,
 
-    /// `/Name1`, `/ASomewhatLongerName`.
-    Name, // TODO: add terminated flag
+    /// `/Name1`, `/ASomewhatLongerName`, `/Lime#20Green`.
+    ///
+    /// A name isn't terminated if it ends within a `#xx` escape, e.g. `/A#2`.
+    Name { terminated: bool },
 
     /// Literal String `(This is a string)`, `(This is a string with \(escaped\) characters)`.
-    LiteralString, // TODO: add terminated flag
+    LiteralString { terminated: bool },
 
     /// Hexadecimal String `<0123456789ABCDEF>`, `<0123456789abcdef>`.
-    HexString, // TODO: add terminated flag
+    HexString { terminated: bool },
 }
@@ -39,6 +39,14 @@ impl Limit {
         self.upper_bound
     }
 
+    /// Gets the largest value checked against a limit created with
+    /// [`Limit::new_tracking`] so far.
+    #[inline]
+    #[cfg(feature = "tracking")]
+    pub fn max(&self) -> usize {
+        self.max.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
     /// Checks whether the given value is below the limit.
     /// Returns `Ok` when `other` is below `self`, and `Err` otherwise.
     #[inline]
@@ -47,16 +55,48 @@ impl Limit {
             Err(())
         } else {
             #[cfg(feature = "tracking")]
-            loop {
-                use std::sync::atomic::Ordering;
-                let old_max = self.max.load(Ordering::Relaxed);
-                if other <= old_max || old_max == 0 {
-                    break;
-                }
-                _ = self.max.compare_exchange_weak(old_max, other, Ordering::Relaxed, Ordering::Relaxed);
-            }
+            self.record(other);
 
             Ok(())
         }
     }
+
+    /// Records `other` as seen by a limit created with
+    /// [`Limit::new_tracking`], whether or not it is below the limit. This is
+    /// for callers enforcing a bound of their own.
+    #[inline]
+    #[cfg(feature = "tracking")]
+    pub fn record(&self, other: usize) {
+        use std::sync::atomic::Ordering;
+        loop {
+            let old_max = self.max.load(Ordering::Relaxed);
+            if other <= old_max || old_max == 0 {
+                break;
+            }
+            _ = self.max.compare_exchange_weak(old_max, other, Ordering::Relaxed, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tracking"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_values_above_the_limit() {
+        let limit = Limit::new_tracking(4);
+        assert_eq!(limit.check(3), Ok(()));
+        assert_eq!(limit.max(), 3);
+        assert_eq!(limit.check(10), Err(()));
+        assert_eq!(limit.max(), 3);
+        limit.record(10);
+        assert_eq!(limit.max(), 10);
+        limit.record(7);
+        assert_eq!(limit.max(), 10);
+
+        // Limits created without tracking record nothing.
+        let limit = Limit::new(4);
+        limit.record(10);
+        assert_eq!(limit.max(), 0);
+    }
 }
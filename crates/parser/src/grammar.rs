@@ -29,11 +29,12 @@
 //! caller is responsible for branching on the first token.
 
 mod atom;
+mod content;
 mod expressions;
 mod items;
 
 use crate::{
-    SyntaxKind::*,
+    SyntaxKind::{self, *},
     T, TokenSet,
     parser::{CompletedMarker, Marker, Parser},
 };
@@ -46,10 +47,25 @@ pub(crate) mod entry {
 
         pub(crate) fn pdf_document(p: &mut Parser<'_>) {
             let m = p.start();
-            items::pdf_body(p);
+            if p.at(T![header_comment]) {
+                items::pdf_header(p);
+            }
+            items::revisions(p);
             m.complete(p, PDF_DOCUMENT);
         }
 
+        pub(crate) fn object_stream(p: &mut Parser<'_>) {
+            let m = p.start();
+            items::object_stream_header(p);
+            m.complete(p, OBJECT_STREAM);
+        }
+
+        pub(crate) fn content_stream(p: &mut Parser<'_>) {
+            let m = p.start();
+            content::content_items(p, false);
+            m.complete(p, CONTENT_STREAM);
+        }
+
         pub(crate) fn expr(p: &mut Parser<'_>) {
             let m = p.start();
             expressions::expr(p);
@@ -64,3 +80,19 @@ pub(crate) mod entry {
         }
     }
 }
+
+pub(crate) fn reparser(node: SyntaxKind) -> Option<fn(&mut Parser<'_>)> {
+    let res: fn(&mut Parser<'_>) = match node {
+        ARRAY_EXPR => |p| {
+            atom::array_expr(p);
+        },
+        DICTIONARY_EXPR => |p| {
+            atom::dictionary_expr(p);
+        },
+        INDIRECT_OBJECT_EXPR => |p| {
+            items::indirect_object(p);
+        },
+        _ => return None,
+    };
+    Some(res)
+}
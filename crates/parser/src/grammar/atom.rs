@@ -4,6 +4,40 @@ pub(crate) const LITERAL_FIRST: TokenSet = TokenSet::new(&[T![true], T![false],
 pub(super) const EXPR_RECOVERY_SET: TokenSet = TokenSet::new(&[T![>>], T![']']]);
 pub(super) const ATOM_EXPR_FIRST: TokenSet = LITERAL_FIRST.union(TokenSet::new(&[T!['['], T![<<]]));
 
+/// Keywords which can't be part of an object's value, only follow it. Arrays
+/// and dictionaries missing their closing delimiter end before them.
+pub(super) const VALUE_END: TokenSet = TokenSet::new(&[
+    T![obj],
+    T![endobj],
+    T![stream],
+    T![endstream],
+    T![xref],
+    T![trailer],
+    T![startxref],
+    T![eof_comment],
+]);
+
+/// Whether the parser is at one of the [`VALUE_END`] keywords, or at the
+/// `N G obj` header of the next object.
+pub(super) fn at_value_end(p: &Parser<'_>) -> bool {
+    p.at_ts(VALUE_END) || at_object_header(p)
+}
+
+pub(super) fn at_object_header(p: &Parser<'_>) -> bool {
+    p.at(INT_NUMBER) && p.nth_at(1, INT_NUMBER) && p.nth_at(2, T![obj])
+}
+
+/// Reports and skips a closing delimiter of `unmatched` which doesn't close
+/// the construct being parsed. It can't start an expression either, so
+/// skipping it makes sure that the parser always makes progress.
+pub(super) fn eat_unmatched_delimiter(p: &mut Parser<'_>, unmatched: TokenSet) -> bool {
+    if !p.at_ts(unmatched) {
+        return false;
+    }
+    p.err_and_bump("unmatched closing delimiter");
+    true
+}
+
 fn literal(p: &mut Parser<'_>) -> Option<CompletedMarker> {
     if !p.at_ts(LITERAL_FIRST) {
         return None;
@@ -52,38 +86,110 @@ pub(super) fn indirect_reference(p: &mut Parser<'_>) -> Option<CompletedMarker>
     }
 }
 
-fn array_expr(p: &mut Parser<'_>) -> CompletedMarker {
+pub(super) fn array_expr(p: &mut Parser<'_>) -> CompletedMarker {
     assert!(p.at(T!['[']));
+    if !p.enter_nested() {
+        return too_deeply_nested(p);
+    }
     let m = p.start();
 
     p.bump(T!['[']);
-    while !p.at(EOF) && !p.at(T![']']) {
-        if expressions::expr(p).is_none() {
-            break;
+    while !p.at(EOF) && !p.at(T![']']) && !at_value_end(p) {
+        if eat_unmatched_delimiter(p, TokenSet::new(&[T![>>]])) {
+            continue;
         }
+        expressions::expr(p);
     }
 
     p.expect(T![']']);
+    p.leave_nested();
     m.complete(p, ARRAY_EXPR)
 }
 
-fn dictionary_expr(p: &mut Parser<'_>) -> CompletedMarker {
-    // TODO: << no_valid_name no_valid_value >>
+pub(super) fn dictionary_expr(p: &mut Parser<'_>) -> CompletedMarker {
     assert!(p.at(T![<<]));
+    if !p.enter_nested() {
+        return too_deeply_nested(p);
+    }
     let m = p.start();
 
     p.bump(T![<<]);
-    while !p.at(EOF) && !p.at(T![>>]) {
-        // if !p.at_ts(LITERAL_FIRST) {
-        //     p.err_and_bump("expected key");
-        //     break;
-        // }
-        // literal(p);
-        if expressions::expr(p).is_none() {
-            break;
+    while !p.at(EOF) && !p.at(T![>>]) && !at_value_end(p) {
+        if eat_unmatched_delimiter(p, TokenSet::new(&[T![']']])) {
+            continue;
         }
+        dictionary_item(p);
     }
 
     p.expect(T![>>]);
+    p.leave_nested();
     m.complete(p, DICTIONARY_EXPR)
 }
+
+/// Wraps an array or a dictionary which would be nested deeper than
+/// the nesting limit, together with everything inside
+/// of it, into a single `ERROR` node. The delimiters are only counted, so
+/// arbitrarily deep nesting doesn't recurse.
+fn too_deeply_nested(p: &mut Parser<'_>) -> CompletedMarker {
+    let m = p.start();
+    p.error(format!("arrays and dictionaries are nested deeper than {} levels", p.nesting_limit()));
+    let mut depth = 0usize;
+    loop {
+        match p.current() {
+            T!['['] | T![<<] => depth += 1,
+            T![']'] | T![>>] => depth -= 1,
+            _ if p.at(EOF) || at_value_end(p) => break,
+            _ => (),
+        }
+        p.bump_any();
+        if depth == 0 {
+            break;
+        }
+    }
+    m.complete(p, ERROR)
+}
+
+/// Parses a key and its value. See ISO `32000-2:2020`, Section 7.3.7.
+///
+/// Keys must be names, but a lone value in key position usually means that
+/// a key or a value has been left out, e.g. `<< /A 1 2 /B 3 >>`. When the
+/// expression in key position is followed by a name or by the end of the
+/// dictionary, it's wrapped into an `ERROR` node, so that the following
+/// items are still paired up correctly. Any other expression is kept as the
+/// key, and is rejected by validation.
+fn dictionary_item(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    if p.at(NAME) {
+        let m = p.start();
+        let key = p.start();
+        literal(p);
+        key.complete(p, DICTIONARY_ITEM_KEY_EXPR);
+
+        if p.at_ts(EXPR_RECOVERY_SET) || p.at(EOF) || at_value_end(p) {
+            p.error("expected dictionary value");
+        } else {
+            let value = p.start();
+            if expressions::expr(p).is_some() {
+                value.complete(p, DICTIONARY_ITEM_VALUE_EXPR);
+            } else {
+                value.abandon(p);
+            }
+        }
+        return Some(m.complete(p, DICTIONARY_ITEM_EXPR));
+    }
+
+    let key = expressions::expr(p)?;
+    if p.at(NAME) || p.at_ts(EXPR_RECOVERY_SET) || p.at(EOF) || at_value_end(p) {
+        let m = key.precede(p);
+        p.error("expected a name as dictionary key");
+        return Some(m.complete(p, ERROR));
+    }
+
+    let m = key.precede(p).complete(p, DICTIONARY_ITEM_KEY_EXPR).precede(p);
+    let value = p.start();
+    if expressions::expr(p).is_some() {
+        value.complete(p, DICTIONARY_ITEM_VALUE_EXPR);
+    } else {
+        value.abandon(p);
+    }
+    Some(m.complete(p, DICTIONARY_ITEM_EXPR))
+}
@@ -0,0 +1,155 @@
+//! Content streams: sequences of operations, each consisting of operands
+//! followed by an operator, some of which open or close a block.
+//!
+//! See ISO `32000-2:2020`, Section 7.8.2 Content streams and Annex A.
+
+use super::*;
+
+/// The operators which open a block.
+const BLOCK_OPENING: [SyntaxKind; 4] = [T![BT], T![q], T![BMC], T![BDC]];
+
+/// The operators which close a block.
+const BLOCK_CLOSING: [SyntaxKind; 3] = [T![ET], T![Q], T![EMC]];
+
+/// The operators which are parsed as part of a block rather than as a plain
+/// operation.
+const BLOCK_OPERATORS: [SyntaxKind; 10] = [T![BT], T![ET], T![q], T![Q], T![BMC], T![BDC], T![EMC], T![BI], T![ID], T![EI]];
+
+/// Parses content items until EOF or, inside of a block, until an operator
+/// closing a block.
+pub(super) fn content_items(p: &mut Parser<'_>, in_block: bool) {
+    while !p.at(EOF) {
+        if BLOCK_CLOSING.iter().any(|&it| p.at_contextual_kw(it)) {
+            // Even a mismatched closing operator ends the block, as one of
+            // the enclosing blocks may match it.
+            if in_block {
+                return;
+            }
+            p.err_and_bump("closing operator without matching opening operator");
+            continue;
+        }
+        content_item(p);
+    }
+}
+
+fn content_item(p: &mut Parser<'_>) {
+    if p.at_contextual_kw(T![BT]) {
+        block(p, T![BT], T![ET], TEXT_OBJECT);
+        return;
+    }
+    if p.at_contextual_kw(T![q]) {
+        block(p, T![q], T![Q], GRAPHICS_STATE_BLOCK);
+        return;
+    }
+    if p.at_contextual_kw(T![BI]) {
+        inline_image(p);
+        return;
+    }
+
+    let m = p.start();
+    let has_operands = operands(p);
+
+    if let Some(opening) = [T![BMC], T![BDC]].into_iter().find(|&it| p.at_contextual_kw(it)) {
+        if !p.enter_nested() {
+            too_deeply_nested(p);
+            m.complete(p, ERROR);
+            return;
+        }
+        p.bump_remap(opening);
+        content_items(p, true);
+        expect_contextual_kw(p, T![EMC]);
+        p.leave_nested();
+        m.complete(p, MARKED_CONTENT);
+        return;
+    }
+
+    if at_operator(p) {
+        p.bump_remap(OPERATOR);
+    } else if has_operands {
+        p.error("expected operator");
+    } else {
+        p.err_and_bump("expected operand or operator");
+    }
+    m.complete(p, OPERATION);
+}
+
+fn block(p: &mut Parser<'_>, opening: SyntaxKind, closing: SyntaxKind, kind: SyntaxKind) {
+    let m = p.start();
+    if !p.enter_nested() {
+        too_deeply_nested(p);
+        m.complete(p, ERROR);
+        return;
+    }
+    p.bump_remap(opening);
+    content_items(p, true);
+    expect_contextual_kw(p, closing);
+    p.leave_nested();
+    m.complete(p, kind);
+}
+
+/// Skips a block which would be nested deeper than
+/// the nesting limit, together with everything inside
+/// of it, for the caller to wrap into an `ERROR` node. Like arrays and
+/// dictionaries, the blocks are only counted, so arbitrarily deep nesting
+/// doesn't recurse.
+fn too_deeply_nested(p: &mut Parser<'_>) {
+    p.error(format!("blocks are nested deeper than {} levels", p.nesting_limit()));
+    let mut depth = 0usize;
+    while !p.at(EOF) {
+        if BLOCK_OPENING.iter().any(|&it| p.at_contextual_kw(it)) {
+            depth += 1;
+        } else if BLOCK_CLOSING.iter().any(|&it| p.at_contextual_kw(it)) {
+            depth -= 1;
+        }
+        p.bump_any();
+        if depth == 0 {
+            break;
+        }
+    }
+}
+
+/// An inline image: `BI`, key-value pairs, `ID`, the image data and `EI`.
+fn inline_image(p: &mut Parser<'_>) {
+    let m = p.start();
+    p.bump_remap(T![BI]);
+    while p.at_ts(atom::ATOM_EXPR_FIRST) {
+        expressions::expr(p);
+    }
+    if expect_contextual_kw(p, T![ID]) {
+        p.eat(INLINE_IMAGE_DATA);
+    }
+    expect_contextual_kw(p, T![EI]);
+    m.complete(p, INLINE_IMAGE);
+}
+
+/// Parses the operands preceding an operator, if any.
+fn operands(p: &mut Parser<'_>) -> bool {
+    if !p.at_ts(atom::ATOM_EXPR_FIRST) {
+        return false;
+    }
+    let m = p.start();
+    while p.at_ts(atom::ATOM_EXPR_FIRST) {
+        if expressions::expr(p).is_none() {
+            break;
+        }
+    }
+    m.complete(p, OPERANDS);
+    true
+}
+
+/// Any identifier or keyword which isn't an operand or a block operator can
+/// be an operator.
+fn at_operator(p: &Parser<'_>) -> bool {
+    match p.current() {
+        IDENT => !BLOCK_OPERATORS.iter().any(|&it| p.at_contextual_kw(it)),
+        kind => kind.is_keyword(p.edition()) && !atom::LITERAL_FIRST.contains(kind),
+    }
+}
+
+fn expect_contextual_kw(p: &mut Parser<'_>, kw: SyntaxKind) -> bool {
+    if p.eat_contextual_kw(kw) {
+        return true;
+    }
+    p.error(format!("expected {}", kw.text()));
+    false
+}
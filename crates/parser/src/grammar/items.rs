@@ -1,21 +1,52 @@
 use super::*;
 
-pub(super) fn pdf_body(p: &mut Parser<'_>) {
-    while !(p.at(EOF)) {
-        pdf_item(p);
+/// Tokens which end the body of a revision.
+const BODY_END: TokenSet = TokenSet::new(&[T![xref], T![trailer], T![startxref], T![eof_comment]]);
+
+/// Parses the `%PDF-x.y` line and the binary marker comment following it.
+/// See ISO `32000-2:2020`, Section 7.5.2 File header.
+pub(super) fn pdf_header(p: &mut Parser<'_>) {
+    assert!(p.at(T![header_comment]));
+    let m = p.start();
+    p.bump(T![header_comment]);
+    p.eat(T![binary_comment]);
+    m.complete(p, PDF_HEADER);
+}
+
+/// Parses the revisions of a file: the original one, followed by one for each
+/// incremental update. See ISO `32000-2:2020`, Section 7.5.6.
+pub(super) fn revisions(p: &mut Parser<'_>) {
+    while !p.at(EOF) {
+        revision(p);
     }
 }
 
-pub(super) fn pdf_item(p: &mut Parser<'_>) -> Option<CompletedMarker> {
-    if let Some(m) = indirect_object(p) {
-        return Some(m);
+fn revision(p: &mut Parser<'_>) {
+    let m = p.start();
+    body(p);
+    cross_reference_table(p);
+    file_trailer(p);
+    if p.at(T![eof_comment]) {
+        let eof = p.start();
+        p.bump(T![eof_comment]);
+        eof.complete(p, EOF_MARKER);
     }
+    m.complete(p, REVISION);
+}
 
-    if let Some(m) = cross_reference_table(p) {
-        return Some(m);
+fn body(p: &mut Parser<'_>) {
+    let m = p.start();
+    while !p.at(EOF) && !p.at_ts(BODY_END) {
+        if atom::eat_unmatched_delimiter(p, atom::EXPR_RECOVERY_SET) {
+            continue;
+        }
+        pdf_item(p);
     }
+    m.complete(p, BODY);
+}
 
-    if let Some(m) = file_trailer(p) {
+pub(super) fn pdf_item(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+    if let Some(m) = indirect_object(p) {
         return Some(m);
     }
 
@@ -25,40 +56,35 @@ pub(super) fn pdf_item(p: &mut Parser<'_>) -> Option<CompletedMarker> {
 const X_REF_ENTRTY_TYPES: TokenSet = TokenSet::new(&[T![f], T![n]]);
 
 fn file_trailer(p: &mut Parser<'_>) -> Option<CompletedMarker> {
-    if !p.at(T![trailer]) {
+    if !p.at(T![trailer]) && !p.at(T![startxref]) {
         return None;
     }
 
     let m = p.start();
-    p.bump(T![trailer]);
-
-    match expressions::expr(p) {
-        Some(expr) if expr.kind() == DICTIONARY_EXPR => expr,
-        _ => {
-            m.abandon(p);
-            return None;
+    // Cross-reference streams replace both the table and the trailer
+    // dictionary, only `startxref` remains.
+    if p.eat(T![trailer]) {
+        if p.at(T![<<]) {
+            atom::dictionary_expr(p);
+        } else {
+            p.error("expected trailer dictionary");
         }
-    };
-
-    if !p.at(T![startxref]) {
-        m.abandon(p);
-        return None;
     }
 
-    p.bump(T![startxref]);
-
-    if !p.at(INT_NUMBER) {
-        m.abandon(p);
-        return None;
+    if p.eat(T![startxref]) {
+        if p.at(INT_NUMBER) {
+            atom::atom_expr(p); // byte offset of the last cross-reference section
+        } else {
+            p.error("expected byte offset of the cross-reference section");
+        }
+    } else {
+        p.error("expected startxref");
     }
 
-    atom::atom_expr(p); // byte offset of the last cross-reference section
-
-    // TODO: %%EOF
     Some(m.complete(p, TRAILER))
 }
 
-fn indirect_object(p: &mut Parser<'_>) -> Option<CompletedMarker> {
+pub(super) fn indirect_object(p: &mut Parser<'_>) -> Option<CompletedMarker> {
     let m: Marker = p.start();
 
     if indirect_reference_definition(p).is_none() {
@@ -68,14 +94,16 @@ fn indirect_object(p: &mut Parser<'_>) -> Option<CompletedMarker> {
 
     indirect_object_body(p);
 
-    assert!(p.at(T![endobj]));
-    atom::atom_expr(p); // endobj
+    // A missing `endobj` is reported by validation.
+    err_recover_until(p, T![endobj], "expected `endobj` after the object value");
+    p.eat(T![endobj]);
     return Some(m.complete(p, INDIRECT_OBJECT_EXPR));
 }
 
 fn indirect_object_body(p: &mut Parser<'_>) {
     // When the object body is empty, we can just return, as there is no need to parse anything.
-    if p.at(T![endobj]) {
+    // This includes objects which are directly followed by the next one.
+    if p.at(T![endobj]) || atom::at_value_end(p) {
         return;
     }
 
@@ -85,12 +113,12 @@ fn indirect_object_body(p: &mut Parser<'_>) {
     };
 
     if object_body.kind() == DICTIONARY_EXPR && p.at(T![stream]) {
-        stream_expr(p);
+        stream_expr(p, object_body);
     }
 }
 
 fn indirect_reference_definition(p: &mut Parser<'_>) -> Option<CompletedMarker> {
-    match p.nth_at(0, INT_NUMBER) && p.nth_at(1, INT_NUMBER) && p.nth_at(2, T![obj]) {
+    match atom::at_object_header(p) {
         true => {
             let m = p.start();
             atom::atom_expr(p); // object number
@@ -102,19 +130,62 @@ fn indirect_reference_definition(p: &mut Parser<'_>) -> Option<CompletedMarker>
     }
 }
 
-fn stream_expr(p: &mut Parser<'_>) -> CompletedMarker {
+fn stream_expr(p: &mut Parser<'_>, dictionary: CompletedMarker) -> CompletedMarker {
     assert!(p.at(T![stream]));
-    let m = p.start();
+    // The stream dictionary is a part of the stream object.
+    let m = dictionary.precede(p);
 
     p.bump(T![stream]);
-    while !p.at(EOF) && !p.at(T![endstream]) {
-        p.bump_any();
+    if p.at(RAW_STREAM) {
+        p.bump_remap(STREAM_DATA);
     }
-
+    err_recover_until(p, T![endstream], "expected `endstream` after the stream data");
     p.expect(T![endstream]);
     m.complete(p, STREAM_EXPR)
 }
 
+/// Wraps the tokens up to `kind` into an `ERROR` node. Recovery stops early at
+/// the end of the object, so that a missing `kind` doesn't swallow the
+/// objects which follow.
+fn err_recover_until(p: &mut Parser<'_>, kind: SyntaxKind, message: &str) {
+    let at_end = |p: &Parser<'_>| p.at(EOF) || p.at(kind) || p.at(T![endobj]) || p.at_ts(BODY_END) || atom::at_object_header(p);
+    if at_end(p) {
+        return;
+    }
+    let m = p.start();
+    p.error(message);
+    while !at_end(p) {
+        p.bump_any();
+    }
+    m.complete(p, ERROR);
+}
+
+/// Parses the header of a decoded object stream: pairs of integers, the
+/// object number and the offset of each object stored in the stream.
+/// See ISO `32000-2:2020`, Section 7.5.7 Object Streams.
+///
+/// The header isn't delimited from the objects that follow it, only `/First`
+/// tells where it ends, so the input must be the header alone. Anything but
+/// a pair of integers is an error.
+pub(super) fn object_stream_header(p: &mut Parser<'_>) -> CompletedMarker {
+    let m = p.start();
+    while p.at(INT_NUMBER) && p.nth_at(1, INT_NUMBER) {
+        let entry = p.start();
+        atom::atom_expr(p); // object number
+        atom::atom_expr(p); // byte offset of the object, relative to `/First`
+        entry.complete(p, OBJECT_STREAM_ENTRY);
+    }
+    if !p.at(EOF) {
+        let error = p.start();
+        p.error("expected pairs of integers in the object stream header");
+        while !p.at(EOF) {
+            p.bump_any();
+        }
+        error.complete(p, ERROR);
+    }
+    m.complete(p, OBJECT_STREAM_HEADER)
+}
+
 fn cross_reference_table(p: &mut Parser<'_>) -> Option<CompletedMarker> {
     // The cross-reference table consists of one or more cross-reference sections.
     // Each cross-reference section starts with the xref keyword. See ISO 32000-1:2008, 7.5.4.
@@ -151,7 +222,9 @@ fn cross_reference_section(p: &mut Parser<'_>) -> Option<CompletedMarker> {
 }
 
 fn cross_reference_sub_section(p: &mut Parser<'_>) -> Option<CompletedMarker> {
-    if !p.nth_at(0, INT_NUMBER) && !p.nth_at(1, INT_NUMBER) {
+    // The header of a subsection is the number of its first object and the
+    // number of entries, an entry has its type as the third token.
+    if !(p.nth_at(0, INT_NUMBER) && p.nth_at(1, INT_NUMBER)) || p.nth_at_ts(2, X_REF_ENTRTY_TYPES) {
         return None;
     }
 
@@ -169,13 +242,15 @@ fn cross_reference_sub_section(p: &mut Parser<'_>) -> Option<CompletedMarker> {
 }
 
 fn cross_reference_entry(p: &mut Parser<'_>) -> Option<CompletedMarker> {
-    if !p.nth_at(0, INT_NUMBER) && !p.nth_at(1, INT_NUMBER) && !p.nth_at_ts(2, X_REF_ENTRTY_TYPES) {
+    if !(p.nth_at(0, INT_NUMBER) && p.nth_at(1, INT_NUMBER) && p.nth_at_ts(2, X_REF_ENTRTY_TYPES)) {
         return None;
     }
 
     let m = p.start();
-    atom::atom_expr(p); // object number
+    atom::atom_expr(p); // byte offset, or the next free object number
     atom::atom_expr(p); // generation number
-    atom::atom_expr(p); // 'f' or 'n' keyword
+    let entry_type = p.start();
+    p.bump_any(); // 'f' or 'n' keyword
+    entry_type.complete(p, X_REF_ENTRY_TYPE);
     Some(m.complete(p, X_REF_ENTRY))
 }
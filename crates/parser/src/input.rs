@@ -26,6 +26,13 @@ impl Input {
         self.push_impl(kind, SyntaxKind::EOF)
     }
 
+    /// Pushes an `IDENT` token, which the parser may remap to
+    /// `contextual_kind` (or `EOF` if it isn't a contextual keyword).
+    #[inline]
+    pub fn push_ident(&mut self, contextual_kind: SyntaxKind) {
+        self.push_impl(SyntaxKind::IDENT, contextual_kind)
+    }
+
     #[inline]
     fn push_impl(&mut self, kind: SyntaxKind, contextual_kind: SyntaxKind) {
         let idx = self.len();
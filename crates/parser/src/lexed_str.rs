@@ -10,8 +10,12 @@
 
 // TODO: adjust docs
 
+mod stream_length;
+
 use std::ops;
 
+use self::stream_length::XRefOffsets;
+pub use self::stream_length::{DeclaredLength, declared_stream_length};
 use crate::{
     Edition,
     SyntaxKind::{self, *},
@@ -33,12 +37,31 @@ struct LexError {
 impl<'a> LexedStr<'a> {
     pub fn new(edition: Edition, text: &'a [u8]) -> LexedStr<'a> {
         let _p = tracing::info_span!("LexedStr::new").entered();
+        LexedStr::lex(edition, text, lexer::Cursor::new(text))
+    }
+
+    /// Like [`LexedStr::new`], for the decoded data of a content stream, where
+    /// the `ID` operator starts the data of an inline image.
+    pub fn new_content_stream(edition: Edition, text: &'a [u8]) -> LexedStr<'a> {
+        let _p = tracing::info_span!("LexedStr::new_content_stream").entered();
+        LexedStr::lex(edition, text, lexer::Cursor::new_content_stream(text))
+    }
+
+    fn lex(edition: Edition, text: &'a [u8], mut cursor: lexer::Cursor<'a>) -> LexedStr<'a> {
         let mut conv = Converter::new(edition, text);
-        let mut tokenized = lexer::tokenize(text);
 
-        while let Some(token) = tokenized.next() {
+        loop {
+            let token = cursor.advance_token();
+            if token.kind == lexer::TokenKind::Eof {
+                break;
+            }
             let token_text = &text[conv.offset..][..token.len as usize];
             conv.extend_token(&token.kind, token_text);
+
+            if conv.res.kind.last() == Some(&T![stream]) {
+                conv.stream_length = conv.declared_stream_length();
+                cursor.set_stream_length(conv.stream_length.and_then(|it| usize::try_from(it).ok()));
+            }
         }
 
         conv.finalize_with_eof()
@@ -117,6 +140,23 @@ impl<'a> LexedStr<'a> {
         self.error.iter().map(|it| (it.token as usize, it.msg.as_str()))
     }
 
+    /// Finds the `N G obj` headers of indirect objects by their tokens alone,
+    /// as `(object number, generation, offset)` in text order.
+    ///
+    /// Unlike the parsed tree, this doesn't depend on the objects being
+    /// well-formed, which helps when reconstructing damaged files.
+    pub fn object_headers(&self) -> impl Iterator<Item = (u64, u64, usize)> + '_ {
+        let significant: Vec<usize> = (0..self.len()).filter(|&i| !self.kind(i).is_trivia()).collect();
+        let int = |i: usize| std::str::from_utf8(self.text(i)).ok()?.parse::<u64>().ok();
+        (0..significant.len().saturating_sub(2)).filter_map(move |j| {
+            let [number, generation, keyword] = [significant[j], significant[j + 1], significant[j + 2]];
+            if self.kind(number) != INT_NUMBER || self.kind(generation) != INT_NUMBER || self.kind(keyword) != T![obj] {
+                return None;
+            }
+            Some((int(number)?, int(generation)?, self.text_start(number)))
+        })
+    }
+
     fn push(&mut self, kind: SyntaxKind, offset: usize) {
         self.kind.push(kind);
         self.start.push(offset as u32);
@@ -127,6 +167,10 @@ struct Converter<'a> {
     res: LexedStr<'a>,
     offset: usize,
     edition: Edition,
+    /// The declared length of the data of the stream being lexed.
+    stream_length: Option<u64>,
+    /// Built on demand, when the first indirect `/Length` is found.
+    xref_offsets: Option<XRefOffsets>,
 }
 
 impl<'a> Converter<'a> {
@@ -140,9 +184,44 @@ impl<'a> Converter<'a> {
             },
             offset: 0,
             edition,
+            stream_length: None,
+            xref_offsets: None,
         }
     }
 
+    fn declared_stream_length(&mut self) -> Option<u64> {
+        let (text, start, offset) = (self.res.text, &self.res.start, self.offset);
+        let token_text = |i: usize| &text[start[i] as usize..start.get(i + 1).map_or(offset, |&it| it as usize)];
+        match declared_stream_length(&self.res.kind, token_text)? {
+            DeclaredLength::Direct(length) => Some(length),
+            DeclaredLength::Indirect { object_number, generation } => {
+                self.xref_offsets
+                    .get_or_insert_with(|| XRefOffsets::new(text))
+                    .resolve(text, object_number, generation)
+            }
+        }
+    }
+
+    /// Comments are trivia, except for the ones which make up the file
+    /// header and the end-of-file marker.
+    /// See ISO `32000-2:2020`, Sections 7.5.2 File header and 7.5.5 File trailer.
+    fn comment_kind(&self, text: &[u8]) -> SyntaxKind {
+        let kinds = &self.res.kind;
+        if text.starts_with(b"%PDF-") && kinds.iter().all(|it| it.is_trivia()) {
+            return HEADER_COMMENT;
+        }
+
+        let previous = kinds.iter().rev().find(|&&it| it != WHITESPACE && it != NEWLINE);
+        if previous == Some(&HEADER_COMMENT) && text.iter().filter(|&&b| b >= 128).count() >= 4 {
+            return BINARY_COMMENT;
+        }
+
+        if text.trim_ascii_end() == b"%%EOF" {
+            return EOF_COMMENT;
+        }
+        COMMENT
+    }
+
     fn finalize_with_eof(mut self) -> LexedStr<'a> {
         self.res.push(EOF, self.offset);
         self.res
@@ -164,21 +243,24 @@ impl<'a> Converter<'a> {
         // We drop some useful information here, namely the exact text of the token.
         // Storing that info in `SyntaxKind` is not possible due to its layout requirements of
         // being `u16` that come from `rowan::SyntaxKind`.
-        let err = "";
+        let mut err = "";
 
         let syntax_kind = {
             match kind {
-                lexer::TokenKind::Unknown => ERROR,
+                lexer::TokenKind::Unknown => {
+                    err = "Unknown character, expected a PDF token";
+                    ERROR
+                }
                 lexer::TokenKind::Eol => NEWLINE,
                 lexer::TokenKind::Whitespace => WHITESPACE,
-                lexer::TokenKind::Comment => COMMENT,
+                lexer::TokenKind::Comment => self.comment_kind(token_text),
                 lexer::TokenKind::Ident => {
                     let token_text_str: String = token_text
                         .iter()
                         .map(|&c| if c.is_ascii() { (c as char).to_string() } else { format!("\\x{:02x}", c) })
                         .collect();
 
-                    SyntaxKind::from_keyword(token_text_str.as_str(), self.edition).unwrap_or(ERROR)
+                    SyntaxKind::from_keyword(token_text_str.as_str(), self.edition).unwrap_or(IDENT)
                 }
                 lexer::TokenKind::Literal { kind, .. } => {
                     self.extend_literal(token_text.len(), kind);
@@ -188,7 +270,12 @@ impl<'a> Converter<'a> {
                 lexer::TokenKind::CloseBracket => T![']'],
                 lexer::TokenKind::OpenDict => T![<<],
                 lexer::TokenKind::CloseDict => T![>>],
-                lexer::TokenKind::RawStreamData => RAW_STREAM,
+                lexer::TokenKind::RawStreamData => {
+                    let err = self.stream_length.take().and_then(|declared| stream_length_error(declared, token_text));
+                    self.push(RAW_STREAM, token_text.len(), err.as_deref());
+                    return;
+                }
+                lexer::TokenKind::InlineImageData => INLINE_IMAGE_DATA,
                 lexer::TokenKind::Eof => EOF,
             }
         };
@@ -198,17 +285,49 @@ impl<'a> Converter<'a> {
     }
 
     fn extend_literal(&mut self, len: usize, kind: &lexer::LiteralKind) {
-        let err = "";
+        let mut err = "";
 
         let syntax_kind = match *kind {
             lexer::LiteralKind::Int => INT_NUMBER,
             lexer::LiteralKind::Real => REAL_NUMBER,
-            lexer::LiteralKind::Name => NAME,
-            lexer::LiteralKind::LiteralString => LITERAL_STRING,
-            lexer::LiteralKind::HexString => HEX_STRING,
+            lexer::LiteralKind::Name { terminated } => {
+                if !terminated {
+                    err = "Missing hexadecimal digits after `#` in the name";
+                }
+                NAME
+            }
+            lexer::LiteralKind::LiteralString { terminated } => {
+                if !terminated {
+                    err = "Missing trailing `)` symbol to terminate the literal string";
+                }
+                LITERAL_STRING
+            }
+            lexer::LiteralKind::HexString { terminated } => {
+                if !terminated {
+                    err = "Missing trailing `>` symbol to terminate the hexadecimal string";
+                }
+                HEX_STRING
+            }
         };
 
         let err = if err.is_empty() { None } else { Some(err) };
         self.push(syntax_kind, len, err);
     }
 }
+
+/// Checks the declared length of a stream against its data, which may be
+/// followed by whitespace but nothing else before `endstream`.
+fn stream_length_error(declared: u64, raw_stream: &[u8]) -> Option<String> {
+    let data = raw_stream
+        .strip_prefix(b"\r\n")
+        .or_else(|| raw_stream.strip_prefix(b"\n"))
+        .unwrap_or(raw_stream);
+    let after_data = usize::try_from(declared).ok().and_then(|it| data.get(it..));
+    if after_data.is_some_and(|it| it.iter().all(|&b| matches!(b, b'\0' | b'\t' | b'\n' | b'\x0C' | b'\r' | b' '))) {
+        return None;
+    }
+
+    let data = data.strip_suffix(b"\n").unwrap_or(data);
+    let data = data.strip_suffix(b"\r").unwrap_or(data);
+    Some(format!("stream /Length is {declared}, but the stream data is {} bytes long", data.len()))
+}
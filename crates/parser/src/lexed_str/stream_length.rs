@@ -0,0 +1,183 @@
+//! Finding the declared length of stream data while lexing.
+//!
+//! The `/Length` entry of a stream dictionary is either a direct integer or an
+//! indirect reference to one. In the latter case the referenced object is
+//! looked up through the cross-reference table at the end of the file.
+//!
+//! See ISO `32000-2:2020`, Section 7.3.8.2 Stream extent.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{SyntaxKind, T};
+
+/// The `/Length` entry of a stream dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclaredLength {
+    Direct(u64),
+    Indirect { object_number: u64, generation: u64 },
+}
+
+/// Finds `/Length` in the dictionary preceding the `stream` keyword, which is
+/// the last of `kinds`, the way the lexer does when it reaches the keyword.
+pub fn declared_stream_length<'t>(kinds: &[SyntaxKind], text: impl Fn(usize) -> &'t [u8]) -> Option<DeclaredLength> {
+    let significant = |range: std::ops::Range<usize>| range.filter(|&i| !kinds[i].is_trivia());
+
+    let (&stream, rest) = kinds.split_last()?;
+    debug_assert_eq!(stream, T![stream]);
+    let r_dict = significant(0..rest.len()).next_back().filter(|&i| kinds[i] == T![>>])?;
+
+    let mut depth = 0;
+    let l_dict = (0..r_dict).rev().find(|&i| {
+        match kinds[i] {
+            T![>>] => depth += 1,
+            T![<<] if depth == 0 => return true,
+            T![<<] => depth -= 1,
+            _ => {}
+        }
+        false
+    })?;
+
+    let mut depth = 0;
+    let mut tokens = significant(l_dict + 1..r_dict);
+    while let Some(i) = tokens.next() {
+        match kinds[i] {
+            T![<<] | T!['['] => depth += 1,
+            T![>>] | T![']'] => depth -= 1,
+            SyntaxKind::NAME if depth == 0 && text(i) == b"/Length" => {
+                let mut value = tokens.clone().map(|i| (kinds[i], int(text(i))));
+                return match (value.next()?, value.next(), value.next()) {
+                    ((SyntaxKind::INT_NUMBER, Some(object_number)), Some((SyntaxKind::INT_NUMBER, Some(generation))), Some((T![R], _))) => {
+                        Some(DeclaredLength::Indirect { object_number, generation })
+                    }
+                    ((SyntaxKind::INT_NUMBER, Some(length)), _, _) => Some(DeclaredLength::Direct(length)),
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The byte offsets of the objects listed in the cross-reference tables of a
+/// file, following the `/Prev` chain from the last `startxref`.
+///
+/// Cross-reference streams are not supported, as their data would have to be
+/// decoded first.
+pub(super) struct XRefOffsets {
+    offsets: HashMap<(u64, u64), usize>,
+}
+
+impl XRefOffsets {
+    pub(super) fn new(text: &[u8]) -> XRefOffsets {
+        let _p = tracing::info_span!("XRefOffsets::new").entered();
+        let mut offsets = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut next = startxref(text);
+        while let Some(offset) = next {
+            if !visited.insert(offset) {
+                break;
+            }
+            next = read_xref_section(text, offset, &mut offsets);
+        }
+        XRefOffsets { offsets }
+    }
+
+    /// Resolves an indirect `/Length` to the integer stored in the referenced
+    /// object.
+    pub(super) fn resolve(&self, text: &[u8], object_number: u64, generation: u64) -> Option<u64> {
+        let &offset = self.offsets.get(&(object_number, generation))?;
+        let mut tokens = significant_tokens(text.get(offset..)?);
+        if next_int(&mut tokens)? != object_number || next_int(&mut tokens)? != generation {
+            return None;
+        }
+        match tokens.next()? {
+            (lexer::TokenKind::Ident, b"obj") => {}
+            _ => return None,
+        }
+        next_int(&mut tokens)
+    }
+}
+
+/// The offset following the last `startxref` keyword.
+fn startxref(text: &[u8]) -> Option<usize> {
+    const KEYWORD: &[u8] = b"startxref";
+    let keyword = text.windows(KEYWORD.len()).rposition(|it| it == KEYWORD)?;
+    match significant_tokens(&text[keyword + KEYWORD.len()..]).next()? {
+        (kind, text) if is_int(&kind) => usize::try_from(int(text)?).ok(),
+        _ => None,
+    }
+}
+
+/// Records the in-use entries of the `xref` section at `offset`, unless
+/// already known from a newer section, and returns the `/Prev` offset of its
+/// trailer.
+fn read_xref_section(text: &[u8], offset: usize, offsets: &mut HashMap<(u64, u64), usize>) -> Option<usize> {
+    let mut tokens = significant_tokens(text.get(offset..)?).peekable();
+    match tokens.next()? {
+        (lexer::TokenKind::Ident, b"xref") => {}
+        _ => return None,
+    }
+
+    while let Some((kind, _)) = tokens.peek()
+        && is_int(kind)
+    {
+        let (first, count) = (next_int(&mut tokens)?, next_int(&mut tokens)?);
+        for object_number in first..first.checked_add(count)? {
+            let (entry_offset, generation) = (next_int(&mut tokens)?, next_int(&mut tokens)?);
+            match tokens.next()? {
+                (lexer::TokenKind::Ident, b"n") => {
+                    offsets.entry((object_number, generation)).or_insert(usize::try_from(entry_offset).ok()?);
+                }
+                (lexer::TokenKind::Ident, b"f") => {}
+                _ => return None,
+            }
+        }
+    }
+
+    match (tokens.next()?, tokens.next()?) {
+        ((lexer::TokenKind::Ident, b"trailer"), (lexer::TokenKind::OpenDict, _)) => {}
+        _ => return None,
+    }
+    let mut depth = 0;
+    while let Some((kind, text)) = tokens.next() {
+        match kind {
+            lexer::TokenKind::OpenDict | lexer::TokenKind::OpenBracket => depth += 1,
+            lexer::TokenKind::CloseDict if depth == 0 => return None,
+            lexer::TokenKind::CloseDict | lexer::TokenKind::CloseBracket => depth -= 1,
+            lexer::TokenKind::Literal {
+                kind: lexer::LiteralKind::Name { .. },
+            } if depth == 0 && text == b"/Prev" => {
+                return match tokens.next()? {
+                    (kind, text) if is_int(&kind) => usize::try_from(int(text)?).ok(),
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn significant_tokens(text: &[u8]) -> impl Iterator<Item = (lexer::TokenKind, &[u8])> {
+    let mut offset = 0;
+    lexer::tokenize(text)
+        .map(move |token| {
+            let token_text = &text[offset..offset + token.len as usize];
+            offset += token.len as usize;
+            (token.kind, token_text)
+        })
+        .filter(|(kind, _)| !matches!(kind, lexer::TokenKind::Whitespace | lexer::TokenKind::Eol | lexer::TokenKind::Comment))
+}
+
+fn next_int<'t>(tokens: &mut impl Iterator<Item = (lexer::TokenKind, &'t [u8])>) -> Option<u64> {
+    tokens.next().filter(|(kind, _)| is_int(kind)).and_then(|(_, text)| int(text))
+}
+
+fn is_int(kind: &lexer::TokenKind) -> bool {
+    *kind == lexer::TokenKind::Literal { kind: lexer::LiteralKind::Int }
+}
+
+fn int(text: &[u8]) -> Option<u64> {
+    std::str::from_utf8(text).ok()?.parse().ok()
+}
@@ -42,8 +42,9 @@ pub use edition::Edition;
 
 pub use crate::{
     input::Input,
-    lexed_str::LexedStr,
+    lexed_str::{DeclaredLength, LexedStr, declared_stream_length},
     output::{Output, Step},
+    parser::NESTING_LIMIT,
     shortcuts::StrStep,
     syntax_kind::SyntaxKind,
 };
@@ -61,7 +62,12 @@ pub use crate::{
 #[derive(Debug)]
 pub enum TopEntryPoint {
     PdfDocument,
-    // StreamStmts,
+    /// The header of the decoded data of an object stream (`/Type /ObjStm`),
+    /// i.e. the bytes before `/First`. The objects after it are parsed one by
+    /// one with [`TopEntryPoint::Expr`].
+    ObjectStream,
+    /// The decoded data of a content stream, e.g. the `/Contents` of a page.
+    ContentStream,
     // Pattern,
     // Type,
     Expr,
@@ -69,15 +75,23 @@ pub enum TopEntryPoint {
 
 impl TopEntryPoint {
     pub fn parse(&self, input: &Input, edition: Edition) -> Output {
+        self.parse_with_nesting_limit(input, edition, NESTING_LIMIT.inner())
+    }
+
+    /// Like [`TopEntryPoint::parse`], but with arrays, dictionaries and the
+    /// blocks of content streams nested at most `nesting_limit` levels deep
+    /// instead of [`NESTING_LIMIT`].
+    pub fn parse_with_nesting_limit(&self, input: &Input, edition: Edition, nesting_limit: usize) -> Output {
         let _p = tracing::info_span!("TopEntryPoint::parse", ?self).entered();
         let entry_point: fn(&'_ mut parser::Parser<'_>) = match self {
             TopEntryPoint::PdfDocument => grammar::entry::top::pdf_document,
-            // TopEntryPoint::StreamStmts => grammar::entry::top::macro_stmts,
+            TopEntryPoint::ObjectStream => grammar::entry::top::object_stream,
+            TopEntryPoint::ContentStream => grammar::entry::top::content_stream,
             // TopEntryPoint::Pattern => grammar::entry::top::pattern,
             // TopEntryPoint::Type => grammar::entry::top::type_,
             TopEntryPoint::Expr => grammar::entry::top::expr,
         };
-        let mut p = parser::Parser::new(input, edition);
+        let mut p = parser::Parser::new(input, edition, nesting_limit);
         entry_point(&mut p);
         let events = p.finish();
         let res = event::process(events);
@@ -101,3 +115,35 @@ impl TopEntryPoint {
         res
     }
 }
+
+/// A parsing function for a specific self-delimited node.
+///
+/// Used by incremental reparsing to parse only the text of an edited
+/// array, dictionary or indirect object instead of the whole file.
+pub struct Reparser(fn(&mut parser::Parser<'_>));
+
+impl Reparser {
+    /// If the node is self-delimited (it starts and ends with dedicated
+    /// tokens), return the corresponding `Reparser`.
+    pub fn for_node(node: SyntaxKind) -> Option<Reparser> {
+        grammar::reparser(node).map(Reparser)
+    }
+
+    /// Re-parse given tokens using this `Reparser`.
+    ///
+    /// Tokens must start with the opening token of the node (`[`, `<<` or an
+    /// object identifier) and end with the matching closing token.
+    pub fn parse(self, tokens: &Input, edition: Edition) -> Output {
+        self.parse_with_nesting_limit(tokens, edition, NESTING_LIMIT.inner())
+    }
+
+    /// Like [`Reparser::parse`], with the `nesting_limit` the whole input was
+    /// parsed with, see [`TopEntryPoint::parse_with_nesting_limit`].
+    pub fn parse_with_nesting_limit(self, tokens: &Input, edition: Edition, nesting_limit: usize) -> Output {
+        let Reparser(r) = self;
+        let mut p = parser::Parser::new(tokens, edition, nesting_limit);
+        r(&mut p);
+        let events = p.finish();
+        event::process(events)
+    }
+}
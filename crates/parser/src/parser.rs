@@ -27,18 +27,35 @@ pub(crate) struct Parser<'t> {
     pos: usize,
     events: Vec<Event>,
     steps: Cell<u32>,
+    depth: usize,
+    nesting_limit: usize,
     edition: Edition,
 }
 
 static PARSER_STEP_LIMIT: Limit = Limit::new(15_000_000);
 
+/// The default of how deeply arrays, dictionaries and the blocks of content
+/// streams may be nested inside of each other. Anything nested deeper is
+/// parsed as a flat `ERROR` node.
+///
+/// Other limits can be given to
+/// [`TopEntryPoint::parse_with_nesting_limit`](crate::TopEntryPoint::parse_with_nesting_limit).
+/// With the `tracking` feature, this records the deepest nesting seen with
+/// any limit.
+#[cfg(not(feature = "tracking"))]
+pub static NESTING_LIMIT: Limit = Limit::new(256);
+#[cfg(feature = "tracking")]
+pub static NESTING_LIMIT: Limit = Limit::new_tracking(256);
+
 impl<'t> Parser<'t> {
-    pub(super) fn new(inp: &'t Input, edition: Edition) -> Parser<'t> {
+    pub(super) fn new(inp: &'t Input, edition: Edition, nesting_limit: usize) -> Parser<'t> {
         Parser {
             inp,
             pos: 0,
             events: Vec::new(),
             steps: Cell::new(0),
+            depth: 0,
+            nesting_limit,
             edition,
         }
     }
@@ -99,7 +116,6 @@ impl<'t> Parser<'t> {
         true
     }
 
-    #[allow(dead_code)]
     pub(crate) fn eat_contextual_kw(&mut self, kind: SyntaxKind) -> bool {
         if !self.at_contextual_kw(kind) {
             return false;
@@ -124,6 +140,31 @@ impl<'t> Parser<'t> {
         self.inp.contextual_kind(self.pos + n) == kw
     }
 
+    /// Enters an array, a dictionary or a block. Returns `false` if that would
+    /// nest them deeper than the nesting limit, in which case the depth is left
+    /// as is.
+    pub(crate) fn enter_nested(&mut self) -> bool {
+        if self.depth >= self.nesting_limit {
+            return false;
+        }
+        self.depth += 1;
+        // The bound is `self.nesting_limit`, which may be above `NESTING_LIMIT`.
+        #[cfg(feature = "tracking")]
+        NESTING_LIMIT.record(self.depth);
+        true
+    }
+
+    /// How deeply arrays, dictionaries and blocks may be nested, see
+    /// [`NESTING_LIMIT`].
+    pub(crate) fn nesting_limit(&self) -> usize {
+        self.nesting_limit
+    }
+
+    /// Leaves an array, a dictionary or a block entered with [`Parser::enter_nested`].
+    pub(crate) fn leave_nested(&mut self) {
+        self.depth -= 1;
+    }
+
     /// Starts a new node in the syntax tree. All nodes and tokens
     /// consumed between the `start` and the corresponding `Marker::complete`
     /// belong to the same node.
@@ -25,12 +25,15 @@ pub enum StrStep<'a> {
 }
 
 impl LexedStr<'_> {
-    pub fn to_input(&self, _edition: Edition) -> crate::Input {
+    pub fn to_input(&self, edition: Edition) -> crate::Input {
         let _p = tracing::info_span!("LexedStr::to_input").entered();
         let mut res = crate::Input::default();
         for i in 0..self.len() {
             let kind = self.kind(i);
-            if !kind.is_trivia() {
+            if kind == IDENT {
+                let text = std::str::from_utf8(self.text(i)).unwrap_or_default();
+                res.push_ident(SyntaxKind::from_contextual_keyword(text, edition).unwrap_or(EOF));
+            } else if !kind.is_trivia() {
                 res.push(kind);
             }
         }
@@ -119,16 +122,17 @@ impl Builder<'_, '_> {
     }
 
     fn exit(&mut self, kind: SyntaxKind) {
-        let n_trivias = (self.pos..self.lexed.len()).take_while(|&it| self.lexed.kind(it).is_trivia()).count();
-        let trailing_trivias = self.pos..self.pos + n_trivias;
-        let n_attached_trivias = n_attached_trailing_trivias(kind, trailing_trivias.map(|it| (self.lexed.kind(it), self.lexed.text(it))));
-        self.eat_n_trivias(n_attached_trivias); // eat trailing trivias, so they are attached inside the node before exiting
-
+        // Close the previous node first, so trailing trivias end up in the node being exited rather than in its last child.
         match mem::replace(&mut self.state, State::PendingExit) {
             State::PendingEnter => unreachable!(),
             State::PendingExit => (self.sink)(StrStep::Exit),
             State::Normal => (),
         }
+
+        let n_trivias = (self.pos..self.lexed.len()).take_while(|&it| self.lexed.kind(it).is_trivia()).count();
+        let trailing_trivias = self.pos..self.pos + n_trivias;
+        let n_attached_trivias = n_attached_trailing_trivias(kind, trailing_trivias.map(|it| (self.lexed.kind(it), self.lexed.text(it))));
+        self.eat_n_trivias(n_attached_trivias); // eat trailing trivias, so they are attached inside the node before exiting
     }
 
     fn eat_trivias(&mut self) {
@@ -162,9 +166,10 @@ fn n_attached_leading_trivias<'a>(kind: SyntaxKind, _trivias: impl Iterator<Item
     }
 }
 
-fn n_attached_trailing_trivias<'a>(kind: SyntaxKind, _trivias: impl Iterator<Item = (SyntaxKind, &'a [u8])>) -> usize {
+fn n_attached_trailing_trivias<'a>(kind: SyntaxKind, mut trivias: impl Iterator<Item = (SyntaxKind, &'a [u8])>) -> usize {
     match kind {
-        X_REF_ENTRY => 1,
+        // An entry at the very end of the input has no trivia to attach.
+        X_REF_ENTRY => usize::from(trivias.next().is_some()),
         _ => 0,
     }
 }
@@ -1,6 +1,6 @@
 //! Generated by `cargo xtask codegen grammar`, do not edit by hand.
 
-#![allow(bad_style, missing_docs, unreachable_pub)]
+#![allow(bad_style, missing_docs, unreachable_pub, clippy::match_like_matches_macro)]
 use crate::Edition;
 #[doc = r" The kind of syntax node, e.g. `IDENT`, `USE_KW`, or `STRUCT`."]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
@@ -27,30 +27,60 @@ pub enum SyntaxKind {
     TRAILER_KW,
     TRUE_KW,
     XREF_KW,
+    BDC_KW,
+    BI_KW,
+    BMC_KW,
+    BT_KW,
+    EI_KW,
+    EMC_KW,
+    ET_KW,
+    ID_KW,
+    RESTORE_KW,
+    SAVE_KW,
     HEX_STRING,
     INT_NUMBER,
     LITERAL_STRING,
     NAME,
     REAL_NUMBER,
+    BINARY_COMMENT,
     COMMENT,
+    EOF_COMMENT,
     ERROR,
+    HEADER_COMMENT,
+    IDENT,
+    INLINE_IMAGE_DATA,
     NEWLINE,
+    OPERATOR,
     RAW_STREAM,
     STREAM_DATA,
     WHITESPACE,
     ARRAY_EXPR,
     BODY,
+    CONTENT_ITEM,
+    CONTENT_STREAM,
     DICTIONARY_EXPR,
     DICTIONARY_ITEM_EXPR,
     DICTIONARY_ITEM_KEY_EXPR,
     DICTIONARY_ITEM_VALUE_EXPR,
+    EOF_MARKER,
     EXPR,
+    GRAPHICS_STATE_BLOCK,
     INDIRECT_OBJECT_EXPR,
     INDIRECT_OBJECT_ID,
     INDIRECT_REFERENCE_EXPR,
+    INLINE_IMAGE,
     LITERAL,
+    MARKED_CONTENT,
+    OBJECT_STREAM,
+    OBJECT_STREAM_ENTRY,
+    OBJECT_STREAM_HEADER,
+    OPERANDS,
+    OPERATION,
     PDF_DOCUMENT,
+    PDF_HEADER,
+    REVISION,
     STREAM_EXPR,
+    TEXT_OBJECT,
     TRAILER,
     X_REF_ENTRY,
     X_REF_ENTRY_TYPE,
@@ -75,26 +105,46 @@ impl SyntaxKind {
             | REAL_NUMBER
             | ARRAY_EXPR
             | BODY
+            | CONTENT_ITEM
+            | CONTENT_STREAM
             | DICTIONARY_EXPR
             | DICTIONARY_ITEM_EXPR
             | DICTIONARY_ITEM_KEY_EXPR
             | DICTIONARY_ITEM_VALUE_EXPR
+            | EOF_MARKER
             | EXPR
+            | GRAPHICS_STATE_BLOCK
             | INDIRECT_OBJECT_EXPR
             | INDIRECT_OBJECT_ID
             | INDIRECT_REFERENCE_EXPR
+            | INLINE_IMAGE
             | LITERAL
+            | MARKED_CONTENT
+            | OBJECT_STREAM
+            | OBJECT_STREAM_ENTRY
+            | OBJECT_STREAM_HEADER
+            | OPERANDS
+            | OPERATION
             | PDF_DOCUMENT
+            | PDF_HEADER
+            | REVISION
             | STREAM_EXPR
+            | TEXT_OBJECT
             | TRAILER
             | X_REF_ENTRY
             | X_REF_ENTRY_TYPE
             | X_REF_SECTION
             | X_REF_SUBSECTION
             | X_REF_TABLE
+            | BINARY_COMMENT
             | COMMENT
+            | EOF_COMMENT
             | ERROR
+            | HEADER_COMMENT
+            | IDENT
+            | INLINE_IMAGE_DATA
             | NEWLINE
+            | OPERATOR
             | RAW_STREAM
             | STREAM_DATA
             | WHITESPACE => panic!("no text for these `SyntaxKind`s"),
@@ -115,6 +165,16 @@ impl SyntaxKind {
             TRAILER_KW => "trailer",
             TRUE_KW => "true",
             XREF_KW => "xref",
+            BDC_KW => "BDC",
+            BI_KW => "BI",
+            BMC_KW => "BMC",
+            BT_KW => "BT",
+            EI_KW => "EI",
+            EMC_KW => "EMC",
+            ET_KW => "ET",
+            ID_KW => "ID",
+            RESTORE_KW => "Q",
+            SAVE_KW => "q",
         }
     }
     #[doc = r" Checks whether this syntax kind is a strict keyword for the given edition."]
@@ -133,6 +193,16 @@ impl SyntaxKind {
     #[allow(unused_variables)]
     pub fn is_contextual_keyword(self, edition: Edition) -> bool {
         match self {
+            BDC_KW => true,
+            BI_KW => true,
+            BMC_KW => true,
+            BT_KW => true,
+            EI_KW => true,
+            EMC_KW => true,
+            ET_KW => true,
+            ID_KW => true,
+            RESTORE_KW => true,
+            SAVE_KW => true,
             _ => false,
         }
     }
@@ -143,6 +213,16 @@ impl SyntaxKind {
             self,
             R_KW | ENDOBJ_KW | ENDSTREAM_KW | F_KW | FALSE_KW | N_KW | NULL_KW | OBJ_KW | STARTXREF_KW | STREAM_KW | TRAILER_KW | TRUE_KW | XREF_KW
         ) || match self {
+            BDC_KW => true,
+            BI_KW => true,
+            BMC_KW => true,
+            BT_KW => true,
+            EI_KW => true,
+            EMC_KW => true,
+            ET_KW => true,
+            ID_KW => true,
+            RESTORE_KW => true,
+            SAVE_KW => true,
             _ => false,
         }
     }
@@ -172,6 +252,16 @@ impl SyntaxKind {
     pub fn from_contextual_keyword(ident: &str, edition: Edition) -> Option<SyntaxKind> {
         #[allow(unused_variables)]
         let kw = match ident {
+            "BDC" => BDC_KW,
+            "BI" => BI_KW,
+            "BMC" => BMC_KW,
+            "BT" => BT_KW,
+            "EI" => EI_KW,
+            "EMC" => EMC_KW,
+            "ET" => ET_KW,
+            "ID" => ID_KW,
+            "Q" => RESTORE_KW,
+            "q" => SAVE_KW,
             _ => return None,
         };
         #[allow(unreachable_code)]
@@ -187,4 +277,4 @@ impl SyntaxKind {
     }
 }
 #[macro_export]
-macro_rules ! T { ['['] => { $ crate :: SyntaxKind :: L_BRACK } ; [']'] => { $ crate :: SyntaxKind :: R_BRACK } ; [<<] => { $ crate :: SyntaxKind :: L_DICT } ; [>>] => { $ crate :: SyntaxKind :: R_DICT } ; [R] => { $ crate :: SyntaxKind :: R_KW } ; [endobj] => { $ crate :: SyntaxKind :: ENDOBJ_KW } ; [endstream] => { $ crate :: SyntaxKind :: ENDSTREAM_KW } ; [f] => { $ crate :: SyntaxKind :: F_KW } ; [false] => { $ crate :: SyntaxKind :: FALSE_KW } ; [n] => { $ crate :: SyntaxKind :: N_KW } ; [null] => { $ crate :: SyntaxKind :: NULL_KW } ; [obj] => { $ crate :: SyntaxKind :: OBJ_KW } ; [startxref] => { $ crate :: SyntaxKind :: STARTXREF_KW } ; [stream] => { $ crate :: SyntaxKind :: STREAM_KW } ; [trailer] => { $ crate :: SyntaxKind :: TRAILER_KW } ; [true] => { $ crate :: SyntaxKind :: TRUE_KW } ; [xref] => { $ crate :: SyntaxKind :: XREF_KW } ; [stream_data] => { $ crate :: SyntaxKind :: STREAM_DATA } ; }
+macro_rules ! T { ['['] => { $ crate :: SyntaxKind :: L_BRACK } ; [']'] => { $ crate :: SyntaxKind :: R_BRACK } ; [<<] => { $ crate :: SyntaxKind :: L_DICT } ; [>>] => { $ crate :: SyntaxKind :: R_DICT } ; [R] => { $ crate :: SyntaxKind :: R_KW } ; [endobj] => { $ crate :: SyntaxKind :: ENDOBJ_KW } ; [endstream] => { $ crate :: SyntaxKind :: ENDSTREAM_KW } ; [f] => { $ crate :: SyntaxKind :: F_KW } ; [false] => { $ crate :: SyntaxKind :: FALSE_KW } ; [n] => { $ crate :: SyntaxKind :: N_KW } ; [null] => { $ crate :: SyntaxKind :: NULL_KW } ; [obj] => { $ crate :: SyntaxKind :: OBJ_KW } ; [startxref] => { $ crate :: SyntaxKind :: STARTXREF_KW } ; [stream] => { $ crate :: SyntaxKind :: STREAM_KW } ; [trailer] => { $ crate :: SyntaxKind :: TRAILER_KW } ; [true] => { $ crate :: SyntaxKind :: TRUE_KW } ; [xref] => { $ crate :: SyntaxKind :: XREF_KW } ; [BDC] => { $ crate :: SyntaxKind :: BDC_KW } ; [BI] => { $ crate :: SyntaxKind :: BI_KW } ; [BMC] => { $ crate :: SyntaxKind :: BMC_KW } ; [BT] => { $ crate :: SyntaxKind :: BT_KW } ; [EI] => { $ crate :: SyntaxKind :: EI_KW } ; [EMC] => { $ crate :: SyntaxKind :: EMC_KW } ; [ET] => { $ crate :: SyntaxKind :: ET_KW } ; [ID] => { $ crate :: SyntaxKind :: ID_KW } ; [Q] => { $ crate :: SyntaxKind :: RESTORE_KW } ; [q] => { $ crate :: SyntaxKind :: SAVE_KW } ; [stream_data] => { $ crate :: SyntaxKind :: STREAM_DATA } ; [operator] => { $ crate :: SyntaxKind :: OPERATOR } ; [inline_image_data] => { $ crate :: SyntaxKind :: INLINE_IMAGE_DATA } ; [ident] => { $ crate :: SyntaxKind :: IDENT } ; [header_comment] => { $ crate :: SyntaxKind :: HEADER_COMMENT } ; [binary_comment] => { $ crate :: SyntaxKind :: BINARY_COMMENT } ; [eof_comment] => { $ crate :: SyntaxKind :: EOF_COMMENT } ; }
@@ -14,6 +14,8 @@ mod lexer_runner;
 #[path = "../test_data/parser/generated/runner.rs"]
 mod parser_runner;
 
+mod top_entries;
+
 fn lex(text: &[u8]) -> String {
     let lexed = LexedStr::new(Edition::LATEST, text);
 
@@ -41,7 +43,10 @@ fn escape_bytes(text: &[u8]) -> String {
 }
 
 fn parse(entry: TopEntryPoint, text: &[u8], edition: Edition) -> (String, bool) {
-    let lexed = LexedStr::new(edition, text);
+    let lexed = match entry {
+        TopEntryPoint::ContentStream => LexedStr::new_content_stream(edition, text),
+        _ => LexedStr::new(edition, text),
+    };
     let input = lexed.to_input(edition);
     let output = entry.parse(&input, edition);
 
@@ -99,6 +104,18 @@ fn lex_and_expect_no_errors(path: &str) {
     expect_file![p].assert_eq(&actual)
 }
 
+#[track_caller]
+fn lex_and_expect_errors(path: &str) {
+    let path = PathBuf::from(path);
+    let text = fs::read(&path).unwrap().into_boxed_slice();
+    let actual = lex(&text);
+    assert!(actual.contains(" error: "), "no errors in an ERR file {}:\n{actual}", path.display());
+    let mut p = PathBuf::from("..");
+    p.push(path);
+    p.set_extension("rast");
+    expect_file![p].assert_eq(&actual)
+}
+
 #[track_caller]
 fn parse_and_expect_no_errors(path: &str) {
     parse_and_expect_no_errors_with_edition(path, Edition::CURRENT)
@@ -115,3 +132,35 @@ fn parse_and_expect_no_errors_with_edition(path: &str, edition: Edition) {
     p.set_extension("rast");
     expect_file![p].assert_eq(&actual)
 }
+
+#[track_caller]
+fn parse_and_expect_errors(path: &str) {
+    let path = PathBuf::from(path);
+    let text = fs::read(&path).unwrap().into_boxed_slice();
+    let (actual, errors) = parse(TopEntryPoint::PdfDocument, &text, Edition::CURRENT);
+    assert!(errors, "no errors in an ERR file {}:\n{actual}", path.display());
+    let mut p = PathBuf::from("..");
+    p.push(path);
+    p.set_extension("rast");
+    expect_file![p].assert_eq(&actual)
+}
+
+/// Reports how deeply arrays and dictionaries are nested in the test data, to
+/// check that [`NESTING_LIMIT`](crate::NESTING_LIMIT) is well above what files
+/// actually use. Run with `cargo test -p parser --features tracking -- --nocapture`.
+#[cfg(feature = "tracking")]
+#[test]
+fn nesting_depth_of_test_data() {
+    for dir in ["test_data/parser/ok", "test_data/parser/err"] {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|it| it == "pdf") {
+                let text = fs::read(&path).unwrap();
+                parse(TopEntryPoint::PdfDocument, &text, Edition::CURRENT);
+            }
+        }
+    }
+    let max = crate::NESTING_LIMIT.max();
+    eprintln!("maximum nesting depth of arrays and dictionaries: {max}");
+    assert!(max < crate::NESTING_LIMIT.inner());
+}
@@ -0,0 +1,536 @@
+use expect_test::expect;
+
+use crate::{Edition, LexedStr, StrStep, TopEntryPoint};
+
+#[test]
+fn object_stream() {
+    check(
+        TopEntryPoint::ObjectStream,
+        b"11 0 12 27\n",
+        expect![[r#"
+        OBJECT_STREAM
+          OBJECT_STREAM_HEADER
+            OBJECT_STREAM_ENTRY
+              LITERAL
+                INT_NUMBER "11"
+              WHITESPACE " "
+              LITERAL
+                INT_NUMBER "0"
+            WHITESPACE " "
+            OBJECT_STREAM_ENTRY
+              LITERAL
+                INT_NUMBER "12"
+              WHITESPACE " "
+              LITERAL
+                INT_NUMBER "27"
+          NEWLINE "\n"
+    "#]],
+    );
+}
+
+#[test]
+fn object_stream_header_is_read_to_the_end() {
+    // The input is the header alone, so an integer pair on the next line is
+    // still part of it.
+    check(
+        TopEntryPoint::ObjectStream,
+        b"7 0 8 3\n42 7",
+        expect![[r#"
+        OBJECT_STREAM
+          OBJECT_STREAM_HEADER
+            OBJECT_STREAM_ENTRY
+              LITERAL
+                INT_NUMBER "7"
+              WHITESPACE " "
+              LITERAL
+                INT_NUMBER "0"
+            WHITESPACE " "
+            OBJECT_STREAM_ENTRY
+              LITERAL
+                INT_NUMBER "8"
+              WHITESPACE " "
+              LITERAL
+                INT_NUMBER "3"
+            NEWLINE "\n"
+            OBJECT_STREAM_ENTRY
+              LITERAL
+                INT_NUMBER "42"
+              WHITESPACE " "
+              LITERAL
+                INT_NUMBER "7"
+    "#]],
+    );
+}
+
+#[test]
+fn object_stream_header_rejects_objects() {
+    check(
+        TopEntryPoint::ObjectStream,
+        b"1 0 5 << /A 1 >> ] true",
+        expect![[r#"
+        OBJECT_STREAM
+          OBJECT_STREAM_HEADER
+            OBJECT_STREAM_ENTRY
+              LITERAL
+                INT_NUMBER "1"
+              WHITESPACE " "
+              LITERAL
+                INT_NUMBER "0"
+            WHITESPACE " "
+            ERROR
+              INT_NUMBER "5"
+              WHITESPACE " "
+              L_DICT "<<"
+              WHITESPACE " "
+              NAME "/A"
+              WHITESPACE " "
+              INT_NUMBER "1"
+              WHITESPACE " "
+              R_DICT ">>"
+              WHITESPACE " "
+              R_BRACK "]"
+              WHITESPACE " "
+              TRUE_KW "true"
+        error 4: expected pairs of integers in the object stream header
+    "#]],
+    );
+}
+
+#[test]
+fn content_stream_operations() {
+    check(
+        TopEntryPoint::ContentStream,
+        b"0.5 g\n10 10 m 20 20 l S\n[1 2] 0 d f* T* (a) ' 1 2 (b) \"",
+        expect![[r#"
+        CONTENT_STREAM
+          OPERATION
+            OPERANDS
+              LITERAL
+                REAL_NUMBER "0.5"
+            WHITESPACE " "
+            OPERATOR "g"
+          NEWLINE "\n"
+          OPERATION
+            OPERANDS
+              LITERAL
+                INT_NUMBER "10"
+              WHITESPACE " "
+              LITERAL
+                INT_NUMBER "10"
+            WHITESPACE " "
+            OPERATOR "m"
+          WHITESPACE " "
+          OPERATION
+            OPERANDS
+              LITERAL
+                INT_NUMBER "20"
+              WHITESPACE " "
+              LITERAL
+                INT_NUMBER "20"
+            WHITESPACE " "
+            OPERATOR "l"
+          WHITESPACE " "
+          OPERATION
+            OPERATOR "S"
+          NEWLINE "\n"
+          OPERATION
+            OPERANDS
+              ARRAY_EXPR
+                L_BRACK "["
+                LITERAL
+                  INT_NUMBER "1"
+                WHITESPACE " "
+                LITERAL
+                  INT_NUMBER "2"
+                R_BRACK "]"
+              WHITESPACE " "
+              LITERAL
+                INT_NUMBER "0"
+            WHITESPACE " "
+            OPERATOR "d"
+          WHITESPACE " "
+          OPERATION
+            OPERATOR "f*"
+          WHITESPACE " "
+          OPERATION
+            OPERATOR "T*"
+          WHITESPACE " "
+          OPERATION
+            OPERANDS
+              LITERAL
+                LITERAL_STRING "(a)"
+            WHITESPACE " "
+            OPERATOR "'"
+          WHITESPACE " "
+          OPERATION
+            OPERANDS
+              LITERAL
+                INT_NUMBER "1"
+              WHITESPACE " "
+              LITERAL
+                INT_NUMBER "2"
+              WHITESPACE " "
+              LITERAL
+                LITERAL_STRING "(b)"
+            WHITESPACE " "
+            OPERATOR "\""
+    "#]],
+    );
+}
+
+#[test]
+fn content_stream_blocks() {
+    check(
+        TopEntryPoint::ContentStream,
+        b"q 1 0 0 1 0 0 cm BT /F1 12 Tf (Hi) Tj ET Q /Span << /MCID 0 >> BDC /Tag BMC EMC EMC",
+        expect![[r#"
+            CONTENT_STREAM
+              GRAPHICS_STATE_BLOCK
+                SAVE_KW "q"
+                WHITESPACE " "
+                OPERATION
+                  OPERANDS
+                    LITERAL
+                      INT_NUMBER "1"
+                    WHITESPACE " "
+                    LITERAL
+                      INT_NUMBER "0"
+                    WHITESPACE " "
+                    LITERAL
+                      INT_NUMBER "0"
+                    WHITESPACE " "
+                    LITERAL
+                      INT_NUMBER "1"
+                    WHITESPACE " "
+                    LITERAL
+                      INT_NUMBER "0"
+                    WHITESPACE " "
+                    LITERAL
+                      INT_NUMBER "0"
+                  WHITESPACE " "
+                  OPERATOR "cm"
+                WHITESPACE " "
+                TEXT_OBJECT
+                  BT_KW "BT"
+                  WHITESPACE " "
+                  OPERATION
+                    OPERANDS
+                      LITERAL
+                        NAME "/F1"
+                      WHITESPACE " "
+                      LITERAL
+                        INT_NUMBER "12"
+                    WHITESPACE " "
+                    OPERATOR "Tf"
+                  WHITESPACE " "
+                  OPERATION
+                    OPERANDS
+                      LITERAL
+                        LITERAL_STRING "(Hi)"
+                    WHITESPACE " "
+                    OPERATOR "Tj"
+                  WHITESPACE " "
+                  ET_KW "ET"
+                WHITESPACE " "
+                RESTORE_KW "Q"
+              WHITESPACE " "
+              MARKED_CONTENT
+                OPERANDS
+                  LITERAL
+                    NAME "/Span"
+                  WHITESPACE " "
+                  DICTIONARY_EXPR
+                    L_DICT "<<"
+                    WHITESPACE " "
+                    DICTIONARY_ITEM_EXPR
+                      DICTIONARY_ITEM_KEY_EXPR
+                        LITERAL
+                          NAME "/MCID"
+                      WHITESPACE " "
+                      DICTIONARY_ITEM_VALUE_EXPR
+                        LITERAL
+                          INT_NUMBER "0"
+                    WHITESPACE " "
+                    R_DICT ">>"
+                WHITESPACE " "
+                BDC_KW "BDC"
+                WHITESPACE " "
+                MARKED_CONTENT
+                  OPERANDS
+                    LITERAL
+                      NAME "/Tag"
+                  WHITESPACE " "
+                  BMC_KW "BMC"
+                  WHITESPACE " "
+                  EMC_KW "EMC"
+                WHITESPACE " "
+                EMC_KW "EMC"
+        "#]],
+    );
+}
+
+#[test]
+fn content_stream_inline_image() {
+    check(
+        TopEntryPoint::ContentStream,
+        b"BI /W 2 /H 1 /BPC 8 /CS /G ID \x00EI\xff EI Q",
+        expect![[r#"
+        CONTENT_STREAM
+          INLINE_IMAGE
+            BI_KW "BI"
+            WHITESPACE " "
+            LITERAL
+              NAME "/W"
+            WHITESPACE " "
+            LITERAL
+              INT_NUMBER "2"
+            WHITESPACE " "
+            LITERAL
+              NAME "/H"
+            WHITESPACE " "
+            LITERAL
+              INT_NUMBER "1"
+            WHITESPACE " "
+            LITERAL
+              NAME "/BPC"
+            WHITESPACE " "
+            LITERAL
+              INT_NUMBER "8"
+            WHITESPACE " "
+            LITERAL
+              NAME "/CS"
+            WHITESPACE " "
+            LITERAL
+              NAME "/G"
+            WHITESPACE " "
+            ID_KW "ID"
+            INLINE_IMAGE_DATA " \0EI\\xff "
+            EI_KW "EI"
+          WHITESPACE " "
+          ERROR
+            IDENT "Q"
+        error 38: closing operator without matching opening operator
+    "#]],
+    );
+}
+
+#[test]
+fn content_stream_recovers_from_unbalanced_blocks() {
+    check(
+        TopEntryPoint::ContentStream,
+        b"ET q BT 1 Q BI ID",
+        expect![[r#"
+            CONTENT_STREAM
+              ERROR
+                IDENT "ET"
+              WHITESPACE " "
+              GRAPHICS_STATE_BLOCK
+                SAVE_KW "q"
+                WHITESPACE " "
+                TEXT_OBJECT
+                  BT_KW "BT"
+                  WHITESPACE " "
+                  OPERATION
+                    OPERANDS
+                      LITERAL
+                        INT_NUMBER "1"
+                WHITESPACE " "
+                RESTORE_KW "Q"
+              WHITESPACE " "
+              INLINE_IMAGE
+                BI_KW "BI"
+                WHITESPACE " "
+                ID_KW "ID"
+            error 0: closing operator without matching opening operator
+            error 9: expected operator
+            error 9: expected ET
+            error 17: expected EI
+        "#]],
+    );
+}
+
+#[test]
+fn pdf_document_recovers_from_incomplete_trailers() {
+    check(
+        TopEntryPoint::PdfDocument,
+        b"trailer startxref xref trailer << >>",
+        expect![[r#"
+            PDF_DOCUMENT
+              REVISION
+                BODY
+                TRAILER
+                  TRAILER_KW "trailer"
+                  WHITESPACE " "
+                  STARTXREF_KW "startxref"
+              WHITESPACE " "
+              REVISION
+                BODY
+                X_REF_TABLE
+                  X_REF_SECTION
+                    XREF_KW "xref"
+                WHITESPACE " "
+                TRAILER
+                  TRAILER_KW "trailer"
+                  WHITESPACE " "
+                  DICTIONARY_EXPR
+                    L_DICT "<<"
+                    WHITESPACE " "
+                    R_DICT ">>"
+            error 7: expected trailer dictionary
+            error 17: expected byte offset of the cross-reference section
+            error 36: expected startxref
+        "#]],
+    );
+}
+
+#[test]
+fn dictionary_items_recover_from_missing_keys_and_values() {
+    check(
+        TopEntryPoint::Expr,
+        b"<< /A 1 2 /B [3] (k) (v) /C >>",
+        expect![[r#"
+        DICTIONARY_EXPR
+          L_DICT "<<"
+          WHITESPACE " "
+          DICTIONARY_ITEM_EXPR
+            DICTIONARY_ITEM_KEY_EXPR
+              LITERAL
+                NAME "/A"
+            WHITESPACE " "
+            DICTIONARY_ITEM_VALUE_EXPR
+              LITERAL
+                INT_NUMBER "1"
+          WHITESPACE " "
+          ERROR
+            LITERAL
+              INT_NUMBER "2"
+          WHITESPACE " "
+          DICTIONARY_ITEM_EXPR
+            DICTIONARY_ITEM_KEY_EXPR
+              LITERAL
+                NAME "/B"
+            WHITESPACE " "
+            DICTIONARY_ITEM_VALUE_EXPR
+              ARRAY_EXPR
+                L_BRACK "["
+                LITERAL
+                  INT_NUMBER "3"
+                R_BRACK "]"
+          WHITESPACE " "
+          DICTIONARY_ITEM_EXPR
+            DICTIONARY_ITEM_KEY_EXPR
+              LITERAL
+                LITERAL_STRING "(k)"
+            WHITESPACE " "
+            DICTIONARY_ITEM_VALUE_EXPR
+              LITERAL
+                LITERAL_STRING "(v)"
+          WHITESPACE " "
+          DICTIONARY_ITEM_EXPR
+            DICTIONARY_ITEM_KEY_EXPR
+              LITERAL
+                NAME "/C"
+          WHITESPACE " "
+          R_DICT ">>"
+        error 9: expected a name as dictionary key
+        error 27: expected dictionary value
+    "#]],
+    );
+}
+
+#[test]
+fn top_entries_consume_all_input() {
+    for entry in [
+        TopEntryPoint::PdfDocument,
+        TopEntryPoint::ObjectStream,
+        TopEntryPoint::ContentStream,
+        TopEntryPoint::Expr,
+    ] {
+        let text = b"1 0 obj << /A [1 2] >> endobj ] >> 3 4";
+        let lexed = LexedStr::new(Edition::CURRENT, text);
+        let output = entry.parse(&lexed.to_input(Edition::CURRENT), Edition::CURRENT);
+        let mut len = 0;
+        lexed.intersperse_trivia(&output, &mut |step| {
+            if let StrStep::Token { text, .. } = step {
+                len += text.len();
+            }
+        });
+        assert_eq!(len, text.len(), "{entry:?} didn't consume all input");
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "tracking", ignore = "raises the tracked nesting depth to the limit")]
+fn deep_nesting_is_collapsed_into_an_error() {
+    let limit = crate::NESTING_LIMIT.inner();
+    let cases = [
+        (TopEntryPoint::Expr, &b"["[..], &b"]"[..], "arrays and dictionaries"),
+        (TopEntryPoint::Expr, b"<< /A ", b">>", "arrays and dictionaries"),
+        (TopEntryPoint::ContentStream, b"q ", b"Q ", "blocks"),
+        (TopEntryPoint::ContentStream, b"BT ", b"ET ", "blocks"),
+        (TopEntryPoint::ContentStream, b"/Tag BMC ", b"EMC ", "blocks"),
+        // Unterminated blocks.
+        (TopEntryPoint::ContentStream, b"q ", b"", "blocks"),
+    ];
+    for (entry, open, close, nested) in cases {
+        let text = [open.repeat(100_000), b"1".to_vec(), close.repeat(100_000)].concat();
+        let lexed = LexedStr::new(Edition::CURRENT, &text);
+        let output = entry.parse(&lexed.to_input(Edition::CURRENT), Edition::CURRENT);
+
+        let (mut depth, mut max_depth, mut errors) = (0, 0, Vec::new());
+        lexed.intersperse_trivia(&output, &mut |step| match step {
+            StrStep::Enter { .. } => {
+                depth += 1;
+                max_depth = max_depth.max(depth);
+            }
+            StrStep::Exit => depth -= 1,
+            StrStep::Error { msg, pos } => errors.push(format!("error {pos}: {msg}")),
+            StrStep::Token { .. } => (),
+        });
+
+        // Each level of a dictionary is an item, its value and the dictionary.
+        assert!(max_depth <= 3 * limit + 2, "nodes are nested {max_depth} levels deep");
+        // The error is reported before the opening delimiter or operator.
+        let pos = limit * open.len() + if open.starts_with(b"/Tag") { 4 } else { 0 };
+        let expected = format!("error {pos}: {nested} are nested deeper than {limit} levels");
+        assert_eq!(errors.first(), Some(&expected), "{errors:?}");
+        if !close.is_empty() {
+            assert_eq!(errors.len(), 1, "{errors:?}");
+        }
+    }
+}
+
+#[test]
+fn nesting_limit_is_configurable() {
+    let errors = |entry: TopEntryPoint, text: &[u8], limit: usize| {
+        let lexed = LexedStr::new(Edition::CURRENT, text);
+        let output = entry.parse_with_nesting_limit(&lexed.to_input(Edition::CURRENT), Edition::CURRENT, limit);
+        let mut errors = Vec::new();
+        lexed.intersperse_trivia(&output, &mut |step| {
+            if let StrStep::Error { msg, pos } = step {
+                errors.push(format!("error {pos}: {msg}"));
+            }
+        });
+        errors
+    };
+
+    assert_eq!(errors(TopEntryPoint::Expr, b"[[<< /A [1] >>]]", 4), Vec::<String>::new());
+    assert_eq!(
+        errors(TopEntryPoint::Expr, b"[[<< /A [1] >>]]", 3),
+        ["error 8: arrays and dictionaries are nested deeper than 3 levels"]
+    );
+    assert_eq!(
+        errors(TopEntryPoint::ContentStream, b"q BT [1] TJ ET Q", 2),
+        ["error 5: arrays and dictionaries are nested deeper than 2 levels"]
+    );
+    assert_eq!(
+        errors(TopEntryPoint::ContentStream, b"q q q Q Q Q q Q", 2),
+        ["error 4: blocks are nested deeper than 2 levels"]
+    );
+}
+
+#[track_caller]
+fn check(entry: TopEntryPoint, input: &[u8], expect: expect_test::Expect) {
+    let (parsed, _errors) = super::parse(entry, input, Edition::CURRENT);
+    expect.assert_eq(&parsed);
+}
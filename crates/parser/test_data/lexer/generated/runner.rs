@@ -31,8 +31,18 @@ mod ok {
     fn pdf_7_5_5_file_trailer() { lex_and_expect_no_errors("test_data/lexer/ok/pdf_7_5_5_file_trailer.pdf"); }
     #[test]
     fn safedocs_pdf_syntax_tests() { lex_and_expect_no_errors("test_data/lexer/ok/safedocs_pdf_syntax_tests.pdf"); }
+    #[test]
+    fn stream_length_direct() { lex_and_expect_no_errors("test_data/lexer/ok/stream_length_direct.pdf"); }
+    #[test]
+    fn stream_length_indirect() { lex_and_expect_no_errors("test_data/lexer/ok/stream_length_indirect.pdf"); }
 }
 mod err {
     #![allow(unused_imports)]
     use crate::tests::*;
+    #[test]
+    fn stream_length_mismatch() { lex_and_expect_errors("test_data/lexer/err/stream_length_mismatch.pdf"); }
+    #[test]
+    fn unknown_characters() { lex_and_expect_errors("test_data/lexer/err/unknown_characters.pdf"); }
+    #[test]
+    fn unterminated_literals() { lex_and_expect_errors("test_data/lexer/err/unterminated_literals.pdf"); }
 }
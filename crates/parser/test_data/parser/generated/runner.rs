@@ -30,9 +30,33 @@ mod ok {
     #[test]
     fn pdf_7_5_5_file_trailer() { parse_and_expect_no_errors("test_data/parser/ok/pdf_7_5_5_file_trailer.pdf"); }
     #[test]
+    fn pdf_7_5_6_incremental_updates() { parse_and_expect_no_errors("test_data/parser/ok/pdf_7_5_6_incremental_updates.pdf"); }
+    #[test]
+    fn pdf_7_5_8_xref_stream_trailer() { parse_and_expect_no_errors("test_data/parser/ok/pdf_7_5_8_xref_stream_trailer.pdf"); }
+    #[test]
     fn safedocs_pdf_syntax_tests() { parse_and_expect_no_errors("test_data/parser/ok/safedocs_pdf_syntax_tests.pdf"); }
+    #[test]
+    fn xref_entry_at_end_of_input() { parse_and_expect_no_errors("test_data/parser/ok/xref_entry_at_end_of_input.pdf"); }
 }
 mod err {
     #![allow(unused_imports)]
     use crate::tests::*;
+    #[test]
+    fn dictionary_keys() { parse_and_expect_errors("test_data/parser/err/dictionary_keys.pdf"); }
+    #[test]
+    fn malformed_trailer() { parse_and_expect_errors("test_data/parser/err/malformed_trailer.pdf"); }
+    #[test]
+    fn missing_endstream() { parse_and_expect_errors("test_data/parser/err/missing_endstream.pdf"); }
+    #[test]
+    fn stray_inline_image_operators() { parse_and_expect_errors("test_data/parser/err/stray_inline_image_operators.pdf"); }
+    #[test]
+    fn stray_keywords() { parse_and_expect_errors("test_data/parser/err/stray_keywords.pdf"); }
+    #[test]
+    fn tokens_after_object_value() { parse_and_expect_errors("test_data/parser/err/tokens_after_object_value.pdf"); }
+    #[test]
+    fn unmatched_delimiters() { parse_and_expect_errors("test_data/parser/err/unmatched_delimiters.pdf"); }
+    #[test]
+    fn unterminated_arrays() { parse_and_expect_errors("test_data/parser/err/unterminated_arrays.pdf"); }
+    #[test]
+    fn unterminated_dictionaries() { parse_and_expect_errors("test_data/parser/err/unterminated_dictionaries.pdf"); }
 }
@@ -0,0 +1,10 @@
+//! Driver for pdf-analyzer: serves the Language Server Protocol over stdio.
+
+use lsp_server::Connection;
+
+fn main() -> anyhow::Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    pdf_analyzer::run_server(connection)?;
+    io_threads.join()?;
+    Ok(())
+}
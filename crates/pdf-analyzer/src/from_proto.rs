@@ -0,0 +1,12 @@
+//! Conversion of LSP positions into offsets.
+
+use lsp_types::Position;
+use syntax::TextSize;
+
+use crate::line_index::LineIndex;
+
+pub(crate) fn offset(line_index: &LineIndex<'_>, position: Position) -> anyhow::Result<TextSize> {
+    line_index
+        .offset(position)
+        .ok_or_else(|| anyhow::format_err!("invalid position {}:{}", position.line, position.character))
+}
@@ -0,0 +1,113 @@
+//! The handlers of the requests and notifications the server supports.
+
+use lsp_types::{
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams, DocumentSymbolParams, DocumentSymbolResponse, FoldingRange,
+    FoldingRangeParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverParams, Location, ReferenceParams, SemanticTokensParams,
+    SemanticTokensResult, Url,
+};
+use syntax::{PdfDocument, TextRange};
+use text_edit::Indel;
+
+use crate::{
+    from_proto,
+    main_loop::{FileState, GlobalState},
+    to_proto,
+};
+
+pub(crate) fn handle_did_open_text_document(state: &mut GlobalState, params: DidOpenTextDocumentParams) -> anyhow::Result<Url> {
+    let document = params.text_document;
+    state.files.insert(document.uri.clone(), FileState::new(document.version, document.text));
+    Ok(document.uri)
+}
+
+/// Applies the changes to the text, and reparses only what they touched
+/// unless the edition declared by the header changed.
+pub(crate) fn handle_did_change_text_document(state: &mut GlobalState, params: DidChangeTextDocumentParams) -> anyhow::Result<Url> {
+    let uri = params.text_document.uri;
+    let Some(file) = state.files.get_mut(&uri) else {
+        anyhow::bail!("file {uri} is not open");
+    };
+    for change in params.content_changes {
+        let Some(range) = change.range else {
+            *file = FileState::new(params.text_document.version, change.text);
+            continue;
+        };
+        let line_index = file.line_index();
+        let (start, end) = (from_proto::offset(&line_index, range.start)?, from_proto::offset(&line_index, range.end)?);
+        let range = TextRange::new(start, end.max(start));
+        file.text.replace_range(std::ops::Range::<usize>::from(range), &change.text);
+        file.parse = file.parse.reparse(&Indel::replace(range, change.text.into_bytes()), file.edition);
+    }
+    file.version = params.text_document.version;
+    if PdfDocument::detect_edition(file.text.as_bytes()).is_some_and(|it| it != file.edition) {
+        *file = FileState::new(file.version, std::mem::take(&mut file.text));
+    }
+    Ok(uri)
+}
+
+pub(crate) fn handle_did_close_text_document(state: &mut GlobalState, params: DidCloseTextDocumentParams) -> anyhow::Result<Url> {
+    state.files.remove(&params.text_document.uri);
+    Ok(params.text_document.uri)
+}
+
+pub(crate) fn handle_document_symbol(state: &GlobalState, params: DocumentSymbolParams) -> anyhow::Result<Option<DocumentSymbolResponse>> {
+    let file = state.file(&params.text_document.uri)?;
+    let line_index = file.line_index();
+    let symbols = ide::file_structure(&file.parse.tree())
+        .into_iter()
+        .map(|it| to_proto::document_symbol(&line_index, it))
+        .collect();
+    Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+}
+
+pub(crate) fn handle_folding_range(state: &GlobalState, params: FoldingRangeParams) -> anyhow::Result<Option<Vec<FoldingRange>>> {
+    let file = state.file(&params.text_document.uri)?;
+    let line_index = file.line_index();
+    let folds = ide::folding_ranges(&file.parse.tree())
+        .into_iter()
+        .map(|it| to_proto::folding_range(&line_index, it))
+        .collect();
+    Ok(Some(folds))
+}
+
+pub(crate) fn handle_semantic_tokens_full(state: &GlobalState, params: SemanticTokensParams) -> anyhow::Result<Option<SemanticTokensResult>> {
+    let file = state.file(&params.text_document.uri)?;
+    let highlights = ide::highlight(&file.parse.tree());
+    let tokens = to_proto::semantic_tokens(&file.text, &file.line_index(), highlights);
+    Ok(Some(tokens.into()))
+}
+
+pub(crate) fn handle_goto_definition(state: &GlobalState, params: GotoDefinitionParams) -> anyhow::Result<Option<GotoDefinitionResponse>> {
+    let uri = params.text_document_position_params.text_document.uri;
+    let file = state.file(&uri)?;
+    let line_index = file.line_index();
+    let offset = from_proto::offset(&line_index, params.text_document_position_params.position)?;
+    let Some(target) = ide::goto_definition(&file.parse.tree(), offset) else {
+        return Ok(None);
+    };
+    Ok(Some(to_proto::location_link(&line_index, uri, target).into()))
+}
+
+pub(crate) fn handle_references(state: &GlobalState, params: ReferenceParams) -> anyhow::Result<Option<Vec<Location>>> {
+    let uri = params.text_document_position.text_document.uri;
+    let file = state.file(&uri)?;
+    let line_index = file.line_index();
+    let offset = from_proto::offset(&line_index, params.text_document_position.position)?;
+    let Some(result) = ide::find_all_refs(&file.parse.tree(), offset) else {
+        return Ok(None);
+    };
+    let declaration = result.declaration.filter(|_| params.context.include_declaration).map(|it| it.focus_range);
+    let locations = declaration
+        .into_iter()
+        .chain(result.references)
+        .map(|range| Location::new(uri.clone(), to_proto::range(&line_index, range)))
+        .collect();
+    Ok(Some(locations))
+}
+
+pub(crate) fn handle_hover(state: &GlobalState, params: HoverParams) -> anyhow::Result<Option<Hover>> {
+    let file = state.file(&params.text_document_position_params.text_document.uri)?;
+    let line_index = file.line_index();
+    let offset = from_proto::offset(&line_index, params.text_document_position_params.position)?;
+    Ok(ide::hover(&file.parse.tree(), offset).map(|it| to_proto::hover(&line_index, it)))
+}
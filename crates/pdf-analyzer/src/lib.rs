@@ -0,0 +1,34 @@
+//! Implementation of the Language Server Protocol for PDF files.
+//!
+//! The server keeps each open file parsed with [`syntax`], reparsing only the
+//! parts touched by an edit, and publishes the syntax errors as diagnostics.
+//! It also provides the outline of a file (its objects, cross-reference
+//! sections and trailers), folding ranges, semantic tokens, and navigation
+//! between indirect references and objects.
+//!
+//! The features themselves live in [`ide`] and don't know about LSP;
+//! `from_proto` and `to_proto` convert positions and results.
+
+mod caps;
+mod from_proto;
+mod handlers;
+mod line_index;
+mod main_loop;
+mod semantic_tokens;
+mod to_proto;
+
+use lsp_server::Connection;
+
+pub use crate::{caps::server_capabilities, main_loop::main_loop};
+
+/// Initializes the connection and runs the server until the client asks it to
+/// shut down.
+pub fn run_server(connection: Connection) -> anyhow::Result<()> {
+    let (id, _params) = connection.initialize_start()?;
+    let result = serde_json::json!({
+        "capabilities": server_capabilities(),
+        "serverInfo": { "name": "pdf-analyzer", "version": env!("CARGO_PKG_VERSION") },
+    });
+    connection.initialize_finish(id, result)?;
+    main_loop(connection)
+}
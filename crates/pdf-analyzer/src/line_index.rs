@@ -0,0 +1,90 @@
+//! Conversion between byte offsets and LSP positions.
+//!
+//! LSP positions are a zero-based line and a column counted in UTF-16 code
+//! units. Lines end with `\n`, `\r\n` or a lone `\r`, like in PDF itself.
+
+use lsp_types::Position;
+use syntax::TextSize;
+
+pub(crate) struct LineIndex<'a> {
+    text: &'a str,
+    /// The offset of the first byte of each line.
+    line_starts: Vec<TextSize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub(crate) fn new(text: &'a str) -> LineIndex<'a> {
+        let bytes = text.as_bytes();
+        let mut line_starts = vec![TextSize::new(0)];
+        for (i, &b) in bytes.iter().enumerate() {
+            if b == b'\n' || (b == b'\r' && bytes.get(i + 1) != Some(&b'\n')) {
+                line_starts.push(TextSize::new(i as u32 + 1));
+            }
+        }
+        LineIndex { text, line_starts }
+    }
+
+    /// The position of `offset`. Offsets inside of a character are moved to
+    /// its start.
+    pub(crate) fn position(&self, offset: TextSize) -> Position {
+        let mut offset = usize::from(offset).min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_starts.partition_point(|&it| usize::from(it) <= offset) - 1;
+        let start = usize::from(self.line_starts[line]);
+        let character = self.text[start..offset].encode_utf16().count();
+        Position::new(line as u32, character as u32)
+    }
+
+    /// The offset of `position`, or `None` if the line doesn't exist. Columns
+    /// past the end of the line are moved to its end.
+    pub(crate) fn offset(&self, position: Position) -> Option<TextSize> {
+        let start = usize::from(*self.line_starts.get(position.line as usize)?);
+        let end = self.line_starts.get(position.line as usize + 1).map_or(self.text.len(), |&it| usize::from(it));
+        let line = self.text[start..end].trim_end_matches(['\r', '\n']);
+
+        let mut character = 0;
+        for (i, c) in line.char_indices() {
+            if character >= position.character as usize {
+                return Some(TextSize::new((start + i) as u32));
+            }
+            character += c.len_utf16();
+        }
+        Some(TextSize::new((start + line.len()) as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_and_offsets() {
+        let text = "%PDF-1.7\r\n1 0 obj\r(\u{e9}\u{1F600}x)\nendobj";
+        let index = LineIndex::new(text);
+        let cases = [
+            (0, (0, 0)),
+            (8, (0, 8)),
+            (10, (1, 0)),
+            (18, (2, 0)),
+            (19, (2, 1)),
+            (21, (2, 2)),
+            (25, (2, 4)),
+            (27, (2, 6)),
+            (28, (3, 0)),
+        ];
+        for (offset, (line, character)) in cases {
+            let position = Position::new(line, character);
+            assert_eq!(index.position(TextSize::new(offset)), position, "offset {offset}");
+            assert_eq!(index.offset(position), Some(TextSize::new(offset)), "position {position:?}");
+        }
+
+        // Inside of a character.
+        assert_eq!(index.position(TextSize::new(20)), Position::new(2, 1));
+        // Past the end of a line, and past the last line.
+        assert_eq!(index.offset(Position::new(0, 100)), Some(TextSize::new(8)));
+        assert_eq!(index.offset(Position::new(3, 100)), Some(TextSize::new(34)));
+        assert_eq!(index.offset(Position::new(4, 0)), None);
+    }
+}
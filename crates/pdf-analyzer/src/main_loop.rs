@@ -0,0 +1,387 @@
+//! The main loop of the server: it keeps the open files parsed, publishes
+//! their errors and answers requests, one message at a time.
+
+use std::collections::HashMap;
+
+use crossbeam_channel::Sender;
+use lsp_server::{Connection, ErrorCode, ExtractError, Message, Notification, Request, Response};
+use lsp_types::{
+    PublishDiagnosticsParams, Url,
+    notification::{self, Notification as _},
+    request,
+};
+use syntax::{Edition, Parse, PdfDocument};
+
+use crate::{handlers, line_index::LineIndex, to_proto};
+
+/// An open file, parsed.
+pub(crate) struct FileState {
+    pub(crate) version: i32,
+    pub(crate) text: String,
+    pub(crate) edition: Edition,
+    pub(crate) parse: Parse<PdfDocument>,
+}
+
+impl FileState {
+    pub(crate) fn new(version: i32, text: String) -> FileState {
+        let edition = PdfDocument::detect_edition(text.as_bytes()).unwrap_or(Edition::CURRENT);
+        let parse = PdfDocument::parse(text.as_bytes(), edition);
+        FileState { version, text, edition, parse }
+    }
+
+    pub(crate) fn line_index(&self) -> LineIndex<'_> {
+        LineIndex::new(&self.text)
+    }
+}
+
+pub(crate) struct GlobalState {
+    sender: Sender<Message>,
+    pub(crate) files: HashMap<Url, FileState>,
+}
+
+impl GlobalState {
+    /// The state of an open file, or an error for the client.
+    pub(crate) fn file(&self, uri: &Url) -> anyhow::Result<&FileState> {
+        self.files.get(uri).ok_or_else(|| anyhow::format_err!("file {uri} is not open"))
+    }
+
+    fn send(&self, message: Message) {
+        // The client may already be gone, in which case the main loop ends
+        // with the next message.
+        _ = self.sender.send(message);
+    }
+
+    fn publish_diagnostics(&self, uri: Url) {
+        let (diagnostics, version) = match self.files.get(&uri) {
+            Some(file) => {
+                let line_index = file.line_index();
+                let errors = file.parse.errors();
+                (errors.iter().map(|it| to_proto::diagnostic(&line_index, it)).collect(), Some(file.version))
+            }
+            None => (Vec::new(), None),
+        };
+        let params = PublishDiagnosticsParams { uri, diagnostics, version };
+        self.send(Notification::new(notification::PublishDiagnostics::METHOD.to_owned(), params).into());
+    }
+}
+
+pub fn main_loop(connection: Connection) -> anyhow::Result<()> {
+    let mut state = GlobalState {
+        sender: connection.sender.clone(),
+        files: HashMap::new(),
+    };
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(req) => {
+                if connection.handle_shutdown(&req)? {
+                    return Ok(());
+                }
+                RequestDispatcher { req: Some(req), state: &state }
+                    .on::<request::DocumentSymbolRequest>(handlers::handle_document_symbol)
+                    .on::<request::FoldingRangeRequest>(handlers::handle_folding_range)
+                    .on::<request::GotoDefinition>(handlers::handle_goto_definition)
+                    .on::<request::References>(handlers::handle_references)
+                    .on::<request::HoverRequest>(handlers::handle_hover)
+                    .on::<request::SemanticTokensFullRequest>(handlers::handle_semantic_tokens_full)
+                    .finish();
+            }
+            Message::Notification(not) => {
+                let mut dispatcher = NotificationDispatcher {
+                    not: Some(not),
+                    state: &mut state,
+                    changed: None,
+                };
+                dispatcher
+                    .on::<notification::DidOpenTextDocument>(handlers::handle_did_open_text_document)
+                    .on::<notification::DidChangeTextDocument>(handlers::handle_did_change_text_document)
+                    .on::<notification::DidCloseTextDocument>(handlers::handle_did_close_text_document);
+                if let Some(uri) = dispatcher.changed {
+                    state.publish_diagnostics(uri);
+                }
+            }
+            Message::Response(_) => (),
+        }
+    }
+    Ok(())
+}
+
+/// Passes a request to the first handler of its method, and sends back the
+/// result.
+struct RequestDispatcher<'a> {
+    req: Option<Request>,
+    state: &'a GlobalState,
+}
+
+impl RequestDispatcher<'_> {
+    fn on<R>(&mut self, f: fn(&GlobalState, R::Params) -> anyhow::Result<R::Result>) -> &mut Self
+    where
+        R: request::Request,
+    {
+        let Some(req) = self.req.take() else { return self };
+        let id = req.id.clone();
+        let response = match req.extract::<R::Params>(R::METHOD) {
+            Ok((id, params)) => match f(self.state, params) {
+                Ok(result) => Response::new_ok(id, result),
+                Err(err) => Response::new_err(id, ErrorCode::InternalError as i32, err.to_string()),
+            },
+            Err(ExtractError::JsonError { method, error }) => {
+                Response::new_err(id, ErrorCode::InvalidParams as i32, format!("invalid parameters for {method}: {error}"))
+            }
+            Err(ExtractError::MethodMismatch(req)) => {
+                self.req = Some(req);
+                return self;
+            }
+        };
+        self.state.send(response.into());
+        self
+    }
+
+    fn finish(&mut self) {
+        if let Some(req) = self.req.take() {
+            let message = format!("unknown request: {}", req.method);
+            self.state.send(Response::new_err(req.id, ErrorCode::MethodNotFound as i32, message).into());
+        }
+    }
+}
+
+/// Passes a notification to the first handler of its method. Handlers return
+/// the file they changed, if any.
+struct NotificationDispatcher<'a> {
+    not: Option<Notification>,
+    state: &'a mut GlobalState,
+    changed: Option<Url>,
+}
+
+impl NotificationDispatcher<'_> {
+    fn on<N>(&mut self, f: fn(&mut GlobalState, N::Params) -> anyhow::Result<Url>) -> &mut Self
+    where
+        N: notification::Notification,
+    {
+        let Some(not) = self.not.take() else { return self };
+        match not.extract::<N::Params>(N::METHOD) {
+            Ok(params) => match f(self.state, params) {
+                Ok(uri) => self.changed = Some(uri),
+                Err(err) => tracing::error!("failed to handle {}: {err}", N::METHOD),
+            },
+            Err(ExtractError::JsonError { method, error }) => tracing::error!("invalid parameters for {method}: {error}"),
+            Err(ExtractError::MethodMismatch(not)) => self.not = Some(not),
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_server::RequestId;
+    use lsp_types::{
+        DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams, DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams,
+        GotoDefinitionResponse, Hover, HoverContents, HoverParams, Location, Position, Range, ReferenceContext, ReferenceParams, SemanticTokenType,
+        SemanticTokensParams, SemanticTokensResult, TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams,
+        VersionedTextDocumentIdentifier,
+    };
+    use serde_json::Value;
+
+    use super::*;
+    use crate::semantic_tokens::type_index;
+
+    struct Client {
+        connection: Connection,
+        next_id: i32,
+    }
+
+    impl Client {
+        fn notify<N: notification::Notification>(&self, params: N::Params) {
+            self.connection.sender.send(Notification::new(N::METHOD.to_owned(), params).into()).unwrap();
+        }
+
+        fn request<R: request::Request>(&mut self, params: R::Params) -> Value {
+            self.next_id += 1;
+            let id = RequestId::from(self.next_id);
+            self.connection
+                .sender
+                .send(Request::new(id.clone(), R::METHOD.to_owned(), params).into())
+                .unwrap();
+            match self.connection.receiver.recv().unwrap() {
+                Message::Response(response) if response.id == id => response.result.unwrap_or_default(),
+                message => panic!("unexpected message {message:?}"),
+            }
+        }
+
+        fn diagnostics(&self) -> PublishDiagnosticsParams {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Notification(not) => not.extract(notification::PublishDiagnostics::METHOD).unwrap(),
+                message => panic!("unexpected message {message:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn serves_open_files() {
+        let (server, client) = Connection::memory();
+        let server = std::thread::spawn(move || main_loop(server));
+        let mut client = Client {
+            connection: client,
+            next_id: 0,
+        };
+        let uri = Url::parse("file:///test.pdf").unwrap();
+        let document = TextDocumentIdentifier::new(uri.clone());
+
+        client.notify::<notification::DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(
+                uri.clone(),
+                "pdf".to_owned(),
+                1,
+                "%PDF-1.7\n1 0 obj\n<< /A [1 2] /B >>\nendobj\n%%EOF\n".to_owned(),
+            ),
+        });
+        let diagnostics = client.diagnostics();
+        assert_eq!(diagnostics.version, Some(1));
+        let messages: Vec<_> = diagnostics.diagnostics.iter().map(|it| (it.range, it.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [(Range::new(Position::new(2, 14), Position::new(2, 14)), "expected dictionary value")]
+        );
+
+        // Add the missing value, and rename the other key.
+        client.notify::<notification::DidChangeTextDocument>(DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier::new(uri.clone(), 2),
+            content_changes: vec![
+                TextDocumentContentChangeEvent {
+                    range: Some(Range::new(Position::new(2, 14), Position::new(2, 14))),
+                    range_length: None,
+                    text: " 2".to_owned(),
+                },
+                TextDocumentContentChangeEvent {
+                    range: Some(Range::new(Position::new(2, 4), Position::new(2, 5))),
+                    range_length: None,
+                    text: "Kids".to_owned(),
+                },
+            ],
+        });
+        let diagnostics = client.diagnostics();
+        assert_eq!((diagnostics.version, diagnostics.diagnostics), (Some(2), Vec::new()));
+
+        let symbols = client.request::<request::DocumentSymbolRequest>(DocumentSymbolParams {
+            text_document: document.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        let DocumentSymbolResponse::Nested(symbols) = serde_json::from_value(symbols).unwrap() else {
+            panic!()
+        };
+        assert_eq!(
+            symbols.iter().map(|it| (it.name.as_str(), it.range)).collect::<Vec<_>>(),
+            [("1 0 obj", Range::new(Position::new(1, 0), Position::new(3, 6)))]
+        );
+
+        let tokens = client.request::<request::SemanticTokensFullRequest>(SemanticTokensParams {
+            text_document: document.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        let SemanticTokensResult::Tokens(tokens) = serde_json::from_value(tokens).unwrap() else {
+            panic!()
+        };
+        let tokens: Vec<_> = tokens.data.iter().map(|it| (it.delta_line, it.delta_start, it.length, it.token_type)).collect();
+        let (comment, keyword, number, property) = (
+            type_index(SemanticTokenType::COMMENT),
+            type_index(SemanticTokenType::KEYWORD),
+            type_index(SemanticTokenType::NUMBER),
+            type_index(SemanticTokenType::PROPERTY),
+        );
+        assert_eq!(
+            tokens,
+            [
+                (0, 0, 8, comment),
+                (1, 0, 1, number),
+                (0, 2, 1, number),
+                (0, 2, 3, keyword),
+                (1, 3, 5, property),
+                (0, 7, 1, number),
+                (0, 2, 1, number),
+                (0, 3, 2, property),
+                (0, 3, 1, number),
+                (1, 0, 6, keyword),
+                (1, 0, 5, comment),
+            ]
+        );
+
+        client.notify::<notification::DidCloseTextDocument>(DidCloseTextDocumentParams { text_document: document });
+        assert_eq!(client.diagnostics().diagnostics, Vec::new());
+
+        client.request::<request::Shutdown>(());
+        client.notify::<notification::Exit>(());
+        server.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn navigates_references() {
+        let (server, client) = Connection::memory();
+        let server = std::thread::spawn(move || main_loop(server));
+        let mut client = Client {
+            connection: client,
+            next_id: 0,
+        };
+        let uri = Url::parse("file:///test.pdf").unwrap();
+        client.notify::<notification::DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(
+                uri.clone(),
+                "pdf".to_owned(),
+                1,
+                "%PDF-1.7\n1 0 obj\n<< /Type /Catalog >>\nendobj\ntrailer << /Root 1 0 R >>\n%%EOF\n".to_owned(),
+            ),
+        });
+        client.diagnostics();
+        let position = TextDocumentPositionParams::new(TextDocumentIdentifier::new(uri.clone()), Position::new(4, 18));
+
+        let definition = client.request::<request::GotoDefinition>(GotoDefinitionParams {
+            text_document_position_params: position.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        let GotoDefinitionResponse::Link(links) = serde_json::from_value(definition).unwrap() else {
+            panic!()
+        };
+        assert_eq!(
+            links.iter().map(|it| (it.target_range, it.target_selection_range)).collect::<Vec<_>>(),
+            [(
+                Range::new(Position::new(1, 0), Position::new(3, 6)),
+                Range::new(Position::new(1, 0), Position::new(1, 7))
+            )]
+        );
+
+        let references = client.request::<request::References>(ReferenceParams {
+            text_document_position: position.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: ReferenceContext { include_declaration: true },
+        });
+        let references: Vec<Location> = serde_json::from_value(references).unwrap();
+        assert_eq!(
+            references.iter().map(|it| it.range).collect::<Vec<_>>(),
+            [
+                Range::new(Position::new(1, 0), Position::new(1, 7)),
+                Range::new(Position::new(4, 17), Position::new(4, 22))
+            ]
+        );
+
+        let hover = client.request::<request::HoverRequest>(HoverParams {
+            text_document_position_params: position,
+            work_done_progress_params: Default::default(),
+        });
+        let Hover {
+            contents: HoverContents::Markup(contents),
+            range,
+        } = serde_json::from_value(hover).unwrap()
+        else {
+            panic!()
+        };
+        assert_eq!(range, Some(Range::new(Position::new(4, 17), Position::new(4, 22))));
+        assert!(contents.value.starts_with("`1 0 obj` `/Catalog`"), "{}", contents.value);
+
+        client.request::<request::Shutdown>(());
+        client.notify::<notification::Exit>(());
+        server.join().unwrap().unwrap();
+    }
+}
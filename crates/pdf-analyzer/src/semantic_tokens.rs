@@ -0,0 +1,50 @@
+//! The legend of the semantic tokens, and encoding them the way LSP expects.
+
+use lsp_types::{SemanticToken, SemanticTokenType, SemanticTokens};
+
+pub(crate) const SUPPORTED_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::COMMENT,
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::STRING,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::ENUM_MEMBER,
+];
+
+pub(crate) fn type_index(ty: SemanticTokenType) -> u32 {
+    SUPPORTED_TYPES.iter().position(|it| *it == ty).unwrap() as u32
+}
+
+/// Encodes tokens relative to the previous one. Tokens must be pushed in order
+/// and must not span several lines.
+#[derive(Default)]
+pub(crate) struct SemanticTokensBuilder {
+    prev_line: u32,
+    prev_char: u32,
+    data: Vec<SemanticToken>,
+}
+
+impl SemanticTokensBuilder {
+    pub(crate) fn push(&mut self, line: u32, start: u32, length: u32, token_type: u32) {
+        let mut delta_start = start;
+        if line == self.prev_line && !self.data.is_empty() {
+            delta_start -= self.prev_char;
+        }
+        self.data.push(SemanticToken {
+            delta_line: line - self.prev_line,
+            delta_start,
+            length,
+            token_type,
+            token_modifiers_bitset: 0,
+        });
+        self.prev_line = line;
+        self.prev_char = start;
+    }
+
+    pub(crate) fn build(self) -> SemanticTokens {
+        SemanticTokens {
+            result_id: None,
+            data: self.data,
+        }
+    }
+}
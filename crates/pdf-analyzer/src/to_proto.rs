@@ -0,0 +1,116 @@
+//! Conversion of the results of the analyses into LSP types.
+
+use lsp_types::{
+    Diagnostic, DiagnosticSeverity, DocumentSymbol, FoldingRange, Hover, HoverContents, LocationLink, MarkupContent, MarkupKind, SemanticTokenType,
+    SemanticTokens, SymbolKind, Url,
+};
+use syntax::{SyntaxError, TextRange, TextSize};
+
+use ide::{Fold, HlRange, HlTag, HoverResult, NavigationTarget, StructureNode, StructureNodeKind};
+
+use crate::{
+    line_index::LineIndex,
+    semantic_tokens::{self, SemanticTokensBuilder},
+};
+
+pub(crate) fn range(line_index: &LineIndex<'_>, range: TextRange) -> lsp_types::Range {
+    lsp_types::Range::new(line_index.position(range.start()), line_index.position(range.end()))
+}
+
+pub(crate) fn diagnostic(line_index: &LineIndex<'_>, error: &SyntaxError) -> Diagnostic {
+    Diagnostic {
+        range: range(line_index, error.range()),
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("pdf-analyzer".to_owned()),
+        message: error.to_string(),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn symbol_kind(kind: StructureNodeKind) -> SymbolKind {
+    match kind {
+        StructureNodeKind::Object => SymbolKind::OBJECT,
+        StructureNodeKind::XRefSection => SymbolKind::NAMESPACE,
+        StructureNodeKind::XRefSubsection => SymbolKind::ARRAY,
+        StructureNodeKind::Trailer => SymbolKind::STRUCT,
+    }
+}
+
+pub(crate) fn document_symbol(line_index: &LineIndex<'_>, node: StructureNode) -> DocumentSymbol {
+    let children = node.children.into_iter().map(|it| document_symbol(line_index, it)).collect::<Vec<_>>();
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name: node.label,
+        detail: node.detail,
+        kind: symbol_kind(node.kind),
+        tags: None,
+        deprecated: None,
+        range: range(line_index, node.node_range),
+        selection_range: range(line_index, node.navigation_range),
+        children: (!children.is_empty()).then_some(children),
+    }
+}
+
+pub(crate) fn folding_range(line_index: &LineIndex<'_>, fold: Fold) -> FoldingRange {
+    let range = range(line_index, fold.range);
+    FoldingRange {
+        start_line: range.start.line,
+        start_character: None,
+        end_line: range.end.line,
+        end_character: None,
+        kind: None,
+        collapsed_text: None,
+    }
+}
+
+pub(crate) fn location_link(line_index: &LineIndex<'_>, uri: Url, target: NavigationTarget) -> Vec<LocationLink> {
+    vec![LocationLink {
+        origin_selection_range: None,
+        target_uri: uri,
+        target_range: range(line_index, target.full_range),
+        target_selection_range: range(line_index, target.focus_range),
+    }]
+}
+
+pub(crate) fn hover(line_index: &LineIndex<'_>, hover: HoverResult) -> Hover {
+    Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: hover.markup,
+        }),
+        range: Some(range(line_index, hover.range)),
+    }
+}
+
+pub(crate) fn semantic_token_type(tag: HlTag) -> SemanticTokenType {
+    match tag {
+        HlTag::Comment => SemanticTokenType::COMMENT,
+        HlTag::Keyword => SemanticTokenType::KEYWORD,
+        HlTag::Number => SemanticTokenType::NUMBER,
+        HlTag::String => SemanticTokenType::STRING,
+        HlTag::DictionaryKey => SemanticTokenType::PROPERTY,
+        HlTag::Name => SemanticTokenType::ENUM_MEMBER,
+    }
+}
+
+/// Encodes the highlighted ranges, split at line breaks, as most clients
+/// don't support tokens spanning several lines.
+pub(crate) fn semantic_tokens(text: &str, line_index: &LineIndex<'_>, highlights: Vec<HlRange>) -> SemanticTokens {
+    let mut builder = SemanticTokensBuilder::default();
+    for HlRange { range, tag } in highlights {
+        let token_type = semantic_tokens::type_index(semantic_token_type(tag));
+        let Some(token_text) = text.get(std::ops::Range::<usize>::from(range)) else {
+            continue;
+        };
+        let mut start = range.start();
+        for line in token_text.split_inclusive(['\n', '\r']) {
+            let content = line.trim_end_matches(['\n', '\r']);
+            if !content.is_empty() {
+                let position = line_index.position(start);
+                builder.push(position.line, position.character, content.encode_utf16().count() as u32, token_type);
+            }
+            start += TextSize::of(line);
+        }
+    }
+    builder.build()
+}
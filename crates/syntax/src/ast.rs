@@ -1,6 +1,10 @@
 //! Abstract Syntax Tree, layered on top of untyped `SyntaxNode`s
 
+pub mod make;
+
 mod generated;
+mod node_ext;
+mod token_ext;
 
 use std::marker::PhantomData;
 
@@ -12,6 +16,8 @@ use crate::{
 };
 
 pub use self::generated::{nodes::*, tokens::*};
+pub use self::node_ext::LiteralKind;
+pub(crate) use self::node_ext::header_edition;
 
 /// The main trait to go from untyped `SyntaxNode` to a typed ast. The
 /// conversion itself has zero runtime cost: ast and syntax nodes have exactly
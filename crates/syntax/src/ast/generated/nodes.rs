@@ -29,6 +29,15 @@ impl Body {
     pub fn indirect_object_exprs(&self) -> AstChildren<IndirectObjectExpr> { support::children(&self.syntax) }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContentStream {
+    pub(crate) syntax: SyntaxNode,
+}
+impl ContentStream {
+    #[inline]
+    pub fn content_items(&self) -> AstChildren<ContentItem> { support::children(&self.syntax) }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DictionaryExpr {
     pub(crate) syntax: SyntaxNode,
@@ -59,7 +68,7 @@ pub struct DictionaryItemKeyExpr {
 }
 impl DictionaryItemKeyExpr {
     #[inline]
-    pub fn literal(&self) -> Option<Literal> { support::child(&self.syntax) }
+    pub fn expr(&self) -> Option<Expr> { support::child(&self.syntax) }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -71,6 +80,28 @@ impl DictionaryItemValueExpr {
     pub fn expr(&self) -> Option<Expr> { support::child(&self.syntax) }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EofMarker {
+    pub(crate) syntax: SyntaxNode,
+}
+impl EofMarker {
+    #[inline]
+    pub fn eof_comment_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![eof_comment]) }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GraphicsStateBlock {
+    pub(crate) syntax: SyntaxNode,
+}
+impl GraphicsStateBlock {
+    #[inline]
+    pub fn content_items(&self) -> AstChildren<ContentItem> { support::children(&self.syntax) }
+    #[inline]
+    pub fn restore_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![Q]) }
+    #[inline]
+    pub fn save_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![q]) }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct IndirectObjectExpr {
     pub(crate) syntax: SyntaxNode,
@@ -91,10 +122,6 @@ pub struct IndirectObjectId {
     pub(crate) syntax: SyntaxNode,
 }
 impl IndirectObjectId {
-    #[inline]
-    pub fn generation_number(&self) -> Option<Literal> { support::child(&self.syntax) }
-    #[inline]
-    pub fn object_number(&self) -> Option<Literal> { support::child(&self.syntax) }
     #[inline]
     pub fn obj_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![obj]) }
 }
@@ -105,11 +132,24 @@ pub struct IndirectReferenceExpr {
 }
 impl IndirectReferenceExpr {
     #[inline]
-    pub fn generation_number(&self) -> Option<Literal> { support::child(&self.syntax) }
+    pub fn r_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![R]) }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InlineImage {
+    pub(crate) syntax: SyntaxNode,
+}
+impl InlineImage {
+    #[inline]
+    pub fn exprs(&self) -> AstChildren<Expr> { support::children(&self.syntax) }
     #[inline]
-    pub fn object_number(&self) -> Option<Literal> { support::child(&self.syntax) }
+    pub fn bi_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![BI]) }
     #[inline]
-    pub fn R_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![R]) }
+    pub fn ei_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![EI]) }
+    #[inline]
+    pub fn id_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![ID]) }
+    #[inline]
+    pub fn inline_image_data_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![inline_image_data]) }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -118,15 +158,102 @@ pub struct Literal {
 }
 impl Literal {}
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MarkedContent {
+    pub(crate) syntax: SyntaxNode,
+}
+impl MarkedContent {
+    #[inline]
+    pub fn content_items(&self) -> AstChildren<ContentItem> { support::children(&self.syntax) }
+    #[inline]
+    pub fn operands(&self) -> Option<Operands> { support::child(&self.syntax) }
+    #[inline]
+    pub fn bdc_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![BDC]) }
+    #[inline]
+    pub fn bmc_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![BMC]) }
+    #[inline]
+    pub fn emc_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![EMC]) }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ObjectStream {
+    pub(crate) syntax: SyntaxNode,
+}
+impl ObjectStream {
+    #[inline]
+    pub fn object_stream_header(&self) -> Option<ObjectStreamHeader> { support::child(&self.syntax) }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ObjectStreamEntry {
+    pub(crate) syntax: SyntaxNode,
+}
+impl ObjectStreamEntry {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ObjectStreamHeader {
+    pub(crate) syntax: SyntaxNode,
+}
+impl ObjectStreamHeader {
+    #[inline]
+    pub fn entries(&self) -> AstChildren<ObjectStreamEntry> { support::children(&self.syntax) }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Operands {
+    pub(crate) syntax: SyntaxNode,
+}
+impl Operands {
+    #[inline]
+    pub fn exprs(&self) -> AstChildren<Expr> { support::children(&self.syntax) }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Operation {
+    pub(crate) syntax: SyntaxNode,
+}
+impl Operation {
+    #[inline]
+    pub fn operands(&self) -> Option<Operands> { support::child(&self.syntax) }
+    #[inline]
+    pub fn operator_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![operator]) }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PdfDocument {
     pub(crate) syntax: SyntaxNode,
 }
 impl PdfDocument {
+    #[inline]
+    pub fn pdf_header(&self) -> Option<PdfHeader> { support::child(&self.syntax) }
+    #[inline]
+    pub fn revisions(&self) -> AstChildren<Revision> { support::children(&self.syntax) }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PdfHeader {
+    pub(crate) syntax: SyntaxNode,
+}
+impl PdfHeader {
+    #[inline]
+    pub fn binary_comment_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![binary_comment]) }
+    #[inline]
+    pub fn header_comment_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![header_comment]) }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Revision {
+    pub(crate) syntax: SyntaxNode,
+}
+impl Revision {
     #[inline]
     pub fn body(&self) -> Option<Body> { support::child(&self.syntax) }
     #[inline]
+    pub fn eof_marker(&self) -> Option<EofMarker> { support::child(&self.syntax) }
+    #[inline]
     pub fn trailer(&self) -> Option<Trailer> { support::child(&self.syntax) }
+    #[inline]
+    pub fn x_ref_table(&self) -> Option<XRefTable> { support::child(&self.syntax) }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -144,6 +271,19 @@ impl StreamExpr {
     pub fn stream_data_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![stream_data]) }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextObject {
+    pub(crate) syntax: SyntaxNode,
+}
+impl TextObject {
+    #[inline]
+    pub fn content_items(&self) -> AstChildren<ContentItem> { support::children(&self.syntax) }
+    #[inline]
+    pub fn bt_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![BT]) }
+    #[inline]
+    pub fn et_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![ET]) }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Trailer {
     pub(crate) syntax: SyntaxNode,
@@ -166,10 +306,6 @@ pub struct XRefEntry {
 impl XRefEntry {
     #[inline]
     pub fn free_or_used(&self) -> Option<XRefEntryType> { support::child(&self.syntax) }
-    #[inline]
-    pub fn generation_number(&self) -> Option<Literal> { support::child(&self.syntax) }
-    #[inline]
-    pub fn offset(&self) -> Option<Literal> { support::child(&self.syntax) }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -199,10 +335,6 @@ pub struct XRefSubsection {
     pub(crate) syntax: SyntaxNode,
 }
 impl XRefSubsection {
-    #[inline]
-    pub fn count(&self) -> Option<Literal> { support::child(&self.syntax) }
-    #[inline]
-    pub fn first_object(&self) -> Option<Literal> { support::child(&self.syntax) }
     #[inline]
     pub fn x_ref_entrys(&self) -> AstChildren<XRefEntry> { support::children(&self.syntax) }
 }
@@ -216,6 +348,15 @@ impl XRefTable {
     pub fn x_ref_sections(&self) -> AstChildren<XRefSection> { support::children(&self.syntax) }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ContentItem {
+    GraphicsStateBlock(GraphicsStateBlock),
+    InlineImage(InlineImage),
+    MarkedContent(MarkedContent),
+    Operation(Operation),
+    TextObject(TextObject),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Expr {
     ArrayExpr(ArrayExpr),
@@ -265,6 +406,27 @@ impl AstNode for Body {
     #[inline]
     fn syntax(&self) -> &SyntaxNode { &self.syntax }
 }
+impl AstNode for ContentStream {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        CONTENT_STREAM
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == CONTENT_STREAM }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
 impl AstNode for DictionaryExpr {
     #[inline]
     fn kind() -> SyntaxKind
@@ -349,6 +511,48 @@ impl AstNode for DictionaryItemValueExpr {
     #[inline]
     fn syntax(&self) -> &SyntaxNode { &self.syntax }
 }
+impl AstNode for EofMarker {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        EOF_MARKER
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == EOF_MARKER }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl AstNode for GraphicsStateBlock {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        GRAPHICS_STATE_BLOCK
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == GRAPHICS_STATE_BLOCK }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
 impl AstNode for IndirectObjectExpr {
     #[inline]
     fn kind() -> SyntaxKind
@@ -412,6 +616,27 @@ impl AstNode for IndirectReferenceExpr {
     #[inline]
     fn syntax(&self) -> &SyntaxNode { &self.syntax }
 }
+impl AstNode for InlineImage {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        INLINE_IMAGE
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == INLINE_IMAGE }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
 impl AstNode for Literal {
     #[inline]
     fn kind() -> SyntaxKind
@@ -433,6 +658,132 @@ impl AstNode for Literal {
     #[inline]
     fn syntax(&self) -> &SyntaxNode { &self.syntax }
 }
+impl AstNode for MarkedContent {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        MARKED_CONTENT
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == MARKED_CONTENT }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl AstNode for ObjectStream {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        OBJECT_STREAM
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == OBJECT_STREAM }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl AstNode for ObjectStreamEntry {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        OBJECT_STREAM_ENTRY
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == OBJECT_STREAM_ENTRY }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl AstNode for ObjectStreamHeader {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        OBJECT_STREAM_HEADER
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == OBJECT_STREAM_HEADER }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl AstNode for Operands {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        OPERANDS
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == OPERANDS }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl AstNode for Operation {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        OPERATION
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == OPERATION }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
 impl AstNode for PdfDocument {
     #[inline]
     fn kind() -> SyntaxKind
@@ -454,6 +805,48 @@ impl AstNode for PdfDocument {
     #[inline]
     fn syntax(&self) -> &SyntaxNode { &self.syntax }
 }
+impl AstNode for PdfHeader {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        PDF_HEADER
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == PDF_HEADER }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
+impl AstNode for Revision {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        REVISION
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == REVISION }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
 impl AstNode for StreamExpr {
     #[inline]
     fn kind() -> SyntaxKind
@@ -475,6 +868,27 @@ impl AstNode for StreamExpr {
     #[inline]
     fn syntax(&self) -> &SyntaxNode { &self.syntax }
 }
+impl AstNode for TextObject {
+    #[inline]
+    fn kind() -> SyntaxKind
+    where
+        Self: Sized,
+    {
+        TEXT_OBJECT
+    }
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { kind == TEXT_OBJECT }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode { &self.syntax }
+}
 impl AstNode for Trailer {
     #[inline]
     fn kind() -> SyntaxKind
@@ -601,6 +1015,52 @@ impl AstNode for XRefTable {
     #[inline]
     fn syntax(&self) -> &SyntaxNode { &self.syntax }
 }
+impl From<GraphicsStateBlock> for ContentItem {
+    #[inline]
+    fn from(node: GraphicsStateBlock) -> ContentItem { ContentItem::GraphicsStateBlock(node) }
+}
+impl From<InlineImage> for ContentItem {
+    #[inline]
+    fn from(node: InlineImage) -> ContentItem { ContentItem::InlineImage(node) }
+}
+impl From<MarkedContent> for ContentItem {
+    #[inline]
+    fn from(node: MarkedContent) -> ContentItem { ContentItem::MarkedContent(node) }
+}
+impl From<Operation> for ContentItem {
+    #[inline]
+    fn from(node: Operation) -> ContentItem { ContentItem::Operation(node) }
+}
+impl From<TextObject> for ContentItem {
+    #[inline]
+    fn from(node: TextObject) -> ContentItem { ContentItem::TextObject(node) }
+}
+impl AstNode for ContentItem {
+    #[inline]
+    fn can_cast(kind: SyntaxKind) -> bool { matches!(kind, GRAPHICS_STATE_BLOCK | INLINE_IMAGE | MARKED_CONTENT | OPERATION | TEXT_OBJECT) }
+    #[inline]
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        let res = match syntax.kind() {
+            GRAPHICS_STATE_BLOCK => ContentItem::GraphicsStateBlock(GraphicsStateBlock { syntax }),
+            INLINE_IMAGE => ContentItem::InlineImage(InlineImage { syntax }),
+            MARKED_CONTENT => ContentItem::MarkedContent(MarkedContent { syntax }),
+            OPERATION => ContentItem::Operation(Operation { syntax }),
+            TEXT_OBJECT => ContentItem::TextObject(TextObject { syntax }),
+            _ => return None,
+        };
+        Some(res)
+    }
+    #[inline]
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            ContentItem::GraphicsStateBlock(it) => &it.syntax,
+            ContentItem::InlineImage(it) => &it.syntax,
+            ContentItem::MarkedContent(it) => &it.syntax,
+            ContentItem::Operation(it) => &it.syntax,
+            ContentItem::TextObject(it) => &it.syntax,
+        }
+    }
+}
 impl From<ArrayExpr> for Expr {
     #[inline]
     fn from(node: ArrayExpr) -> Expr { Expr::ArrayExpr(node) }
@@ -641,6 +1101,9 @@ impl AstNode for Expr {
         }
     }
 }
+impl std::fmt::Display for ContentItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
 impl std::fmt::Display for Expr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
 }
@@ -650,6 +1113,9 @@ impl std::fmt::Display for ArrayExpr {
 impl std::fmt::Display for Body {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
 }
+impl std::fmt::Display for ContentStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
 impl std::fmt::Display for DictionaryExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
 }
@@ -662,6 +1128,12 @@ impl std::fmt::Display for DictionaryItemKeyExpr {
 impl std::fmt::Display for DictionaryItemValueExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
 }
+impl std::fmt::Display for EofMarker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
+impl std::fmt::Display for GraphicsStateBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
 impl std::fmt::Display for IndirectObjectExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
 }
@@ -671,15 +1143,45 @@ impl std::fmt::Display for IndirectObjectId {
 impl std::fmt::Display for IndirectReferenceExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
 }
+impl std::fmt::Display for InlineImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
 impl std::fmt::Display for Literal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
 }
+impl std::fmt::Display for MarkedContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
+impl std::fmt::Display for ObjectStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
+impl std::fmt::Display for ObjectStreamEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
+impl std::fmt::Display for ObjectStreamHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
+impl std::fmt::Display for Operands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
 impl std::fmt::Display for PdfDocument {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
 }
+impl std::fmt::Display for PdfHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
+impl std::fmt::Display for Revision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
 impl std::fmt::Display for StreamExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
 }
+impl std::fmt::Display for TextObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
+}
 impl std::fmt::Display for Trailer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self.syntax(), f) }
 }
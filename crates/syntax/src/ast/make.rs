@@ -0,0 +1,65 @@
+//! Constructors of small syntax trees, to insert into trees made mutable with
+//! `clone_for_update`.
+//!
+//! The trees are made by parsing a snippet of text, so they are exactly what
+//! the parser would produce for that text.
+
+use crate::{AstNode, Edition, PdfDocument, SyntaxKind, SyntaxToken, XRefEntryKind, ast};
+
+pub fn int_number(value: u64) -> ast::Literal {
+    ast_from_text(&format!("[{value}]"))
+}
+
+/// A dictionary item whose value is an integer, e.g. `/Size 12`.
+pub fn dictionary_item_int(key: &str, value: u64) -> ast::DictionaryItemExpr {
+    ast_from_text(&format!("<< {key} {value} >>"))
+}
+
+pub fn whitespace(text: &str) -> SyntaxToken {
+    assert!(text.bytes().all(|it| matches!(it, b' ' | b'\t' | b'\x0c' | b'\0')), "not whitespace: {text:?}");
+    token_from_text(text, SyntaxKind::WHITESPACE)
+}
+
+/// A cross-reference table with one subsection per `(first object, entries)`
+/// pair, whose entries end with a space and a line feed.
+///
+/// # Panics
+///
+/// Panics on compressed entries, which only cross-reference streams can hold.
+pub fn x_ref_table(subsections: &[(u32, Vec<XRefEntryKind>)]) -> ast::XRefTable {
+    let mut text = String::from("xref\n");
+    for (first, entries) in subsections {
+        text += &format!("{first} {}\n", entries.len());
+        for entry in entries {
+            text += &match *entry {
+                XRefEntryKind::Free { next_free_object, generation } => format!("{next_free_object:010} {generation:05} f \n"),
+                XRefEntryKind::InUse { offset, generation } => format!("{offset:010} {generation:05} n \n"),
+                XRefEntryKind::Compressed { .. } => panic!("cross-reference tables can't hold compressed entries"),
+            };
+        }
+    }
+    ast_from_text(&format!("{text}trailer\n<< >>\nstartxref\n0\n%%EOF\n"))
+}
+
+fn ast_from_text<N: AstNode>(text: &str) -> N {
+    let parse = PdfDocument::parse(text.as_bytes(), Edition::CURRENT);
+    let node = match parse.tree().syntax().descendants().find_map(N::cast) {
+        Some(it) => it,
+        None => panic!("failed to make a node of type `{}` from {text:?}", std::any::type_name::<N>()),
+    };
+    let node = node.clone_for_update();
+    node.syntax().detach();
+    node
+}
+
+fn token_from_text(text: &str, kind: SyntaxKind) -> SyntaxToken {
+    let parse = PdfDocument::parse(text.as_bytes(), Edition::CURRENT);
+    let root = parse.tree().syntax().clone_for_update();
+    let token = root
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == kind)
+        .unwrap_or_else(|| panic!("failed to make a {kind:?} token from {text:?}"));
+    token.detach();
+    token
+}
@@ -0,0 +1,242 @@
+//! Various extension methods to ast Nodes, which are hard to code-generate.
+
+use parser::Edition;
+
+use crate::{
+    SyntaxKind, SyntaxToken, T,
+    ast::{self, AstNode, AstToken, support},
+};
+
+impl ast::PdfHeader {
+    /// The edition declared by the header, e.g. [`Edition::Pdf17`] for
+    /// `%PDF-1.7`, or `None` if the version is unknown.
+    pub fn edition(&self) -> Option<Edition> {
+        header_edition(self.header_comment_token()?.text())
+    }
+}
+
+/// Parses the version of a `%PDF-x.y` header line.
+pub(crate) fn header_edition(line: &[u8]) -> Option<Edition> {
+    let version = line.strip_prefix(b"%PDF-")?.trim_ascii_end();
+    std::str::from_utf8(version).ok()?.parse().ok()
+}
+
+impl ast::Literal {
+    /// The token holding the value of this literal.
+    pub fn token(&self) -> SyntaxToken {
+        self.syntax()
+            .children_with_tokens()
+            .find_map(|it| it.into_token().filter(|it| !it.kind().is_trivia()))
+            .unwrap()
+    }
+
+    pub fn kind(&self) -> LiteralKind {
+        let token = self.token();
+        match token.kind() {
+            SyntaxKind::INT_NUMBER => LiteralKind::IntNumber(ast::IntNumber::cast(token).unwrap()),
+            SyntaxKind::REAL_NUMBER => LiteralKind::RealNumber(ast::RealNumber::cast(token).unwrap()),
+            SyntaxKind::LITERAL_STRING => LiteralKind::LiteralString(ast::LiteralString::cast(token).unwrap()),
+            SyntaxKind::HEX_STRING => LiteralKind::HexString(ast::HexString::cast(token).unwrap()),
+            SyntaxKind::NAME => LiteralKind::Name(ast::Name::cast(token).unwrap()),
+            T![true] => LiteralKind::Bool(true),
+            T![false] => LiteralKind::Bool(false),
+            T![null] => LiteralKind::Null,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LiteralKind {
+    IntNumber(ast::IntNumber),
+    RealNumber(ast::RealNumber),
+    LiteralString(ast::LiteralString),
+    HexString(ast::HexString),
+    Name(ast::Name),
+    Bool(bool),
+    Null,
+}
+
+// Nodes with several labeled children of the same type (like the object and
+// generation numbers, both `Literal`s) can't use the generated `support::child`
+// accessors, so the children are picked by position.
+
+impl ast::IndirectObjectId {
+    pub fn object_number(&self) -> Option<ast::Literal> {
+        support::children(self.syntax()).next()
+    }
+
+    pub fn generation_number(&self) -> Option<ast::Literal> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+impl ast::IndirectReferenceExpr {
+    pub fn object_number(&self) -> Option<ast::Literal> {
+        support::children(self.syntax()).next()
+    }
+
+    pub fn generation_number(&self) -> Option<ast::Literal> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+impl ast::ObjectStreamEntry {
+    pub fn object_number(&self) -> Option<ast::Literal> {
+        support::children(self.syntax()).next()
+    }
+
+    /// The byte offset of the object, relative to the first object in the
+    /// stream (the value of `/First`).
+    pub fn offset(&self) -> Option<ast::Literal> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+impl ast::XRefSubsection {
+    pub fn first_object(&self) -> Option<ast::Literal> {
+        support::children(self.syntax()).next()
+    }
+
+    pub fn count(&self) -> Option<ast::Literal> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+impl ast::XRefEntry {
+    pub fn offset(&self) -> Option<ast::Literal> {
+        support::children(self.syntax()).next()
+    }
+
+    pub fn generation_number(&self) -> Option<ast::Literal> {
+        support::children(self.syntax()).nth(1)
+    }
+}
+
+impl ast::DictionaryExpr {
+    /// Returns the value stored under `key`, which includes the leading
+    /// solidus, e.g. `b"/Type"`. Keys are compared with `#xx` escapes decoded.
+    ///
+    /// If the key appears more than once, the first value wins.
+    pub fn get(&self, key: &[u8]) -> Option<ast::Expr> {
+        self.dictionary_item_exprs().find(|it| it.key_name().is_some_and(|it| it.is(key)))?.value()
+    }
+
+    /// Iterates over the keys which are names, in order.
+    pub fn keys(&self) -> impl Iterator<Item = ast::Name> {
+        self.dictionary_item_exprs().filter_map(|it| it.key_name())
+    }
+}
+
+impl ast::DictionaryItemExpr {
+    pub fn key(&self) -> Option<ast::Expr> {
+        self.dictionary_item_key_expr()?.expr()
+    }
+
+    /// The key of this item, if it's a name as it should be.
+    pub fn key_name(&self) -> Option<ast::Name> {
+        match self.key()? {
+            ast::Expr::Literal(it) => ast::Name::cast(it.token()),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> Option<ast::Expr> {
+        self.dictionary_item_value_expr()?.expr()
+    }
+}
+
+impl ast::Expr {
+    /// The value of this expression if it's an integer literal.
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            ast::Expr::Literal(it) => match it.kind() {
+                ast::LiteralKind::IntNumber(it) => it.value().ok(),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether this expression is the name `name` (including the leading
+    /// solidus, e.g. `b"/ObjStm"`).
+    pub fn is_name(&self, name: &[u8]) -> bool {
+        match self {
+            ast::Expr::Literal(it) => match it.kind() {
+                ast::LiteralKind::Name(it) => it.is(name),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl ast::IndirectObjectExpr {
+    /// Whether the object is a cross-reference stream, i.e. a stream with
+    /// `/Type /XRef`.
+    pub fn is_x_ref_stream(&self) -> bool {
+        self.stream_expr()
+            .and_then(|it| it.dictionary_expr()?.get(b"/Type"))
+            .is_some_and(|it| it.is_name(b"/XRef"))
+    }
+}
+
+impl ast::StreamExpr {
+    /// The encoded bytes of the stream, i.e. the data between the end-of-line
+    /// marker following the `stream` keyword and the one preceding
+    /// `endstream`.
+    ///
+    /// If `/Length` is a direct integer that fits into the data, exactly that
+    /// many bytes are returned. The data is still encrypted in an encrypted
+    /// file.
+    pub fn raw_data(&self) -> Option<Vec<u8>> {
+        let token = self.stream_data_token()?;
+        let data = token.text();
+        let data = data.strip_prefix(b"\r\n").or_else(|| data.strip_prefix(b"\n")).unwrap_or(data);
+
+        let length = self.dictionary_expr().and_then(|it| it.get(b"/Length")).and_then(|it| it.as_int());
+        let data = match length.and_then(|it| usize::try_from(it).ok()) {
+            Some(length) if length <= data.len() => &data[..length],
+            _ => {
+                let data = data.strip_suffix(b"\n").unwrap_or(data);
+                data.strip_suffix(b"\r").unwrap_or(data)
+            }
+        };
+        Some(data.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AstNode, AstToken, Edition, PdfDocument, ast};
+
+    fn dictionary(text: &str) -> ast::DictionaryExpr {
+        let parse = PdfDocument::parse(text.as_bytes(), Edition::CURRENT);
+        parse.syntax_node().descendants().find_map(ast::DictionaryExpr::cast).unwrap()
+    }
+
+    #[test]
+    fn dictionary_lookup() {
+        let dictionary = dictionary("<< /Type /Page /Kids [1 0 R] /Type /Other /Nested << /Type /Inner >> >>");
+
+        assert!(dictionary.get(b"/Type").unwrap().is_name(b"/Page"));
+        assert!(matches!(dictionary.get(b"/Kids"), Some(ast::Expr::ArrayExpr(_))));
+        assert!(dictionary.get(b"/Inner").is_none());
+        assert!(dictionary.get(b"Type").is_none());
+
+        let keys: Vec<_> = dictionary.keys().map(|it| it.text().to_vec()).collect();
+        assert_eq!(keys, vec![b"/Type".to_vec(), b"/Kids".to_vec(), b"/Type".to_vec(), b"/Nested".to_vec()]);
+    }
+
+    #[test]
+    fn dictionary_lookup_in_malformed_dictionaries() {
+        // The stray `2` doesn't shift the keys following it.
+        let dictionary = dictionary("<< /A 1 2 /B 3 (k) (v) /C >>");
+
+        assert_eq!(dictionary.get(b"/A").and_then(|it| it.as_int()), Some(1));
+        assert_eq!(dictionary.get(b"/B").and_then(|it| it.as_int()), Some(3));
+        assert!(dictionary.get(b"/C").is_none());
+        assert_eq!(dictionary.dictionary_item_exprs().count(), 4);
+        assert_eq!(dictionary.keys().count(), 3);
+    }
+}
@@ -0,0 +1,331 @@
+//! There are many AstNodes, but only a few tokens, so we hand-write them here.
+//!
+//! The `value` accessors decode tokens the way readers do, accepting the
+//! common deviations from ISO `32000-2:2020` which don't lose information. Input
+//! which can't be decoded is reported as a [`SyntaxError`] covering the
+//! offending bytes.
+
+use std::ops::Range;
+
+use crate::{
+    SyntaxError, SyntaxToken, TextRange, TextSize,
+    ast::{self, AstToken},
+};
+
+impl ast::IntNumber {
+    /// The value of this integer.
+    ///
+    /// Like most readers, this ignores signs in the middle of the digits
+    /// (`12-3` is `123`).
+    ///
+    /// See ISO `32000-2:2020`, Section 7.3.3 Numeric Objects.
+    pub fn value(&self) -> Result<i64, SyntaxError> {
+        let (negative, digits) = split_number(self.syntax())?;
+        let digits = std::str::from_utf8(&digits).unwrap();
+        let value: i64 = digits.parse().map_err(|_| error(self.syntax(), "integer is too large", 0..self.text().len()))?;
+        Ok(if negative { -value } else { value })
+    }
+}
+
+impl ast::RealNumber {
+    /// The value of this real number.
+    ///
+    /// Signs are handled like for [`ast::IntNumber::value`]. Digits may be
+    /// omitted on either side of the point (`4.`, `-.002`).
+    ///
+    /// See ISO `32000-2:2020`, Section 7.3.3 Numeric Objects.
+    pub fn value(&self) -> Result<f64, SyntaxError> {
+        let (negative, mut digits) = split_number(self.syntax())?;
+        if let Some(second) = self.text().iter().enumerate().filter(|(_, b)| **b == b'.').nth(1).map(|(i, _)| i) {
+            return Err(error(self.syntax(), "real number has more than one decimal point", second..second + 1));
+        }
+        // `f64::from_str` accepts `4.` and `.5`, but not a lone point.
+        digits.insert(0, b'0');
+        let value: f64 = std::str::from_utf8(&digits).unwrap().parse().unwrap();
+        Ok(if negative { -value } else { value })
+    }
+}
+
+/// Splits a numeric token into its sign and its digits (and decimal point),
+/// dropping any other sign.
+fn split_number(token: &SyntaxToken) -> Result<(bool, Vec<u8>), SyntaxError> {
+    let text = token.text();
+    let negative = text.first() == Some(&b'-');
+    let digits: Vec<u8> = text.iter().copied().filter(|&b| b != b'+' && b != b'-').collect();
+    if !digits.iter().any(u8::is_ascii_digit) {
+        return Err(error(token, "number has no digits", 0..text.len()));
+    }
+    Ok((negative, digits))
+}
+
+impl ast::LiteralString {
+    /// The bytes of this string, with escape sequences decoded and the
+    /// enclosing parentheses removed.
+    ///
+    /// An end-of-line marker (CR, LF or CR LF) in the string is read as a
+    /// single LF, unless it follows a backslash, which makes it a line
+    /// continuation. An unknown escape sequence stands for the escaped byte
+    /// and an octal escape which overflows a byte keeps its low-order bits.
+    ///
+    /// The bytes are as written in the file, so they are still encrypted in
+    /// an encrypted file.
+    ///
+    /// See ISO `32000-2:2020`, Section 7.3.4.2 Literal strings.
+    pub fn value(&self) -> Result<Vec<u8>, SyntaxError> {
+        let text = self.text();
+        let Some(content) = text.strip_prefix(b"(").and_then(|it| it.strip_suffix(b")")) else {
+            return Err(error(self.syntax(), "unterminated literal string", text.len()..text.len()));
+        };
+        let mut value = Vec::with_capacity(content.len());
+        let mut i = 0;
+        while i < content.len() {
+            match content[i] {
+                b'\\' => {
+                    let Some(&escaped) = content.get(i + 1) else {
+                        return Err(error(self.syntax(), "unterminated escape sequence", i + 1..i + 2));
+                    };
+                    i += 2;
+                    match escaped {
+                        b'n' => value.push(b'\n'),
+                        b'r' => value.push(b'\r'),
+                        b't' => value.push(b'\t'),
+                        b'b' => value.push(0x08),
+                        b'f' => value.push(0x0C),
+                        b'0'..=b'7' => {
+                            let mut byte = escaped - b'0';
+                            for _ in 0..2 {
+                                match content.get(i) {
+                                    Some(&digit @ b'0'..=b'7') => {
+                                        byte = byte.wrapping_mul(8).wrapping_add(digit - b'0');
+                                        i += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            value.push(byte);
+                        }
+                        b'\r' => {
+                            if content.get(i) == Some(&b'\n') {
+                                i += 1;
+                            }
+                        }
+                        b'\n' => (),
+                        // `\(`, `\)` and `\\` as well as unknown escapes.
+                        byte => value.push(byte),
+                    }
+                }
+                b'\r' => {
+                    value.push(b'\n');
+                    i += if content.get(i + 1) == Some(&b'\n') { 2 } else { 1 };
+                }
+                byte => {
+                    value.push(byte);
+                    i += 1;
+                }
+            }
+        }
+        Ok(value)
+    }
+}
+
+impl ast::HexString {
+    /// The bytes of this string, decoded from pairs of hexadecimal digits.
+    ///
+    /// White-space is ignored, and a missing final digit is taken to be 0.
+    /// The bytes are still encrypted in an encrypted file.
+    ///
+    /// See ISO `32000-2:2020`, Section 7.3.4.3 Hexadecimal strings.
+    pub fn value(&self) -> Result<Vec<u8>, SyntaxError> {
+        let text = self.text();
+        let Some(content) = text.strip_prefix(b"<").and_then(|it| it.strip_suffix(b">")) else {
+            return Err(error(self.syntax(), "unterminated hexadecimal string", text.len()..text.len()));
+        };
+        let mut value = Vec::with_capacity(content.len() / 2);
+        let mut high = None;
+        for (i, &b) in content.iter().enumerate() {
+            if is_white_space(b) {
+                continue;
+            }
+            let Some(digit) = hex_digit(b) else {
+                return Err(error(self.syntax(), "invalid character in hexadecimal string", i + 1..i + 2));
+            };
+            match high.take() {
+                Some(high) => value.push(high << 4 | digit),
+                None => high = Some(digit),
+            }
+        }
+        value.extend(high.map(|it| it << 4));
+        Ok(value)
+    }
+}
+
+impl ast::Name {
+    /// The bytes of this name, without the leading solidus and with `#xx`
+    /// escapes decoded.
+    ///
+    /// See ISO `32000-2:2020`, Section 7.3.5 Name objects.
+    pub fn value(&self) -> Result<Vec<u8>, SyntaxError> {
+        let text = self.text();
+        let content = text.strip_prefix(b"/").unwrap_or(text);
+        let offset = text.len() - content.len();
+        let mut value = Vec::with_capacity(content.len());
+        let mut i = 0;
+        while i < content.len() {
+            if content[i] != b'#' {
+                value.push(content[i]);
+                i += 1;
+                continue;
+            }
+            let range = offset + i..offset + (i + 3).min(content.len());
+            match content.get(i + 1..i + 3).and_then(|it| Some(hex_digit(it[0])? << 4 | hex_digit(it[1])?)) {
+                Some(0) => return Err(error(self.syntax(), "null byte in name", range)),
+                Some(byte) => value.push(byte),
+                None => return Err(error(self.syntax(), "invalid `#` escape in name, expected two hexadecimal digits", range)),
+            }
+            i += 3;
+        }
+        Ok(value)
+    }
+
+    /// Whether this is the name `name`, given with the leading solidus (e.g.
+    /// `b"/Type"`), comparing decoded names.
+    pub fn is(&self, name: &[u8]) -> bool {
+        let text = self.text();
+        text == name || (text.contains(&b'#') && name.strip_prefix(b"/").is_some_and(|name| self.value().is_ok_and(|it| it == name)))
+    }
+}
+
+fn error(token: &SyntaxToken, message: &str, range: Range<usize>) -> SyntaxError {
+    let start = token.text_range().start();
+    let range = TextRange::new(TextSize::from(range.start as u32), TextSize::from(range.end as u32));
+    SyntaxError::new(message, range + start)
+}
+
+fn hex_digit(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|it| it as u8)
+}
+
+/// See ISO `32000-2:2020`, Table 1 White-space characters.
+fn is_white_space(b: u8) -> bool {
+    matches!(b, b'\0' | b'\t' | b'\n' | b'\x0C' | b'\r' | b' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AstNode, Edition, PdfDocument, TextSize, ast};
+
+    fn literal(text: &str) -> ast::Literal {
+        let text = format!("%PDF-2.0\n1 0 obj {text} endobj\n");
+        let parse = PdfDocument::parse(text.as_bytes(), Edition::CURRENT);
+        parse
+            .syntax_node()
+            .descendants()
+            .filter_map(ast::Literal::cast)
+            .find(|it| it.syntax().text_range().start() == 17.into())
+            .unwrap()
+    }
+
+    fn check<T>(text: &str, value: impl FnOnce(ast::LiteralKind) -> Result<T, crate::SyntaxError>) -> Result<T, String> {
+        let literal = literal(text);
+        // Report ranges relative to the token, which starts at 17.
+        value(literal.kind()).map_err(|it| format!("{:?}: {}", it.range() - TextSize::from(17), it))
+    }
+
+    fn int(text: &str) -> Result<i64, String> {
+        check(text, |kind| match kind {
+            ast::LiteralKind::IntNumber(it) => it.value(),
+            kind => panic!("{kind:?}"),
+        })
+    }
+
+    fn real(text: &str) -> Result<f64, String> {
+        check(text, |kind| match kind {
+            ast::LiteralKind::RealNumber(it) => it.value(),
+            kind => panic!("{kind:?}"),
+        })
+    }
+
+    fn string(text: &str) -> Result<Vec<u8>, String> {
+        check(text, |kind| match kind {
+            ast::LiteralKind::LiteralString(it) => it.value(),
+            ast::LiteralKind::HexString(it) => it.value(),
+            ast::LiteralKind::Name(it) => it.value(),
+            kind => panic!("{kind:?}"),
+        })
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(int("123"), Ok(123));
+        assert_eq!(int("+17"), Ok(17));
+        assert_eq!(int("-98"), Ok(-98));
+        assert_eq!(int("0043"), Ok(43));
+        assert_eq!(int("12-3"), Ok(123));
+        assert_eq!(int("-1+-"), Ok(-1));
+        assert_eq!(int("-"), Err("0..1: number has no digits".to_owned()));
+        assert_eq!(int("99999999999999999999"), Err("0..20: integer is too large".to_owned()));
+    }
+
+    #[test]
+    fn reals() {
+        assert_eq!(real("34.5"), Ok(34.5));
+        assert_eq!(real("-3.62"), Ok(-3.62));
+        assert_eq!(real("+123.6"), Ok(123.6));
+        assert_eq!(real("4."), Ok(4.0));
+        assert_eq!(real("-.002"), Ok(-0.002));
+        assert_eq!(real("0.0"), Ok(0.0));
+        assert_eq!(real("-.-5"), Ok(-0.5));
+        assert_eq!(real("1.5-"), Ok(1.5));
+        assert_eq!(real("-."), Err("0..2: number has no digits".to_owned()));
+    }
+
+    #[test]
+    fn literal_strings() {
+        assert_eq!(string("(This is a string)"), Ok(b"This is a string".to_vec()));
+        assert_eq!(string("()"), Ok(b"".to_vec()));
+        assert_eq!(string("(balanced (parentheses) too)"), Ok(b"balanced (parentheses) too".to_vec()));
+        assert_eq!(string(r"(\n\r\t\b\f\\\/)"), Ok(b"\n\r\t\x08\x0C\\/".to_vec()));
+        assert_eq!(string("(a\\\nb\\\r\nc\\\rd)"), Ok(b"abcd".to_vec()));
+        assert_eq!(string("(a\rb\r\nc\nd)"), Ok(b"a\nb\nc\nd".to_vec()));
+        assert_eq!(string(r"(\0053\53\053a\7\1234)"), Ok(b"\x053++a\x07S4".to_vec()));
+        // High-order overflow is ignored.
+        assert_eq!(string(r"(\777)"), Ok(b"\xFF".to_vec()));
+    }
+
+    #[test]
+    fn hex_strings() {
+        assert_eq!(string("<4E6F762073686D6F7A206B6120706F702E>"), Ok(b"Nov shmoz ka pop.".to_vec()));
+        assert_eq!(string("<>"), Ok(b"".to_vec()));
+        assert_eq!(string("<90 1f\nA3>"), Ok(b"\x90\x1F\xA3".to_vec()));
+        assert_eq!(string("<901FA>"), Ok(b"\x90\x1F\xA0".to_vec()));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(string("/Name1"), Ok(b"Name1".to_vec()));
+        assert_eq!(string("/"), Ok(b"".to_vec()));
+        assert_eq!(string("/A;Name_With-Various***Characters?"), Ok(b"A;Name_With-Various***Characters?".to_vec()));
+        assert_eq!(string("/Lime#20Green"), Ok(b"Lime Green".to_vec()));
+        assert_eq!(string("/paired#28#29parentheses"), Ok(b"paired()parentheses".to_vec()));
+        assert_eq!(string("/The_Key_of_F#23_Minor"), Ok(b"The_Key_of_F#_Minor".to_vec()));
+        assert_eq!(string("/A#42"), Ok(b"AB".to_vec()));
+        assert_eq!(
+            string("/A#4"),
+            Err("2..4: invalid `#` escape in name, expected two hexadecimal digits".to_owned())
+        );
+        assert_eq!(
+            string("/A#zzB"),
+            Err("2..5: invalid `#` escape in name, expected two hexadecimal digits".to_owned())
+        );
+        assert_eq!(string("/A#00"), Err("2..5: null byte in name".to_owned()));
+
+        let name = |text| match literal(text).kind() {
+            ast::LiteralKind::Name(it) => it,
+            kind => panic!("{kind:?}"),
+        };
+        assert!(name("/Type").is(b"/Type"));
+        assert!(name("/T#79pe").is(b"/Type"));
+        assert!(!name("/Type").is(b"/Types"));
+        assert!(!name("/T#7").is(b"/Type"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Checks of the lexer and the parser on arbitrary, usually broken, inputs.
+//!
+//! Whatever the input, lexing and parsing must not panic, the tree must
+//! represent the input exactly, and the parser's output must describe a
+//! single, well-nested tree. `tests.rs` runs the checks of every entry point
+//! on mutations of the test fixtures.
+
+use parser::{Edition, LexedStr, Step, TopEntryPoint};
+
+use crate::{AstNode, ContentStream, ObjectStream, PdfDocument, XRefRepair, ast, parsing};
+
+/// Panics if parsing `text` as a file panics or violates one of the
+/// invariants of the parser.
+pub(crate) fn check_document(text: &[u8]) {
+    let edition = PdfDocument::detect_edition(text).unwrap_or(Edition::CURRENT);
+    check_entry(text, TopEntryPoint::PdfDocument, edition);
+
+    let parse = PdfDocument::parse(text, edition);
+    parse.errors();
+    XRefRepair::new(&parse);
+}
+
+/// Like [`check_document`], for the decoded data of a content stream.
+pub(crate) fn check_content_stream(text: &[u8]) {
+    check_entry(text, TopEntryPoint::ContentStream, Edition::CURRENT);
+    ContentStream::parse(text, Edition::CURRENT).errors();
+}
+
+/// Like [`check_document`], for the decoded data of an object stream. The
+/// first line of `text` is taken as its header.
+pub(crate) fn check_object_stream(text: &[u8]) {
+    let first = text.iter().position(|&b| b == b'\n').map_or(text.len(), |it| it + 1);
+    check_entry(&text[..first], TopEntryPoint::ObjectStream, Edition::CURRENT);
+
+    let n = text[..first].split(u8::is_ascii_whitespace).filter(|it| !it.is_empty()).count() / 2;
+    let dictionary = format!("1 0 obj << /Type /ObjStm /N {n} /First {first} >> stream\nendstream endobj");
+    let parse = PdfDocument::parse(dictionary.as_bytes(), Edition::CURRENT);
+    let stream = parse.syntax_node().descendants().find_map(ast::StreamExpr::cast).unwrap();
+    if let Ok(objects) = ObjectStream::parse(&stream, text, Edition::CURRENT) {
+        objects.iter().for_each(|(_, it)| _ = it.errors());
+    }
+}
+
+fn check_entry(text: &[u8], entry: TopEntryPoint, edition: Edition) {
+    let lexed = match entry {
+        TopEntryPoint::ContentStream => LexedStr::new_content_stream(edition, text),
+        _ => LexedStr::new(edition, text),
+    };
+    let lexed_text: Vec<u8> = (0..lexed.len()).flat_map(|i| lexed.text(i).iter().copied()).collect();
+    assert!(lexed_text == text, "the tokens don't add up to the input");
+
+    let output = entry.parse(&lexed.to_input(edition), edition);
+    let mut stack = Vec::new();
+    let (mut roots, mut n_tokens) = (0, 0);
+    for step in output.iter() {
+        match step {
+            Step::Enter { kind } => {
+                if stack.is_empty() {
+                    roots += 1;
+                }
+                stack.push(kind);
+            }
+            Step::Exit { kind } => assert_eq!(stack.pop(), Some(kind), "unbalanced exit from a node"),
+            Step::Token { n_input_tokens, .. } => {
+                assert!(!stack.is_empty(), "token outside of the root node");
+                n_tokens += usize::from(n_input_tokens);
+            }
+            Step::Error { .. } => assert!(!stack.is_empty(), "error outside of the root node"),
+        }
+    }
+    assert!(stack.is_empty(), "unclosed nodes: {stack:?}");
+    assert_eq!(roots, 1, "the output must have a single root node");
+    let n_significant = (0..lexed.len()).filter(|&i| !lexed.kind(i).is_trivia()).count();
+    assert_eq!(n_tokens, n_significant, "not all tokens were consumed by the parser");
+
+    let (green, _errors, _eof) = parsing::build_tree(lexed, output);
+    let mut tree_text = Vec::with_capacity(text.len());
+    crate::SyntaxNode::new_root(green)
+        .text()
+        .for_each_chunk(|chunk| tree_text.extend_from_slice(chunk));
+    assert!(tree_text == text, "the tree's text differs from the input");
+}
+
+/// A small deterministic pseudo-random number generator (xorshift64*), so
+/// that every failure can be reproduced from its seed.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Rng {
+        // The state must not be zero.
+        Rng(seed ^ 0x9E37_79B9_7F4A_7C15 | 1)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A number in `0..n`, `n` must not be zero.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// Bytes and words which change the meaning of what surrounds them.
+const INTERESTING: &[&[u8]] = &[
+    b"(",
+    b")",
+    b"\\",
+    b"<",
+    b">",
+    b"<<",
+    b">>",
+    b"[",
+    b"]",
+    b"{",
+    b"}",
+    b"/",
+    b"#",
+    b"%",
+    b"\n",
+    b"\r",
+    b" ",
+    b"\0",
+    b"0",
+    b"1",
+    b"-",
+    b".",
+    b"65535",
+    b"99999999999999999999",
+    b"obj",
+    b"endobj",
+    b"stream",
+    b"endstream",
+    b"R",
+    b"xref",
+    b"trailer",
+    b"startxref",
+    b"%%EOF",
+    b"%PDF-1.7",
+    b"1 0 obj",
+    b"/Length 5",
+    b"BT",
+    b"ET",
+    b"q",
+    b"Q",
+];
+
+/// Applies one to four random edits to `text`. Chunks of `other` may be
+/// spliced in.
+pub(crate) fn mutate(text: &[u8], other: &[u8], rng: &mut Rng) -> Vec<u8> {
+    let mut text = text.to_vec();
+    for _ in 0..1 + rng.below(4) {
+        let at = rng.below(text.len() + 1);
+        let len = rng.below(text.len() - at + 1).min(1 + rng.below(16));
+        match rng.below(6) {
+            // Flip a bit.
+            0 if at < text.len() => text[at] ^= 1 << rng.below(8),
+            // Insert an interesting word.
+            1 => {
+                let word = INTERESTING[rng.below(INTERESTING.len())];
+                text.splice(at..at, word.iter().copied());
+            }
+            // Insert random bytes.
+            2 => {
+                let bytes: Vec<u8> = (0..len).map(|_| rng.next_u64() as u8).collect();
+                text.splice(at..at, bytes);
+            }
+            // Delete a range.
+            3 => {
+                text.drain(at..at + len);
+            }
+            // Duplicate a range.
+            4 => {
+                let range = text[at..at + len].to_vec();
+                text.splice(at..at, range);
+            }
+            // Splice in a chunk of the other input.
+            _ if !other.is_empty() => {
+                let start = rng.below(other.len());
+                let end = start + rng.below(other.len() - start + 1).min(64);
+                text.splice(at..at, other[start..end].iter().copied());
+            }
+            _ => text.truncate(at),
+        }
+    }
+    text
+}
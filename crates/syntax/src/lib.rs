@@ -27,17 +27,31 @@ extern crate lexer;
 #[cfg(feature = "in-rust-tree")]
 extern crate lexer;
 
+mod object_stream;
 mod parsing;
+mod repair;
 mod syntax_error;
 mod syntax_node;
 mod validation;
+mod xref;
+
+#[cfg(test)]
+mod fuzz;
+#[cfg(test)]
+mod tests;
 
 pub mod ast;
+pub mod ted;
+
+use text_edit::Indel;
 
 pub use crate::{
-    ast::{AstNode, AstToken},
+    ast::{AstNode, AstToken, ContentStream, PdfDocument},
+    object_stream::{ObjectStream, ObjectStreamError},
+    repair::{RepairGuess, XRefRepair},
     syntax_error::SyntaxError,
-    syntax_node::{SyntaxNode, SyntaxNodeChildren, SyntaxToken, SyntaxTreeBuilder},
+    syntax_node::{SyntaxElement, SyntaxNode, SyntaxNodeChildren, SyntaxToken, SyntaxTreeBuilder},
+    xref::{XRefEntryKind, XRefStream, XRefStreamError},
 };
 
 pub use parser::{Edition, SyntaxKind, T};
@@ -53,6 +67,8 @@ use std::{marker::PhantomData, sync::Arc};
 pub struct Parse<T> {
     green: GreenNode,
     errors: Option<Arc<[SyntaxError]>>,
+    /// Recorded by `PdfDocument::parse` for incremental reparsing.
+    stream_extents: Option<Arc<parsing::StreamExtents>>,
     _ty: PhantomData<fn() -> T>,
 }
 
@@ -61,6 +77,7 @@ impl<T> Clone for Parse<T> {
         Parse {
             green: self.green.clone(),
             errors: self.errors.clone(),
+            stream_extents: self.stream_extents.clone(),
             _ty: PhantomData,
         }
     }
@@ -71,6 +88,7 @@ impl<T> Parse<T> {
         Parse {
             green,
             errors: if errors.is_empty() { None } else { Some(errors.into()) },
+            stream_extents: None,
             _ty: PhantomData,
         }
     }
@@ -82,6 +100,10 @@ impl<T> Parse<T> {
     pub fn errors(&self) -> Vec<SyntaxError> {
         let mut errors = if let Some(e) = self.errors.as_deref() { e.to_vec() } else { vec![] };
         validation::validate(&self.syntax_node(), &mut errors);
+        // Lexer, parser and validation errors are collected separately, and
+        // incremental reparsing splices new errors in, so order them by
+        // position to make them independent of how the tree was built.
+        errors.sort_by_key(|it| it.range().start());
         errors
     }
 }
@@ -92,6 +114,7 @@ impl<T: AstNode> Parse<T> {
         Parse {
             green: self.green,
             errors: self.errors,
+            stream_extents: self.stream_extents,
             _ty: PhantomData,
         }
     }
@@ -121,6 +144,7 @@ impl Parse<SyntaxNode> {
             Some(Parse {
                 green: self.green,
                 errors: self.errors,
+                stream_extents: self.stream_extents,
                 _ty: PhantomData,
             })
         } else {
@@ -129,6 +153,85 @@ impl Parse<SyntaxNode> {
     }
 }
 
+impl Parse<PdfDocument> {
+    /// Applies `edit` to the parsed file, reusing as much of the old tree as
+    /// possible.
+    ///
+    /// Only the smallest self-delimited region around the edit (a token, an
+    /// array, a dictionary or an indirect object) is relexed and reparsed. If
+    /// the edit can't be confined to such a region, the whole file is parsed
+    /// again. Either way, the result is the same as parsing the edited text
+    /// from scratch.
+    pub fn reparse(&self, edit: &Indel, edition: Edition) -> Parse<PdfDocument> {
+        self.incremental_reparse(edit, edition).unwrap_or_else(|| self.full_reparse(edit, edition))
+    }
+
+    fn incremental_reparse(&self, edit: &Indel, edition: Edition) -> Option<Parse<PdfDocument>> {
+        let errors = self.errors.as_deref().unwrap_or_default();
+        let stream_extents = match &self.stream_extents {
+            Some(it) => it.clone(),
+            None => Arc::new(parsing::StreamExtents::new(&self.syntax_node(), errors)),
+        };
+        // Validation errors aren't stored, `errors` checks the new tree.
+        let (green, errors, _reparsed_range) = parsing::incremental_reparse(self.tree().syntax(), edit, errors.iter().cloned(), &stream_extents, edition)?;
+        let mut parse = Parse::new(green, errors);
+        // Edits which would change the recorded extents are never reparsed
+        // incrementally.
+        parse.stream_extents = Some(stream_extents);
+        Some(parse)
+    }
+
+    fn full_reparse(&self, edit: &Indel, edition: Edition) -> Parse<PdfDocument> {
+        let mut text = Vec::with_capacity(self.green.text_len().into());
+        self.syntax_node().text().for_each_chunk(|chunk| text.extend_from_slice(chunk));
+        edit.apply(&mut text);
+        PdfDocument::parse(&text, edition)
+    }
+}
+
+/// `PdfDocument` represents a parse tree for a single PDF file.
+impl PdfDocument {
+    pub fn parse(text: &[u8], edition: Edition) -> Parse<PdfDocument> {
+        let _p = tracing::info_span!("PdfDocument::parse").entered();
+        let (green, errors) = parsing::parse_text(text, edition);
+        let root = SyntaxNode::new_root(green.clone());
+
+        assert_eq!(root.kind(), SyntaxKind::PDF_DOCUMENT);
+        let stream_extents = parsing::StreamExtents::new(&root, &errors);
+        let mut parse = Parse::new(green, errors);
+        parse.stream_extents = Some(Arc::new(stream_extents));
+        parse
+    }
+
+    /// Detects the edition of a file from its `%PDF-x.y` header, before it's
+    /// parsed.
+    ///
+    /// Like most readers, this accepts a header anywhere in the first 1024
+    /// bytes. The catalog of the file may declare a later version, see
+    /// `/Version` in ISO `32000-2:2020`, Table 29.
+    pub fn detect_edition(text: &[u8]) -> Option<Edition> {
+        let text = &text[..text.len().min(1024)];
+        let start = text.windows(5).position(|it| it == b"%PDF-")?;
+        let line = text[start..].split(|&b| b == b'\r' || b == b'\n').next()?;
+        ast::header_edition(line)
+    }
+
+    /// The edition declared by the file header, if it has a valid one.
+    pub fn edition(&self) -> Option<Edition> {
+        self.pdf_header()?.edition()
+    }
+}
+
+/// `ContentStream` represents a parse tree for the decoded data of a content
+/// stream.
+impl ContentStream {
+    pub fn parse(text: &[u8], edition: Edition) -> Parse<ContentStream> {
+        let _p = tracing::info_span!("ContentStream::parse").entered();
+        let (green, errors) = parsing::parse_text_at(text, parser::TopEntryPoint::ContentStream, edition);
+        Parse::new(green, errors)
+    }
+}
+
 /// Matches a `SyntaxNode` against an `ast` type.
 ///
 /// # Example:
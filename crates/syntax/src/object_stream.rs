@@ -0,0 +1,196 @@
+//! Parsing of the objects stored inside object streams.
+//!
+//! An object stream is a stream with `/Type /ObjStm` whose (decoded) data
+//! starts with a header of `/N` pairs of integers, the object number and the
+//! offset of each object, followed by the objects themselves. The objects are
+//! bare, i.e. they aren't wrapped in `obj` / `endobj` keywords.
+//!
+//! See ISO `32000-2:2020`, Section 7.5.7 Object Streams.
+
+use std::{collections::BTreeMap, fmt};
+
+use parser::{Edition, TopEntryPoint};
+
+use crate::{AstNode, Parse, SyntaxNode, ast, parsing};
+
+/// The objects embedded in an object stream, each parsed into its own tree.
+#[derive(Debug, Clone)]
+pub struct ObjectStream {
+    objects: BTreeMap<u32, Parse<SyntaxNode>>,
+}
+
+/// The reason why the objects of an object stream couldn't be extracted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectStreamError {
+    /// The stream dictionary doesn't have `/Type /ObjStm`.
+    NotAnObjectStream,
+    /// A required entry of the stream dictionary is missing or is not a
+    /// non-negative integer.
+    InvalidDictionaryEntry(&'static str),
+    /// The header doesn't consist of exactly `/N` pairs of integers.
+    InvalidHeader,
+    /// The offset of an object points outside of the stream data, or before
+    /// the previous object.
+    InvalidOffset { object_number: u32 },
+}
+
+impl fmt::Display for ObjectStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectStreamError::NotAnObjectStream => write!(f, "stream is not an object stream"),
+            ObjectStreamError::InvalidDictionaryEntry(key) => write!(f, "object stream has no valid {key} entry"),
+            ObjectStreamError::InvalidHeader => write!(f, "object stream header doesn't match /N"),
+            ObjectStreamError::InvalidOffset { object_number } => write!(f, "object {object_number} has an invalid offset"),
+        }
+    }
+}
+
+impl std::error::Error for ObjectStreamError {}
+
+impl ObjectStream {
+    /// Splits the decoded `data` of `stream` into its objects and parses each
+    /// of them separately.
+    ///
+    /// The text ranges of each tree are relative to the start of the object
+    /// inside `data`, not to the enclosing file.
+    pub fn parse(stream: &ast::StreamExpr, data: &[u8], edition: Edition) -> Result<ObjectStream, ObjectStreamError> {
+        let _p = tracing::info_span!("ObjectStream::parse").entered();
+        let dictionary = stream.dictionary_expr().ok_or(ObjectStreamError::NotAnObjectStream)?;
+        if !dictionary.get(b"/Type").is_some_and(|it| it.is_name(b"/ObjStm")) {
+            return Err(ObjectStreamError::NotAnObjectStream);
+        }
+
+        let n = non_negative_entry(&dictionary, "/N")?;
+        let first = non_negative_entry(&dictionary, "/First")?;
+        if first > data.len() {
+            return Err(ObjectStreamError::InvalidDictionaryEntry("/First"));
+        }
+
+        let header = header_entries(&data[..first], edition).ok_or(ObjectStreamError::InvalidHeader)?;
+        if header.len() != n {
+            return Err(ObjectStreamError::InvalidHeader);
+        }
+
+        let mut objects = BTreeMap::new();
+        for (i, &(object_number, offset)) in header.iter().enumerate() {
+            let start = first + offset;
+            let end = header.get(i + 1).map_or(data.len(), |&(_, next_offset)| first + next_offset);
+            if start > end || end > data.len() {
+                return Err(ObjectStreamError::InvalidOffset { object_number });
+            }
+
+            let (green, errors) = parsing::parse_text_at(&data[start..end], TopEntryPoint::Expr, edition);
+            objects.insert(object_number, Parse::new(green, errors));
+        }
+
+        Ok(ObjectStream { objects })
+    }
+
+    /// Returns the tree of the object with the given number.
+    pub fn get(&self, object_number: u32) -> Option<&Parse<SyntaxNode>> {
+        self.objects.get(&object_number)
+    }
+
+    /// Iterates over the objects ordered by their object number.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &Parse<SyntaxNode>)> {
+        self.objects.iter().map(|(&object_number, parse)| (object_number, parse))
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+}
+
+fn non_negative_entry(dictionary: &ast::DictionaryExpr, key: &'static str) -> Result<usize, ObjectStreamError> {
+    dictionary
+        .get(key.as_bytes())
+        .and_then(|it| it.as_int())
+        .and_then(|it| usize::try_from(it).ok())
+        .ok_or(ObjectStreamError::InvalidDictionaryEntry(key))
+}
+
+/// Parses the `(object number, offset)` pairs of the header, which must not
+/// contain anything else.
+fn header_entries(header: &[u8], edition: Edition) -> Option<Vec<(u32, usize)>> {
+    let (green, errors) = parsing::parse_text_at(header, TopEntryPoint::ObjectStream, edition);
+    let root = ast::ObjectStream::cast(SyntaxNode::new_root(green))?;
+    if !errors.is_empty() {
+        return None;
+    }
+
+    root.object_stream_header()?
+        .entries()
+        .map(|entry| {
+            let int = |it: Option<ast::Literal>| ast::Expr::Literal(it?).as_int();
+            let object_number = u32::try_from(int(entry.object_number())?).ok()?;
+            let offset = usize::try_from(int(entry.offset())?).ok()?;
+            Some((object_number, offset))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PdfDocument;
+
+    fn object_stream(text: &[u8]) -> Result<ObjectStream, ObjectStreamError> {
+        let parse = PdfDocument::parse(text, Edition::CURRENT);
+        let stream = parse.syntax_node().descendants().find_map(ast::StreamExpr::cast).unwrap();
+        let data = stream.stream_data_token().unwrap().text().to_vec();
+        // Skip the end-of-line marker following the `stream` keyword.
+        let data = data.strip_prefix(b"\r\n").or_else(|| data.strip_prefix(b"\n")).unwrap();
+        ObjectStream::parse(&stream, data, Edition::CURRENT)
+    }
+
+    #[test]
+    fn splits_objects_by_offset() {
+        let stream =
+            object_stream(b"5 0 obj\n<< /Type /ObjStm /N 3 /First 17 >>\nstream\n11 0 12 10 13 21\n<< /A 1 >>[1 2 0 R]  (string)\nendstream\nendobj\n")
+                .unwrap();
+
+        assert_eq!(stream.len(), 3);
+        let objects: Vec<_> = stream.iter().map(|(number, parse)| (number, parse.syntax_node().text().to_string())).collect();
+        assert_eq!(
+            objects,
+            vec![(11, "<< /A 1 >>".to_owned()), (12, "[1 2 0 R]  ".to_owned()), (13, "(string)\n".to_owned())]
+        );
+
+        let array = stream.get(12).unwrap().clone().cast::<ast::ArrayExpr>().unwrap();
+        assert!(array.ok().is_ok());
+        assert!(stream.get(11).unwrap().errors().is_empty());
+    }
+
+    #[test]
+    fn integer_objects_are_not_mistaken_for_header() {
+        let stream = object_stream(b"1 0 obj\n<< /Type /ObjStm /N 2 /First 8 >>\nstream\n7 0 8 3\n42 7\nendstream\nendobj\n").unwrap();
+
+        assert_eq!(stream.get(7).unwrap().syntax_node().text().to_string(), "42 ");
+        assert_eq!(stream.get(8).unwrap().syntax_node().text().to_string(), "7\n");
+    }
+
+    #[test]
+    fn rejects_other_streams() {
+        let err = object_stream(b"1 0 obj\n<< /Type /XRef /N 1 /First 4 >>\nstream\n1 0 null\nendstream\nendobj\n").unwrap_err();
+        assert_eq!(err, ObjectStreamError::NotAnObjectStream);
+    }
+
+    #[test]
+    fn rejects_malformed_streams() {
+        let err = object_stream(b"1 0 obj\n<< /Type /ObjStm /First 4 >>\nstream\n1 0 null\nendstream\nendobj\n").unwrap_err();
+        assert_eq!(err, ObjectStreamError::InvalidDictionaryEntry("/N"));
+
+        let err = object_stream(b"1 0 obj\n<< /Type /ObjStm /N 2 /First 4 >>\nstream\n1 0 null\nendstream\nendobj\n").unwrap_err();
+        assert_eq!(err, ObjectStreamError::InvalidHeader);
+
+        let err = object_stream(b"1 0 obj\n<< /Type /ObjStm /N 2 /First 8 >>\nstream\n1 3 2 0 null true\nendstream\nendobj\n").unwrap_err();
+        assert_eq!(err, ObjectStreamError::InvalidOffset { object_number: 1 });
+
+        let err = object_stream(b"1 0 obj\n<< /Type /ObjStm /N 1 /First 4 >>\nstream\n1 99null\nendstream\nendobj\n").unwrap_err();
+        assert_eq!(err, ObjectStreamError::InvalidOffset { object_number: 1 });
+    }
+}
@@ -1,13 +1,14 @@
 //! Lexing, bridging to parser (which does the actual parsing) and
 //! incremental reparsing.
 
-// TODO: incremental reparsing
+mod reparsing;
 
 use rowan::TextRange;
 
 use crate::{SyntaxError, SyntaxTreeBuilder, syntax_node::GreenNode};
 
-#[allow(dead_code)]
+pub(crate) use crate::parsing::reparsing::{StreamExtents, incremental_reparse};
+
 pub(crate) fn parse_text(text: &[u8], edition: parser::Edition) -> (GreenNode, Vec<SyntaxError>) {
     let _p = tracing::info_span!("parse_text").entered();
     let lexed = parser::LexedStr::new(edition, text);
@@ -17,10 +18,12 @@ pub(crate) fn parse_text(text: &[u8], edition: parser::Edition) -> (GreenNode, V
     (node, errors)
 }
 
-#[allow(dead_code)]
 pub(crate) fn parse_text_at(text: &[u8], entry: parser::TopEntryPoint, edition: parser::Edition) -> (GreenNode, Vec<SyntaxError>) {
     let _p = tracing::info_span!("parse_text_at").entered();
-    let lexed = parser::LexedStr::new(edition, text);
+    let lexed = match entry {
+        parser::TopEntryPoint::ContentStream => parser::LexedStr::new_content_stream(edition, text),
+        _ => parser::LexedStr::new(edition, text),
+    };
     let parser_input = lexed.to_input(edition);
     let parser_output = entry.parse(&parser_input, edition);
     let (node, errors, _eof) = build_tree(lexed, parser_output);
@@ -0,0 +1,654 @@
+//! Implementation of incremental re-parsing.
+//!
+//! We use two simple strategies for this:
+//!   - if the edit modifies only a single token (like changing a name or a
+//!     number), we relex only this token and check if the kind stayed the same.
+//!   - otherwise, we search for the nearest self-delimited node (an indirect
+//!     object, a dictionary or an array), relex and reparse only this node and
+//!     splice the result back into the tree.
+//!
+//! Whenever a strategy can't prove that its result would be identical to a
+//! full reparse, it gives up and the caller falls back to parsing the whole
+//! file.
+//!
+//! Streams need extra care, as the `/Length` of a stream dictionary decides
+//! where the lexer ends the stream data. Stream dictionaries are therefore
+//! never reparsed on their own, and a reparsed node must contain the whole
+//! data of its streams. Edits elsewhere may still move the end of a stream
+//! without `endstream`, or with an indirect `/Length`, which is resolved
+//! through the byte offsets of the cross-reference table. Such streams are
+//! recorded with [`StreamExtents`] when the file is parsed. As long as every
+//! object an indirect `/Length` may resolve to agrees with the stream data, it
+//! doesn't matter whether the lookup succeeds, and only edits to those objects
+//! need a full reparse.
+//!
+//! The reparsed node is parsed as if it was at the top level, so nodes which
+//! would reach the parser's `NESTING_LIMIT` in their actual position are also
+//! parsed from scratch.
+
+use std::collections::HashMap;
+
+use parser::Reparser;
+use rowan::WalkEvent;
+use text_edit::Indel;
+
+use crate::{
+    SyntaxError,
+    SyntaxKind::{self, *},
+    T, TextRange, TextSize,
+    parsing::build_tree,
+    syntax_node::{GreenNode, GreenToken, NodeOrToken, SyntaxElement, SyntaxNode, SyntaxToken},
+};
+
+pub(crate) fn incremental_reparse(
+    node: &SyntaxNode,
+    edit: &Indel,
+    errors: impl IntoIterator<Item = SyntaxError>,
+    extents: &StreamExtents,
+    edition: parser::Edition,
+) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
+    if extents.fragile || extents.is_target_edited(node, edit.delete) {
+        return None;
+    }
+
+    let (green, new_errors, old_range) = reparse_token(node, edit, edition).or_else(|| reparse_block(node, edit, edition))?;
+    // The edit may also turn an object into a target, e.g. by changing its
+    // number.
+    let inserted = TextRange::at(edit.delete.start(), edit.insert_len());
+    if extents.is_target_edited(&SyntaxNode::new_root(green.clone()), inserted) {
+        return None;
+    }
+    Some((green, merge_errors(errors, new_errors, old_range, edit), old_range))
+}
+
+/// How the data of the streams of a file depends on text outside of their
+/// objects, recorded when the file is parsed.
+///
+/// The data usually ends at the first `endstream`, or where a direct
+/// `/Length` declares. Only a stream without `endstream`, or with a `/Length`
+/// which doesn't fit, may end elsewhere once text after it is edited.
+///
+/// An indirect `/Length` is resolved through byte offsets anywhere in the
+/// file. The stream doesn't depend on the outcome if every definition of the
+/// referenced object is an integer agreeing with the stream data, or not an
+/// integer at all: both a failed and a successful lookup then end the data at
+/// the first `endstream`, without an error. This holds until one of the
+/// referenced objects is edited.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct StreamExtents {
+    /// The objects referred to from stream dictionaries, sorted.
+    targets: Vec<(u64, u64)>,
+    /// Whether the data of a stream may end elsewhere after an edit outside
+    /// of its object.
+    fragile: bool,
+}
+
+impl StreamExtents {
+    pub(crate) fn new(root: &SyntaxNode, errors: &[SyntaxError]) -> StreamExtents {
+        let _p = tracing::info_span!("StreamExtents::new").entered();
+        let mut streams = Vec::new();
+        let mut definitions: HashMap<(u64, u64), Vec<Option<u64>>> = HashMap::new();
+        for node in root.descendants() {
+            match node.kind() {
+                STREAM_EXPR => {
+                    let references = stream_references(&node);
+                    streams.push((node, references));
+                }
+                INDIRECT_OBJECT_EXPR => {
+                    if let Some((id, value)) = object_definition(&node) {
+                        definitions.entry(id).or_default().push(value);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let fragile = streams.iter().any(|(stream, references)| {
+            let Some(data) = stable_stream_data(stream, errors) else {
+                return true;
+            };
+            if references.is_empty() {
+                return false;
+            }
+            // Without the lookup, the data ends at the first `endstream`.
+            data.text().windows(b"endstream".len()).any(|it| it == b"endstream")
+                || references
+                    .iter()
+                    .filter_map(|it| definitions.get(it))
+                    .flatten()
+                    .flatten()
+                    .any(|&length| !fits_stream_data(length, data.text()))
+        });
+        let mut targets: Vec<_> = streams.into_iter().flat_map(|(_, references)| references).collect();
+        targets.sort_unstable();
+        targets.dedup();
+        StreamExtents { targets, fragile }
+    }
+
+    /// Whether `range` touches one of the referenced objects.
+    fn is_target_edited(&self, root: &SyntaxNode, range: TextRange) -> bool {
+        if self.targets.is_empty() {
+            return false;
+        }
+        [range.start(), range.end()]
+            .into_iter()
+            .filter(|&offset| offset <= root.text_range().end())
+            .flat_map(|offset| root.token_at_offset(offset))
+            .flat_map(|token| std::iter::successors(token.parent(), |it| it.parent()))
+            .filter(|it| it.kind() == INDIRECT_OBJECT_EXPR)
+            .filter_map(|it| object_definition(&it))
+            .any(|(id, _)| self.targets.binary_search(&id).is_ok())
+    }
+}
+
+/// The objects referred to from the dictionary of `stream`.
+///
+/// Any reference in the stream dictionary counts: the lexer looks for
+/// `/Length` in the tokens, which may pair keys and values differently than
+/// the parser in a malformed dictionary.
+fn stream_references(stream: &SyntaxNode) -> Vec<(u64, u64)> {
+    let Some(dictionary) = stream.children().find(|it| it.kind() == DICTIONARY_EXPR) else {
+        return Vec::new();
+    };
+    dictionary
+        .descendants()
+        .filter(|it| it.kind() == INDIRECT_REFERENCE_EXPR)
+        .filter_map(|it| object_id(&it))
+        .collect()
+}
+
+/// The id of an indirect object, and the integer the lexer reads when it
+/// looks the object up: the first token after `obj`, if it's an integer.
+fn object_definition(object: &SyntaxNode) -> Option<((u64, u64), Option<u64>)> {
+    let id = object.children().find(|it| it.kind() == INDIRECT_OBJECT_ID)?;
+    let value = std::iter::successors(id.last_token()?.next_token(), |it| it.next_token())
+        .find(|it| !it.kind().is_trivia())
+        .filter(|it| it.kind() == INT_NUMBER)
+        .and_then(|it| int(&it));
+    Some((object_id(&id)?, value))
+}
+
+/// The object and generation numbers of an object id or a reference.
+fn object_id(node: &SyntaxNode) -> Option<(u64, u64)> {
+    let mut numbers = node
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == INT_NUMBER);
+    Some((int(&numbers.next()?)?, int(&numbers.next()?)?))
+}
+
+/// The data of `stream`, if it's followed by `endstream` and ends there
+/// regardless of the text after it.
+fn stable_stream_data(stream: &SyntaxNode, errors: &[SyntaxError]) -> Option<SyntaxToken> {
+    let data = stream
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == STREAM_DATA)?;
+    let next = std::iter::successors(data.next_token(), |it| it.next_token()).find(|it| !it.kind().is_trivia());
+    if next.is_none_or(|it| it.kind() != T![endstream]) {
+        return None;
+    }
+    if errors.iter().any(|it| it.range() == data.text_range()) {
+        // A length error means the `/Length` didn't fit the data.
+        let dictionary = stream.children().find(|it| it.kind() == DICTIONARY_EXPR)?;
+        let mut tokens: Vec<SyntaxToken> = dictionary.descendants_with_tokens().filter_map(|it| it.into_token()).collect();
+        tokens.push(
+            stream
+                .children_with_tokens()
+                .filter_map(|it| it.into_token())
+                .find(|it| it.kind() == T![stream])?,
+        );
+        let kinds: Vec<SyntaxKind> = tokens.iter().map(|it| it.kind()).collect();
+        let declared = parser::declared_stream_length(&kinds, |i| tokens[i].text());
+        if reaches_past_data(declared, data.text()) {
+            return None;
+        }
+    }
+    Some(data)
+}
+
+/// Whether `length` bytes of `raw_stream` are followed by nothing but
+/// whitespace, in which case the lexer reports no length error.
+fn fits_stream_data(length: u64, raw_stream: &[u8]) -> bool {
+    let data = raw_stream
+        .strip_prefix(b"\r\n")
+        .or_else(|| raw_stream.strip_prefix(b"\n"))
+        .unwrap_or(raw_stream);
+    usize::try_from(length)
+        .ok()
+        .and_then(|it| data.get(it..))
+        .is_some_and(|it| it.iter().all(|&b| matches!(b, b'\0' | b'\t' | b'\n' | b'\x0C' | b'\r' | b' ')))
+}
+
+fn int(token: &SyntaxToken) -> Option<u64> {
+    std::str::from_utf8(token.text()).ok()?.parse().ok()
+}
+
+fn reparse_token(root: &SyntaxNode, edit: &Indel, edition: parser::Edition) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
+    let prev_token = root.covering_element(edit.delete).as_token()?.clone();
+    if prev_token.parent_ancestors().any(|it| it.kind() == STREAM_EXPR) {
+        return None;
+    }
+    // Whether a comment is the binary marker depends on the file header
+    // before it, which relexing the comment alone doesn't see.
+    if prev_token.kind() == COMMENT && follows_header(&prev_token) {
+        return None;
+    }
+    match prev_token.kind() {
+        WHITESPACE | COMMENT | NAME | INT_NUMBER | REAL_NUMBER | LITERAL_STRING | HEX_STRING => {
+            let mut new_text = get_text_after_edit(prev_token.clone().into(), edit);
+            let (new_token_kind, new_err) = parser::LexedStr::single_token(edition, &new_text)?;
+
+            if new_token_kind != prev_token.kind() {
+                return None;
+            }
+
+            // Check that the edited token is not a part of the bigger token.
+            // E.g. if for source code `/Na me` the user removed the whitespace,
+            // the `/Name` would be a single token and the old tree would be invalid.
+            if let Some(next_byte) = root.text().char_at(prev_token.text_range().end()) {
+                new_text.push(next_byte);
+                let token_with_next_byte = parser::LexedStr::single_token(edition, &new_text);
+                if token_with_next_byte.is_some() {
+                    return None;
+                }
+                new_text.pop();
+            }
+            // The same goes for the token before it, e.g. when `+-` becomes
+            // `+1`.
+            if let Some(prev) = prev_token.prev_token() {
+                let token_with_prev = [prev.text(), &new_text].concat();
+                if parser::LexedStr::single_token(edition, &token_with_prev).is_some() {
+                    return None;
+                }
+            }
+
+            let new_token = GreenToken::new(rowan::SyntaxKind(prev_token.kind().into()), &new_text);
+            let range = TextRange::up_to(TextSize::try_from(new_text.len()).unwrap());
+            Some((
+                prev_token.replace_with(new_token),
+                new_err.into_iter().map(|msg| SyntaxError::new(msg, range)).collect(),
+                prev_token.text_range(),
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn follows_header(token: &SyntaxToken) -> bool {
+    std::iter::successors(token.prev_token(), |it| it.prev_token())
+        .find(|it| !matches!(it.kind(), WHITESPACE | NEWLINE))
+        .is_some_and(|it| it.kind() == HEADER_COMMENT)
+}
+
+fn reparse_block(root: &SyntaxNode, edit: &Indel, edition: parser::Edition) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
+    let (node, reparser) = find_reparsable_node(root, edit.delete)?;
+    let text = get_text_after_edit(node.clone().into(), edit);
+
+    let lexed = parser::LexedStr::new(edition, &text);
+    if !is_balanced(&lexed, node.kind()) || !has_self_contained_streams(&lexed) {
+        return None;
+    }
+
+    let parser_input = lexed.to_input(edition);
+    let tree_traversal = reparser.parse(&parser_input, edition);
+
+    let (green, new_parser_errors, is_eof) = build_tree(lexed, tree_traversal);
+    let new_node = SyntaxNode::new_root(green.clone());
+    // Streams with an indirect `/Length` are neither added nor removed, so that
+    // the recorded `StreamExtents` stay valid.
+    if !is_eof || new_node.kind() != node.kind() || has_indirect_stream_length(&node) || has_indirect_stream_length(&new_node) {
+        return None;
+    }
+    let enclosing = node.ancestors().skip(1).filter(|it| is_nested(it.kind())).count();
+    if enclosing + nesting_depth(&new_node) > parser::NESTING_LIMIT.inner() {
+        return None;
+    }
+
+    Some((node.replace_with(green), new_parser_errors, node.text_range()))
+}
+
+fn get_text_after_edit(element: SyntaxElement, edit: &Indel) -> Vec<u8> {
+    let edit = Indel::replace(edit.delete - element.text_range().start(), edit.insert.clone());
+
+    let mut text = match element {
+        NodeOrToken::Token(token) => token.text().to_vec(),
+        NodeOrToken::Node(node) => {
+            let mut text = Vec::with_capacity(node.text_range().len().into());
+            node.text().for_each_chunk(|chunk| text.extend_from_slice(chunk));
+            text
+        }
+    };
+    edit.apply(&mut text);
+    text
+}
+
+/// Finds the innermost node which can be reparsed on its own and whose
+/// delimiters are untouched by the edit.
+fn find_reparsable_node(node: &SyntaxNode, range: TextRange) -> Option<(SyntaxNode, Reparser)> {
+    let node = node.covering_element(range);
+    // Nodes inside of a stream dictionary can't be reparsed without relexing
+    // the stream data.
+    let in_stream = node.ancestors().position(|it| it.kind() == STREAM_EXPR).map_or(0, |it| it + 1);
+
+    node.ancestors()
+        .skip(in_stream)
+        .filter(|node| {
+            let node_range = node.text_range();
+            node_range.start() < range.start() && range.end() < node_range.end()
+        })
+        .find_map(|node| {
+            let reparser = Reparser::for_node(node.kind())?;
+            Some((node, reparser))
+        })
+}
+
+/// Whether a stream below `node` has a `/Length` which refers to another
+/// object.
+fn has_indirect_stream_length(node: &SyntaxNode) -> bool {
+    node.descendants()
+        .filter(|it| it.kind() == STREAM_EXPR)
+        .any(|it| !stream_references(&it).is_empty())
+}
+
+fn is_nested(kind: SyntaxKind) -> bool {
+    matches!(kind, ARRAY_EXPR | DICTIONARY_EXPR)
+}
+
+/// How deeply arrays and dictionaries are nested in `node`, including `node`
+/// itself.
+fn nesting_depth(node: &SyntaxNode) -> usize {
+    let (mut depth, mut max) = (0, 0);
+    for event in node.preorder() {
+        match event {
+            WalkEvent::Enter(node) if is_nested(node.kind()) => {
+                depth += 1;
+                max = max.max(depth);
+            }
+            WalkEvent::Leave(node) if is_nested(node.kind()) => depth -= 1,
+            _ => (),
+        }
+    }
+    max
+}
+
+/// Checks that the relexed text of a node of the given `kind` still forms
+/// exactly one node of that kind, i.e. that its delimiters are intact and
+/// that no delimiter inside of it closes the node early.
+fn is_balanced(lexed: &parser::LexedStr<'_>, kind: SyntaxKind) -> bool {
+    let tokens: Vec<SyntaxKind> = (0..lexed.len()).map(|i| lexed.kind(i)).filter(|it| !it.is_trivia()).collect();
+
+    let (opening, closing): (&[SyntaxKind], SyntaxKind) = match kind {
+        ARRAY_EXPR => (&[T!['[']], T![']']),
+        DICTIONARY_EXPR => (&[T![<<]], T![>>]),
+        INDIRECT_OBJECT_EXPR => (&[INT_NUMBER, INT_NUMBER, T![obj]], T![endobj]),
+        _ => return false,
+    };
+
+    if !tokens.starts_with(opening) || tokens.last() != Some(&closing) || tokens.len() <= opening.len() {
+        return false;
+    }
+
+    let is_object = kind == INDIRECT_OBJECT_EXPR;
+    let last = tokens.len() - 1;
+    let mut stack = Vec::new();
+    for (i, &token) in tokens.iter().enumerate() {
+        match token {
+            T!['['] | T![<<] => stack.push(token),
+            T![']'] | T![>>] => {
+                let expected = if token == T![']'] { T!['['] } else { T![<<] };
+                if stack.pop() != Some(expected) {
+                    return false;
+                }
+                // For arrays and dictionaries the outermost delimiters must be
+                // the first and the last token.
+                if !is_object && stack.is_empty() && i != last {
+                    return false;
+                }
+            }
+            T![obj] | T![endobj] if is_object && i != opening.len() - 1 && i != last => return false,
+            _ => (),
+        }
+    }
+    stack.is_empty()
+}
+
+/// Checks that the data of every stream in the relexed text ends at an
+/// `endstream` keyword, without a `/Length` reaching past it. Otherwise
+/// lexing the whole file may end the data beyond the node, e.g. at the
+/// `endstream` of the next object.
+fn has_self_contained_streams(lexed: &parser::LexedStr<'_>) -> bool {
+    let kinds: Vec<SyntaxKind> = (0..lexed.len()).map(|i| lexed.kind(i)).collect();
+    (0..kinds.len()).filter(|&i| kinds[i] == RAW_STREAM).all(|i| {
+        let next = kinds[i + 1..].iter().find(|it| !it.is_trivia());
+        if next != Some(&T![endstream]) {
+            return false;
+        }
+        lexed.error(i).is_none() || !reaches_past_data(parser::declared_stream_length(&kinds[..i], |it| lexed.text(it)), lexed.text(i))
+    })
+}
+
+/// Whether the lexer, when it fails to end `raw_stream` at the `declared`
+/// length, may have looked at the text following the data.
+fn reaches_past_data(declared: Option<parser::DeclaredLength>, raw_stream: &[u8]) -> bool {
+    let data = match raw_stream {
+        [b'\r', b'\n', data @ ..] | [b'\r' | b'\n', data @ ..] => data,
+        data => data,
+    };
+    match declared {
+        Some(parser::DeclaredLength::Direct(length)) => usize::try_from(length).is_ok_and(|it| it > data.len()),
+        _ => true,
+    }
+}
+
+fn merge_errors(
+    old_errors: impl IntoIterator<Item = SyntaxError>,
+    new_errors: Vec<SyntaxError>,
+    range_before_reparse: TextRange,
+    edit: &Indel,
+) -> Vec<SyntaxError> {
+    let mut res = Vec::new();
+
+    for old_err in old_errors {
+        let old_err_range = old_err.range();
+        if old_err_range.end() <= range_before_reparse.start() {
+            res.push(old_err);
+        } else if old_err_range.start() >= range_before_reparse.end() {
+            let inserted_len = edit.insert_len();
+            // Adding first can't underflow.
+            res.push(old_err.with_range((old_err_range + inserted_len) - edit.delete.len()));
+        }
+    }
+    res.extend(new_errors.into_iter().map(|new_err| {
+        let offset_in_range = new_err.range() + range_before_reparse.start();
+        new_err.with_range(offset_in_range)
+    }));
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use text_edit::Indel;
+
+    use super::*;
+    use crate::{AstNode, Parse, ast::PdfDocument};
+
+    /// Extracts the range marked with two `$0` markers from `text`.
+    fn extract_range(text: &str) -> (TextRange, String) {
+        let start = text.find("$0").expect("missing start marker");
+        let text = format!("{}{}", &text[..start], &text[start + 2..]);
+        let end = text.find("$0").expect("missing end marker");
+        let text = format!("{}{}", &text[..end], &text[end + 2..]);
+        let range = TextRange::new(TextSize::new(start as u32), TextSize::new(end as u32));
+        (range, text)
+    }
+
+    fn do_check(before: &str, replace_with: &str, reparsed_len: u32) {
+        let (range, before) = extract_range(before);
+        let edit = Indel::replace(range, replace_with.as_bytes().to_vec());
+        let after = {
+            let mut after = before.as_bytes().to_vec();
+            edit.apply(&mut after);
+            after
+        };
+
+        let fully_reparsed = PdfDocument::parse(&after, parser::Edition::CURRENT);
+        let incrementally_reparsed: Parse<PdfDocument> = {
+            let before = PdfDocument::parse(before.as_bytes(), parser::Edition::CURRENT);
+            let errors = before.errors.as_deref().unwrap_or_default();
+            let (green, new_errors, range) = incremental_reparse(
+                before.tree().syntax(),
+                &edit,
+                errors.iter().cloned(),
+                &StreamExtents::new(&before.syntax_node(), errors),
+                parser::Edition::CURRENT,
+            )
+            .expect("cannot incrementally reparse");
+            assert_eq!(range.len(), reparsed_len.into(), "reparsed fragment has wrong length");
+            Parse::new(green, new_errors)
+        };
+
+        assert_eq!(
+            format!("{:#?}", fully_reparsed.tree().syntax()),
+            format!("{:#?}", incrementally_reparsed.tree().syntax())
+        );
+        assert_eq!(fully_reparsed.errors(), incrementally_reparsed.errors());
+    }
+
+    fn check_reparse_matches_full_parse(before: &[u8], edit: &Indel) {
+        let mut after = before.to_vec();
+        edit.apply(&mut after);
+
+        let fully_reparsed = PdfDocument::parse(&after, parser::Edition::CURRENT);
+        let reparsed = PdfDocument::parse(before, parser::Edition::CURRENT).reparse(edit, parser::Edition::CURRENT);
+
+        assert_eq!(fully_reparsed.green, reparsed.green, "edit {edit:?} produced a different tree");
+        assert_eq!(
+            fully_reparsed.stream_extents, reparsed.stream_extents,
+            "edit {edit:?} produced different streams"
+        );
+        assert_eq!(fully_reparsed.errors(), reparsed.errors(), "edit {edit:?} produced different errors");
+    }
+
+    #[test]
+    fn reparse_block_tests() {
+        do_check("<< /Type /Page /Count $05$0 >>", "5 /Kids [ ]", 26);
+        do_check("[1 2 $03$0]", "[4 5]", 7);
+        do_check("1 0 obj\n$0<< >>$0\nendobj\n", "[1 2]", 20);
+        do_check("<< /A [1 2 << /B $0true$0 >> ] >>", "false null", 13);
+        do_check("[$0 $0]", "/A /B", 3);
+        do_check("1 0 obj\n<< /Length 4 >>\nstream\nab$0c$0d\nendstream\nendobj\n", "xyz", 52);
+        // The stream data is relexed when its declared length changes.
+        do_check("1 0 obj << /Length $017$0 >> stream\nabc\nendstream\nxyz\nendstream endobj", "3", 66);
+        do_check("1 0 obj << /Length [$0$0] >> stream\nabc\nendstream endobj", "3", 52);
+        // Edits elsewhere don't matter when an indirect length agrees with
+        // the data, whether or not the stale offsets still find it.
+        let indirect = "1 0 obj << /Length 2 0 R >> stream\nabc\nendstream endobj\n2 0 obj 3 endobj\n";
+        let xref = "xref\n0 3\n0000000000 65535 f \n0000000000 00000 n \n0000000056 00000 n \ntrailer << /Size 3 >>\nstartxref\n94\n%%EOF\n";
+        do_check(&format!("{indirect}3 0 obj [1 $02$0] endobj\n{xref}"), "2 3", 5);
+        do_check(&format!("3 0 obj [1 $02$0] endobj\n{indirect}{xref}"), "2 3", 5);
+    }
+
+    #[test]
+    fn reparse_token_tests() {
+        do_check("<< /Type /Pa$0g$0e >>", "ges", 5);
+        do_check("[1 2$03$0]", "4", 2);
+        do_check("[1 3.1$04$0]", "5", 4);
+        do_check("[1$0 $02]", "\t ", 1);
+        do_check("% abc$0d$0ef\n", "xyz", 8);
+        do_check("[(Hello $0World$0)]", "PDF", 13);
+        do_check("[<48$065$0>]", "6C", 6);
+    }
+
+    #[test]
+    fn reparse_falls_back_to_full_parse() {
+        let cases: &[(&str, &str)] = &[
+            // Closing the array early.
+            ("[1 $0$0 2]", "]"),
+            // Opening a nested dictionary which is never closed.
+            ("<< /A $01$0 >>", "<< /B"),
+            // Merging two top-level names into one.
+            ("/A$0 $0/B", ""),
+            // Merging a token with the one before it.
+            ("+$0-$0", "1"),
+            // Changing the kind of a top-level token.
+            ("1 $02$0 3", "/Two"),
+            // Touching the delimiters of the node.
+            ("$0[$01 2]", "<<"),
+            // Turning a comment into the binary marker of the header.
+            ("%PDF-1.7\n% $0$0\n", "\u{e2}\u{e3}\u{cf}\u{d3}"),
+            // Giving a stream without `endstream` a new end.
+            ("1 0 obj << >> stream\nabc\nendobj 2 0 obj [$0$0] endobj", "endstream"),
+            // Changing the object an indirect length refers to.
+            ("1 0 obj << /Length 2 0 R >> stream\nabc\nendstream endobj 2 0 obj $03$0 endobj", "4"),
+            // Turning an object into the one an indirect length refers to.
+            ("1 0 obj << /Length 2 0 R >> stream\nabc\nendstream endobj $03$0 0 obj 4 endobj", "2"),
+            // Any edit in a file with an indirect length which disagrees with
+            // the data, as the lookup may stop finding it.
+            ("1 0 obj << /Length 2 0 R >> stream\nabc\nendstream endobj 2 0 obj 2 endobj [1 $02$0]", "3"),
+        ];
+
+        for (before, replace_with) in cases {
+            let (range, before) = extract_range(before);
+            let edit = Indel::replace(range, replace_with.as_bytes().to_vec());
+            let parse = PdfDocument::parse(before.as_bytes(), parser::Edition::CURRENT);
+            let extents = StreamExtents::new(&parse.syntax_node(), parse.errors.as_deref().unwrap_or_default());
+            assert!(
+                incremental_reparse(parse.tree().syntax(), &edit, Vec::new(), &extents, parser::Edition::CURRENT).is_none(),
+                "{before:?} was reparsed incrementally"
+            );
+            check_reparse_matches_full_parse(before.as_bytes(), &edit);
+        }
+
+        // Nesting an array one level deeper than the limit.
+        let depth = parser::NESTING_LIMIT.inner() - 1;
+        let before = ["[".repeat(depth), "[1 2]".to_owned(), "]".repeat(depth)].concat();
+        let edit = Indel::replace(TextRange::at(TextSize::new(depth as u32 + 1), 1.into()), b"[1]".to_vec());
+        let parse = PdfDocument::parse(before.as_bytes(), parser::Edition::CURRENT);
+        let extents = StreamExtents::default();
+        assert!(incremental_reparse(parse.tree().syntax(), &edit, Vec::new(), &extents, parser::Edition::CURRENT).is_none());
+        check_reparse_matches_full_parse(before.as_bytes(), &edit);
+    }
+
+    #[test]
+    fn reparse_matches_full_parse_for_fixtures() {
+        let fixtures: &[&[u8]] = &[
+            include_bytes!("../../../parser/test_data/parser/ok/pdf_7_3_6_arrays.pdf"),
+            include_bytes!("../../../parser/test_data/parser/ok/pdf_7_3_7_dictionaries.pdf"),
+            include_bytes!("../../../parser/test_data/parser/ok/pdf_7_3_3_numbers.pdf"),
+            include_bytes!("../../../parser/test_data/parser/err/missing_endstream.pdf"),
+            include_bytes!("../../../parser/test_data/parser/validation/stream_without_length.pdf"),
+        ];
+        let inserts: &[&[u8]] = &[b"", b" ", b"1", b"/N", b"]", b"[", b">>", b"<<", b"(", b"%", b"endstream"];
+
+        for text in fixtures {
+            for offset in 1..text.len() as u32 {
+                for insert in inserts {
+                    let offset = TextSize::new(offset);
+                    check_reparse_matches_full_parse(text, &Indel::insert(offset, insert.to_vec()));
+                    if insert.is_empty() {
+                        check_reparse_matches_full_parse(text, &Indel::delete(TextRange::at(offset - TextSize::new(1), 1.into())));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reparse_matches_full_parse_with_stream_extents() {
+        let text = b"1 0 obj << /Length 2 0 R >> stream\nabc\nendstream endobj\n2 0 obj 3 endobj\n3 0 obj [1 2] endobj\n\
+            xref\n0 4\n0000000000 65535 f \n0000000000 00000 n \n0000000056 00000 n \n0000000073 00000 n \n\
+            trailer << /Size 4 >>\nstartxref\n94\n%%EOF\n";
+        let inserts: &[&[u8]] = &[b"", b" ", b"1", b"4", b"R", b"]", b"[", b"endstream", b"startxref 0"];
+
+        for offset in 1..text.len() as u32 {
+            for insert in inserts {
+                let offset = TextSize::new(offset);
+                check_reparse_matches_full_parse(text, &Indel::insert(offset, insert.to_vec()));
+                if insert.is_empty() {
+                    check_reparse_matches_full_parse(text, &Indel::delete(TextRange::at(offset - TextSize::new(1), 1.into())));
+                }
+            }
+        }
+    }
+}
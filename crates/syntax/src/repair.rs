@@ -0,0 +1,444 @@
+//! Reconstruction of the cross-reference information of damaged files.
+//!
+//! Readers locate objects through the cross-reference table which `startxref`
+//! points to. When that offset is wrong, or the table is missing, truncated or
+//! lists wrong offsets, they fall back to scanning the file for `N G obj`.
+//! [`XRefRepair`] does the same: it rebuilds the object index from the
+//! indirect objects of the parsed bodies and the object headers found by the
+//! lexer, and reconciles it with the `xref` tables of the file.
+//!
+//! Every decision which isn't backed by an intact table is recorded as a
+//! [`RepairGuess`].
+//!
+//! See ISO `32000-2:2020`, Section 7.5.4 Cross-reference table.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::{AstNode, Parse, PdfDocument, XRefEntryKind, ast};
+
+/// An object index rebuilt from the objects in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XRefRepair {
+    entries: BTreeMap<u32, XRefEntryKind>,
+    guesses: Vec<RepairGuess>,
+}
+
+/// Something [`XRefRepair`] found damaged, and how it was worked around.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepairGuess {
+    /// The last `startxref` is missing or doesn't point to a cross-reference
+    /// table or stream.
+    InvalidStartXRef { offset: Option<u64> },
+    /// The file has neither cross-reference tables nor streams, so the index
+    /// is made up of the objects found in the file alone.
+    MissingXRef,
+    /// A subsection of the table at `offset` has fewer entries than it
+    /// declares.
+    TruncatedXRefTable { offset: u64, expected: u64, found: u64 },
+    /// An object header which the parser couldn't make an object of.
+    UnparsedObject { object_number: u64, generation: u64, offset: u64 },
+    /// The table lists the object at `listed`, but it's found at `found`.
+    MisplacedObject {
+        object_number: u32,
+        generation: u64,
+        listed: u64,
+        found: u64,
+    },
+    /// The table lists the object at `listed`, but it doesn't exist.
+    MissingObject { object_number: u32, generation: u64, listed: u64 },
+    /// The object isn't listed by any table, or is defined after the newest
+    /// table listing it, so the definition at `offset` is used.
+    UnlistedObject { object_number: u32, generation: u64, offset: u64 },
+    /// Without a table to tell which one is current, the last of several
+    /// definitions of the object is used.
+    DuplicateObject { object_number: u32, offsets: Vec<u64> },
+}
+
+impl fmt::Display for RepairGuess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepairGuess::InvalidStartXRef { offset: Some(offset) } => {
+                write!(f, "startxref offset {offset} doesn't point to a cross-reference section")
+            }
+            RepairGuess::InvalidStartXRef { offset: None } => write!(f, "missing startxref"),
+            RepairGuess::MissingXRef => write!(f, "no cross-reference section, objects were located by scanning"),
+            RepairGuess::TruncatedXRefTable { offset, expected, found } => {
+                write!(f, "cross-reference table at {offset} is truncated: expected {expected} entries, found {found}")
+            }
+            RepairGuess::UnparsedObject {
+                object_number,
+                generation,
+                offset,
+            } => {
+                write!(f, "object {object_number} {generation} at {offset} couldn't be parsed")
+            }
+            RepairGuess::MisplacedObject {
+                object_number,
+                generation,
+                listed,
+                found,
+            } => {
+                write!(f, "object {object_number} {generation} is listed at {listed}, but found at {found}")
+            }
+            RepairGuess::MissingObject {
+                object_number,
+                generation,
+                listed,
+            } => {
+                write!(f, "object {object_number} {generation} is listed at {listed}, but doesn't exist")
+            }
+            RepairGuess::UnlistedObject {
+                object_number,
+                generation,
+                offset,
+            } => {
+                write!(f, "object {object_number} {generation} at {offset} is not listed in a cross-reference table")
+            }
+            RepairGuess::DuplicateObject { object_number, offsets } => {
+                write!(f, "object {object_number} is defined {} times, using the last definition", offsets.len())
+            }
+        }
+    }
+}
+
+/// An object found in the file.
+#[derive(Debug, Clone, Copy)]
+struct Definition {
+    generation: u64,
+    offset: u64,
+}
+
+impl XRefRepair {
+    pub fn new(parse: &Parse<PdfDocument>) -> XRefRepair {
+        let _p = tracing::info_span!("XRefRepair::new").entered();
+        let document = parse.tree();
+        let mut guesses = Vec::new();
+
+        let definitions = find_definitions(&document, &mut guesses);
+        let tables: Vec<_> = document.revisions().filter_map(|it| it.x_ref_table()).collect();
+        for table in &tables {
+            check_subsections(table, &mut guesses);
+        }
+        check_startxref(&document, &mut guesses);
+
+        // The newest entry of each object, with the offset of its table.
+        let mut listed = BTreeMap::new();
+        for table in &tables {
+            let table_offset = offset_of(table.syntax());
+            for (object_number, entry) in table.entries() {
+                listed.insert(object_number, (entry, table_offset));
+            }
+        }
+        let has_xref_stream = document
+            .revisions()
+            .filter_map(|it| it.body())
+            .flat_map(|it| it.indirect_object_exprs())
+            .any(|it| it.is_x_ref_stream());
+        if tables.is_empty() && !has_xref_stream {
+            guesses.push(RepairGuess::MissingXRef);
+        }
+
+        let mut entries = BTreeMap::new();
+        let numbers = definitions.keys().chain(listed.keys()).copied().collect::<BTreeSet<_>>();
+        for object_number in numbers {
+            let defined = definitions.get(&object_number).map_or(&[][..], Vec::as_slice);
+            let last = defined.last().copied();
+            let entry = match listed.get(&object_number).copied() {
+                Some((XRefEntryKind::InUse { offset, generation }, table_offset)) => match last {
+                    Some(last) if last.offset > table_offset && last.offset != offset => {
+                        guesses.push(RepairGuess::UnlistedObject {
+                            object_number,
+                            generation: last.generation,
+                            offset: last.offset,
+                        });
+                        Some(last)
+                    }
+                    _ if defined.iter().any(|it| it.offset == offset && it.generation == generation) => Some(Definition { generation, offset }),
+                    Some(last) => {
+                        guesses.push(RepairGuess::MisplacedObject {
+                            object_number,
+                            generation,
+                            listed: offset,
+                            found: last.offset,
+                        });
+                        Some(last)
+                    }
+                    None => {
+                        guesses.push(RepairGuess::MissingObject {
+                            object_number,
+                            generation,
+                            listed: offset,
+                        });
+                        None
+                    }
+                },
+                Some((free @ XRefEntryKind::Free { .. }, table_offset)) => match last {
+                    Some(last) if last.offset > table_offset => {
+                        guesses.push(RepairGuess::UnlistedObject {
+                            object_number,
+                            generation: last.generation,
+                            offset: last.offset,
+                        });
+                        Some(last)
+                    }
+                    // The object was deleted by the update the table belongs to.
+                    _ => {
+                        entries.insert(object_number, free);
+                        None
+                    }
+                },
+                // Tables don't have compressed entries.
+                Some((XRefEntryKind::Compressed { .. }, _)) => None,
+                None => {
+                    if !tables.is_empty() {
+                        let last = last.unwrap();
+                        guesses.push(RepairGuess::UnlistedObject {
+                            object_number,
+                            generation: last.generation,
+                            offset: last.offset,
+                        });
+                    }
+                    if defined.len() > 1 && !has_xref_stream {
+                        let offsets = defined.iter().map(|it| it.offset).collect();
+                        guesses.push(RepairGuess::DuplicateObject { object_number, offsets });
+                    }
+                    last
+                }
+            };
+            if let Some(Definition { generation, offset }) = entry {
+                entries.insert(object_number, XRefEntryKind::InUse { offset, generation });
+            }
+        }
+
+        XRefRepair { entries, guesses }
+    }
+
+    /// Iterates over the object numbers and their rebuilt entries, ordered by
+    /// object number.
+    pub fn entries(&self) -> impl Iterator<Item = (u32, XRefEntryKind)> + '_ {
+        self.entries.iter().map(|(&number, &entry)| (number, entry))
+    }
+
+    pub fn get(&self, object_number: u32) -> Option<XRefEntryKind> {
+        self.entries.get(&object_number).copied()
+    }
+
+    /// What had to be guessed, in the order it was found.
+    pub fn guesses(&self) -> &[RepairGuess] {
+        &self.guesses
+    }
+
+    /// Whether the cross-reference information of the file is intact, i.e.
+    /// nothing had to be guessed.
+    pub fn is_intact(&self) -> bool {
+        self.guesses.is_empty()
+    }
+}
+
+/// Collects the definitions of all objects, in text order, from the parsed
+/// bodies and the object headers found by the lexer.
+fn find_definitions(document: &PdfDocument, guesses: &mut Vec<RepairGuess>) -> BTreeMap<u32, Vec<Definition>> {
+    let mut by_offset = BTreeMap::new();
+    for object in document.revisions().filter_map(|it| it.body()).flat_map(|it| it.indirect_object_exprs()) {
+        let Some(id) = object.indirect_object_id() else { continue };
+        let number = id.object_number().and_then(|it| ast::Expr::Literal(it).as_int());
+        let generation = id.generation_number().and_then(|it| ast::Expr::Literal(it).as_int());
+        if let (Some(number), Some(generation)) = (number, generation) {
+            by_offset.insert(offset_of(object.syntax()), (number, generation));
+        }
+    }
+
+    let mut text = Vec::new();
+    document.syntax().text().for_each_chunk(|chunk| text.extend_from_slice(chunk));
+    let lexed = parser::LexedStr::new(document.edition().unwrap_or(parser::Edition::LATEST), &text);
+    for (object_number, generation, offset) in lexed.object_headers() {
+        let offset = offset as u64;
+        if by_offset.contains_key(&offset) {
+            continue;
+        }
+        guesses.push(RepairGuess::UnparsedObject {
+            object_number,
+            generation,
+            offset,
+        });
+        if let (Ok(number), Ok(generation)) = (i64::try_from(object_number), i64::try_from(generation)) {
+            by_offset.insert(offset, (number, generation));
+        }
+    }
+
+    let mut definitions: BTreeMap<u32, Vec<Definition>> = BTreeMap::new();
+    for (offset, (number, generation)) in by_offset {
+        if let (Ok(number), Ok(generation)) = (u32::try_from(number), u64::try_from(generation)) {
+            definitions.entry(number).or_default().push(Definition { generation, offset });
+        }
+    }
+    definitions
+}
+
+fn check_subsections(table: &ast::XRefTable, guesses: &mut Vec<RepairGuess>) {
+    for subsection in table.x_ref_sections().flat_map(|it| it.x_ref_subsections()) {
+        let Some(expected) = subsection.count().and_then(|it| ast::Expr::Literal(it).as_int()) else {
+            continue;
+        };
+        let found = subsection.x_ref_entrys().count() as u64;
+        if u64::try_from(expected).is_ok_and(|expected| expected > found) {
+            guesses.push(RepairGuess::TruncatedXRefTable {
+                offset: offset_of(table.syntax()),
+                expected: expected as u64,
+                found,
+            });
+        }
+    }
+}
+
+fn check_startxref(document: &PdfDocument, guesses: &mut Vec<RepairGuess>) {
+    // Objects appended without a trailer don't replace the last `startxref`.
+    let startxref = document
+        .revisions()
+        .filter_map(|it| it.trailer()?.literal())
+        .last()
+        .and_then(|it| ast::Expr::Literal(it).as_int());
+    let Some(startxref) = startxref else {
+        guesses.push(RepairGuess::InvalidStartXRef { offset: None });
+        return;
+    };
+    let points_to_xref = document.revisions().any(|revision| {
+        let table = revision.x_ref_table().map(|it| offset_of(it.syntax()));
+        let objects = revision.body().into_iter().flat_map(|it| it.indirect_object_exprs());
+        let streams = objects.filter(|it| it.is_x_ref_stream()).map(|it| offset_of(it.syntax()));
+        table.into_iter().chain(streams).any(|offset| i64::try_from(offset) == Ok(startxref))
+    });
+    if !points_to_xref {
+        guesses.push(RepairGuess::InvalidStartXRef {
+            offset: u64::try_from(startxref).ok(),
+        });
+    }
+}
+
+fn offset_of(node: &crate::SyntaxNode) -> u64 {
+    u32::from(node.text_range().start()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Edition;
+
+    /// Builds a file from `parts`, replacing `{N}` with the offset of the
+    /// `N`th part, padded to 3 digits.
+    fn repair(parts: &[&str]) -> XRefRepair {
+        let mut offsets = Vec::new();
+        let mut len = 0;
+        for part in parts {
+            offsets.push(len);
+            len += part.len();
+        }
+        let mut text = parts.concat();
+        for (i, offset) in offsets.iter().enumerate().rev() {
+            text = text.replace(&format!("{{{i}}}"), &format!("{offset:03}"));
+        }
+        XRefRepair::new(&PdfDocument::parse(text.as_bytes(), Edition::CURRENT))
+    }
+
+    fn in_use(offset: u64, generation: u64) -> XRefEntryKind {
+        XRefEntryKind::InUse { offset, generation }
+    }
+
+    #[test]
+    fn intact_file() {
+        let repair = repair(&[
+            "%PDF-1.7\n",
+            "1 0 obj << /Type /Catalog >> endobj\n",
+            "2 0 obj (two) endobj\n",
+            "xref\n0 3\n0000000000 65535 f \n0000000{1} 00000 n \n0000000{2} 00000 n \n",
+            "trailer << /Size 3 /Root 1 0 R >>\nstartxref\n{3}\n%%EOF\n",
+        ]);
+        assert_eq!(repair.guesses(), &[]);
+        assert!(repair.is_intact());
+        assert_eq!(repair.get(1), Some(in_use(9, 0)));
+        assert_eq!(repair.get(2), Some(in_use(45, 0)));
+        assert_eq!(
+            repair.get(0),
+            Some(XRefEntryKind::Free {
+                next_free_object: 0,
+                generation: 65535
+            })
+        );
+    }
+
+    #[test]
+    fn wrong_offsets_and_startxref() {
+        let repair = repair(&[
+            "%PDF-1.7\n",
+            "1 0 obj << /Type /Catalog >> endobj\n",
+            "2 0 obj (two) endobj\n",
+            "xref\n0 4\n0000000000 65535 f \n0000000{1} 00000 n \n0000000999 00000 n \n0000000500 00000 n \n",
+            "trailer << /Size 4 /Root 1 0 R >>\nstartxref\n12\n%%EOF\n",
+        ]);
+        assert_eq!(
+            repair.guesses(),
+            &[
+                RepairGuess::InvalidStartXRef { offset: Some(12) },
+                RepairGuess::MisplacedObject {
+                    object_number: 2,
+                    generation: 0,
+                    listed: 999,
+                    found: 45
+                },
+                RepairGuess::MissingObject {
+                    object_number: 3,
+                    generation: 0,
+                    listed: 500
+                },
+            ]
+        );
+        assert_eq!(repair.get(2), Some(in_use(45, 0)));
+        assert_eq!(repair.get(3), None);
+    }
+
+    #[test]
+    fn truncated_table_and_appended_objects() {
+        // The table lists only object 1, and object 1 is redefined after it by
+        // an update without a table.
+        let repair = repair(&[
+            "%PDF-1.7\n",
+            "1 0 obj (old) endobj\n",
+            "2 0 obj (two) endobj\n",
+            "xref\n0 3\n0000000000 65535 f \n0000000{1} 00000 n \n",
+            "trailer << /Size 3 >>\nstartxref\n{3}\n%%EOF\n",
+            "1 0 obj (new) endobj\n",
+        ]);
+        let guesses: Vec<_> = repair.guesses().iter().map(|it| it.to_string()).collect();
+        assert_eq!(
+            guesses,
+            vec![
+                "cross-reference table at 51 is truncated: expected 3 entries, found 2",
+                "object 1 0 at 142 is not listed in a cross-reference table",
+                "object 2 0 at 30 is not listed in a cross-reference table",
+            ]
+        );
+        assert_eq!(repair.get(1), Some(in_use(142, 0)));
+        assert_eq!(repair.get(2), Some(in_use(30, 0)));
+    }
+
+    #[test]
+    fn no_table() {
+        let repair = repair(&["%PDF-1.7\n", "1 0 obj (a) endobj\n", "1 1 obj (b) endobj\n", "2 0 obj null endobj\n"]);
+        assert_eq!(
+            repair.guesses(),
+            &[
+                RepairGuess::InvalidStartXRef { offset: None },
+                RepairGuess::MissingXRef,
+                RepairGuess::DuplicateObject {
+                    object_number: 1,
+                    offsets: vec![9, 28]
+                },
+            ]
+        );
+        assert_eq!(repair.get(1), Some(in_use(28, 1)));
+        assert_eq!(repair.get(2), Some(in_use(47, 0)));
+    }
+}
@@ -10,7 +10,7 @@ use rowan::{GreenNodeBuilder, Language};
 
 use crate::{Parse, SyntaxError, SyntaxKind, TextSize};
 
-pub(crate) use rowan::GreenNode;
+pub(crate) use rowan::{GreenNode, GreenToken, NodeOrToken};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum PdfLanguage {}
@@ -29,6 +29,7 @@ impl Language for PdfLanguage {
 
 pub type SyntaxNode = rowan::SyntaxNode<PdfLanguage>;
 pub type SyntaxToken = rowan::SyntaxToken<PdfLanguage>;
+pub type SyntaxElement = rowan::SyntaxElement<PdfLanguage>;
 pub type SyntaxNodeChildren = rowan::SyntaxNodeChildren<PdfLanguage>;
 
 #[derive(Default)]
@@ -0,0 +1,26 @@
+//! Primitive tree editor, for trees made mutable with `clone_for_update`.
+//!
+//! The edits happen in place: nodes and tokens of the tree stay valid, and
+//! their ranges reflect the edits.
+
+use crate::SyntaxElement;
+
+/// Replaces `old` with `new`, which is detached from its own tree first.
+pub fn replace(old: impl Into<SyntaxElement>, new: impl Into<SyntaxElement>) {
+    replace_with_many(old, vec![new.into()]);
+}
+
+pub fn replace_with_many(old: impl Into<SyntaxElement>, new: Vec<SyntaxElement>) {
+    let old = old.into();
+    let parent = old.parent().expect("can't replace the root of a tree");
+    let index = old.index();
+    parent.splice_children(index..index + 1, new);
+}
+
+/// Inserts `new` right after `anchor`, in the same parent.
+pub fn insert_all_after(anchor: impl Into<SyntaxElement>, new: Vec<SyntaxElement>) {
+    let anchor = anchor.into();
+    let parent = anchor.parent().expect("can't insert after the root of a tree");
+    let index = anchor.index() + 1;
+    parent.splice_children(index..index, new);
+}
@@ -0,0 +1,155 @@
+//! Runs parsing and validation on the files in the parser's
+//! `test_data/parser/validation`, and compares the syntax tree and the errors
+//! with the `.rast` file next to each of them. Run with `UPDATE_EXPECT=1` to
+//! update the `.rast` files.
+//!
+//! These files are syntactically valid and only break the rules checked by
+//! [`validation`](crate::validation), so they can't go to `err`, whose files
+//! must have parser errors.
+//!
+//! Files are parsed as PDF documents, except for the ones named
+//! `content_stream_*`, which are parsed as decoded content streams.
+//!
+//! All of the parser's fixtures also seed randomized tests, see
+//! [`parser_fuzz_tests`].
+
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use expect_test::expect_file;
+use rowan::{NodeOrToken, WalkEvent};
+
+use crate::{
+    AstNode, ContentStream, Edition, PdfDocument, SyntaxError, SyntaxNode,
+    fuzz::{self, Rng},
+};
+
+#[test]
+fn validation_errors() {
+    for path in pdf_files(&test_data_dir().join("parser/validation")) {
+        let text = fs::read(&path).unwrap();
+        let is_content_stream = path.file_name().unwrap().to_string_lossy().starts_with("content_stream_");
+        let (node, errors) = if is_content_stream {
+            let parse = ContentStream::parse(&text, Edition::CURRENT);
+            (parse.tree().syntax().clone(), parse.errors())
+        } else {
+            let parse = PdfDocument::parse(&text, Edition::CURRENT);
+            (parse.tree().syntax().clone(), parse.errors())
+        };
+        assert!(!errors.is_empty(), "no errors in a validation file {}", path.display());
+        expect_file![path.with_extension("rast")].assert_eq(&dump(&node, &errors));
+    }
+}
+
+/// Checks that lexing and parsing random mutations of the fixtures neither
+/// panics nor loses any bytes, as files, content streams and object streams.
+///
+/// Set `FUZZ_ITERATIONS` to the number of mutations per fixture for a longer
+/// soak run, and `FUZZ_SEED` to vary the mutations. A failing input is saved
+/// to the temporary directory.
+#[test]
+fn parser_fuzz_tests() {
+    let iterations = env_number("FUZZ_ITERATIONS").unwrap_or(16);
+    let seed = env_number("FUZZ_SEED").unwrap_or(0);
+
+    let mut fixtures: Vec<Vec<u8>> = pdf_files(&test_data_dir()).into_iter().map(|path| fs::read(path).unwrap()).collect();
+    assert!(!fixtures.is_empty(), "no fixtures");
+    // Inputs which don't resemble a PDF file at all.
+    let mut rng = Rng::new(seed);
+    fixtures.extend((0..8).map(|_| (0..rng.below(256)).map(|_| rng.next_u64() as u8).collect()));
+    // Object streams, whose first line is the header.
+    fixtures.push(b"7 0 8 3\n42 7".to_vec());
+    fixtures.push(b"1 0 2 12 3 30\n<< /A [1 2] >> (string) [/Name 1 0 R]".to_vec());
+
+    for (i, text) in fixtures.iter().enumerate() {
+        check_parser(text, &format!("fixture {i}"));
+        let mut rng = Rng::new(seed.wrapping_add(i as u64));
+        for iteration in 0..iterations {
+            let other = &fixtures[rng.below(fixtures.len())];
+            let mutated = fuzz::mutate(text, other, &mut rng);
+            check_parser(&mutated, &format!("fixture {i}, iteration {iteration}"));
+        }
+    }
+
+    // Arrays, dictionaries and content stream blocks nested far deeper than
+    // the parser's limit.
+    for (i, open) in [&b"["[..], b"<< /A "].into_iter().enumerate() {
+        let text = [&b"1 0 obj\n"[..], &open.repeat(100_000), b"\nendobj\n"].concat();
+        check_parser(&text, &format!("deeply nested object {i}"));
+        let text = [&b"0 0\n"[..], &open.repeat(100_000)].concat();
+        check_parser(&text, &format!("deeply nested object stream {i}"));
+    }
+    for (i, open) in [&b"q "[..], b"BT ", b"/Tag BMC "].into_iter().enumerate() {
+        check_parser(&open.repeat(100_000), &format!("deeply nested content stream {i}"));
+    }
+}
+
+/// The name of an entry point, and the fuzz check of its input.
+type Check = (&'static str, fn(&[u8]));
+
+/// Runs the checks of every entry point on `text`.
+fn check_parser(text: &[u8], name: &str) {
+    let checks: [Check; 3] = [
+        ("pdf", fuzz::check_document),
+        ("content", fuzz::check_content_stream),
+        ("objstm", fuzz::check_object_stream),
+    ];
+    for (kind, check) in checks {
+        if std::panic::catch_unwind(|| check(text)).is_err() {
+            let file_name = format!("fuzz-failure-{kind}-{}.pdf", name.replace([' ', ','], ""));
+            let path = std::env::temp_dir().join(file_name);
+            fs::write(&path, text).unwrap();
+            panic!("parsing {name} as {kind} failed, the input is saved to {}", path.display());
+        }
+    }
+}
+
+fn env_number(name: &str) -> Option<u64> {
+    let value = std::env::var(name).ok()?;
+    Some(value.parse().unwrap_or_else(|_| panic!("{name} must be a number, not {value:?}")))
+}
+
+fn dump(node: &SyntaxNode, errors: &[SyntaxError]) -> String {
+    let mut buf = String::new();
+    let mut indent = String::new();
+    for event in node.preorder_with_tokens() {
+        match event {
+            WalkEvent::Enter(NodeOrToken::Node(node)) => {
+                writeln!(buf, "{indent}{:?}@{:?}", node.kind(), node.text_range()).unwrap();
+                indent.push_str("  ");
+            }
+            WalkEvent::Enter(NodeOrToken::Token(token)) => {
+                let text = String::from_utf8_lossy(token.text());
+                writeln!(buf, "{indent}{:?}@{:?} {text:?}", token.kind(), token.text_range()).unwrap();
+            }
+            WalkEvent::Leave(NodeOrToken::Node(_)) => indent.truncate(indent.len() - 2),
+            WalkEvent::Leave(NodeOrToken::Token(_)) => (),
+        }
+    }
+    for error in errors {
+        writeln!(buf, "error {:?}: {error}", error.range()).unwrap();
+    }
+    buf
+}
+
+fn test_data_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../parser/test_data")
+}
+
+/// The `.pdf` files in `dir` and its subdirectories, sorted.
+fn pdf_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).unwrap_or_else(|err| panic!("can't `read_dir` {}: {err}", dir.display())) {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(pdf_files(&path));
+        } else if path.extension().unwrap_or_default() == "pdf" {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
@@ -2,17 +2,408 @@
 //!
 //! A failed validation emits a diagnostic.
 
-use crate::{SyntaxError, SyntaxNode, match_ast};
+use std::collections::{HashMap, HashSet};
+
+use crate::{AstNode, AstToken, SyntaxError, SyntaxKind, SyntaxNode, TextRange, TextSize, XRefEntryKind, ast, match_ast};
 
 #[allow(unused_variables)]
 pub(crate) fn validate(root: &SyntaxNode, errors: &mut Vec<SyntaxError>) {
     let _p = tracing::info_span!("parser::validate").entered();
 
+    if let Some(document) = ast::PdfDocument::cast(root.clone()) {
+        validate_header(&document, errors);
+        validate_eof_marker(&document, errors);
+        validate_xref_tables(&document, errors);
+        validate_startxref(&document, errors);
+    }
+
     for node in root.descendants() {
         match_ast! {
             match node {
+                ast::DictionaryExpr(it) => validate_duplicate_keys(&it, errors),
+                ast::DictionaryItemKeyExpr(it) => validate_dictionary_key(&it, errors),
+                ast::IndirectObjectId(it) => {
+                    validate_object_number(it.object_number(), errors);
+                    validate_generation_number(it.generation_number(), errors);
+                },
+                ast::IndirectReferenceExpr(it) => {
+                    validate_object_number(it.object_number(), errors);
+                    validate_generation_number(it.generation_number(), errors);
+                },
+                ast::IndirectObjectExpr(it) => validate_endobj(&it, errors),
+                ast::StreamExpr(it) => validate_stream_length(&it, errors),
+                ast::Literal(it) => validate_literal_limits(&it, errors),
+                ast::GraphicsStateBlock(it) => validate_graphics_state_nesting(&it, errors),
                 _ => (),
             }
         }
     }
 }
+
+/// The largest and smallest integer values.
+/// See ISO `32000-1:2008`, Annex C, Table C.1 Architectural limits.
+const INTEGER_LIMITS: std::ops::RangeInclusive<i64> = i32::MIN as i64..=i32::MAX as i64;
+/// The largest absolute value of a real number.
+const REAL_LIMIT: f64 = 3.403e38;
+/// The maximum length of a name, in bytes.
+const NAME_LENGTH_LIMIT: usize = 127;
+/// The maximum depth of `q` ... `Q` nesting.
+const GRAPHICS_STATE_NESTING_LIMIT: usize = 28;
+/// The largest generation number.
+/// See ISO `32000-2:2020`, Section 7.5.4 Cross-reference table.
+const GENERATION_LIMIT: i64 = 65535;
+
+/// The file must start with `%PDF-x.y`, naming a known version.
+/// See ISO `32000-2:2020`, Section 7.5.2 File header.
+fn validate_header(document: &ast::PdfDocument, errors: &mut Vec<SyntaxError>) {
+    let Some(header) = document.pdf_header() else {
+        errors.push(SyntaxError::new_at_offset("missing PDF header, expected `%PDF-x.y`", TextSize::from(0)));
+        return;
+    };
+    if header.edition().is_none() {
+        let range = header.header_comment_token().map_or_else(|| header.syntax().text_range(), |it| it.text_range());
+        errors.push(SyntaxError::new("malformed PDF header, unknown version", range));
+    }
+}
+
+/// The last line of the file must be `%%EOF`.
+/// See ISO `32000-2:2020`, Section 7.5.5 File trailer.
+fn validate_eof_marker(document: &ast::PdfDocument, errors: &mut Vec<SyntaxError>) {
+    if document.revisions().last().and_then(|it| it.eof_marker()).is_none() {
+        let end = document.syntax().text_range().end();
+        errors.push(SyntaxError::new("missing `%%EOF` marker at the end of the file", TextRange::empty(end)));
+    }
+}
+
+/// Keys must be unique within a dictionary, later entries with the same key
+/// are ignored by most readers.
+/// See ISO `32000-2:2020`, Section 7.3.7 Dictionary objects.
+fn validate_duplicate_keys(dictionary: &ast::DictionaryExpr, errors: &mut Vec<SyntaxError>) {
+    let mut seen = HashSet::new();
+    for key in dictionary.keys() {
+        let Ok(value) = key.value() else { continue };
+        if !seen.insert(value) {
+            let message = format!("duplicate dictionary key `{}`", String::from_utf8_lossy(key.text()));
+            errors.push(SyntaxError::new(message, key.syntax().text_range()));
+        }
+    }
+}
+
+/// See ISO `32000-2:2020`, Section 7.3.7 Dictionary objects.
+fn validate_dictionary_key(key: &ast::DictionaryItemKeyExpr, errors: &mut Vec<SyntaxError>) {
+    let is_name = match key.expr() {
+        Some(ast::Expr::Literal(it)) => matches!(it.kind(), ast::LiteralKind::Name(_)),
+        Some(_) => false,
+        None => return,
+    };
+    if !is_name {
+        errors.push(SyntaxError::new("dictionary key must be a name", key.syntax().text_range()));
+    }
+}
+
+/// See ISO `32000-2:2020`, Section 7.3.10 Indirect objects.
+fn validate_object_number(object_number: Option<ast::Literal>, errors: &mut Vec<SyntaxError>) {
+    let Some(literal) = object_number else { return };
+    if ast::Expr::Literal(literal.clone()).as_int().is_some_and(|it| it <= 0) {
+        errors.push(SyntaxError::new("object number must be a positive integer", literal.syntax().text_range()));
+    }
+}
+
+/// See ISO `32000-2:2020`, Section 7.3.10 Indirect objects.
+fn validate_generation_number(generation: Option<ast::Literal>, errors: &mut Vec<SyntaxError>) {
+    let Some(literal) = generation else { return };
+    if ast::Expr::Literal(literal.clone())
+        .as_int()
+        .is_none_or(|it| !(0..=GENERATION_LIMIT).contains(&it))
+    {
+        let message = format!("generation number must be an integer between 0 and {GENERATION_LIMIT}");
+        errors.push(SyntaxError::new(message, literal.syntax().text_range()));
+    }
+}
+
+/// See ISO `32000-2:2020`, Section 7.3.10 Indirect objects.
+fn validate_endobj(object: &ast::IndirectObjectExpr, errors: &mut Vec<SyntaxError>) {
+    if object.endobj_token().is_some() {
+        return;
+    }
+    let last = object.syntax().children_with_tokens().filter(|it| !it.kind().is_trivia()).last();
+    let end = last.map_or_else(|| object.syntax().text_range().end(), |it| it.text_range().end());
+    errors.push(SyntaxError::new("missing `endobj` at the end of the object", TextRange::empty(end)));
+}
+
+/// See ISO `32000-2:2020`, Section 7.3.8.2 Stream extent.
+fn validate_stream_length(stream: &ast::StreamExpr, errors: &mut Vec<SyntaxError>) {
+    let Some(dictionary) = stream.dictionary_expr() else { return };
+    if dictionary.get(b"/Length").is_none() {
+        errors.push(SyntaxError::new("stream dictionary has no `/Length` entry", dictionary.syntax().text_range()));
+    }
+}
+
+/// Values beyond the architectural limits may not be representable by readers.
+/// Byte offsets in cross-reference tables and after `startxref` are not
+/// objects, and may be larger.
+fn validate_literal_limits(literal: &ast::Literal, errors: &mut Vec<SyntaxError>) {
+    let message = match literal.kind() {
+        ast::LiteralKind::IntNumber(number) => {
+            let is_offset = literal.syntax().parent().is_some_and(|it| it.kind() == SyntaxKind::TRAILER)
+                || literal.syntax().ancestors().any(|it| it.kind() == SyntaxKind::X_REF_TABLE);
+            let out_of_range = match number.value() {
+                Ok(value) => !INTEGER_LIMITS.contains(&value),
+                Err(_) => number.text().iter().any(u8::is_ascii_digit),
+            };
+            if is_offset || !out_of_range {
+                return;
+            }
+            format!(
+                "integer is out of the implementation limits, {} to {}",
+                INTEGER_LIMITS.start(),
+                INTEGER_LIMITS.end()
+            )
+        }
+        ast::LiteralKind::RealNumber(number) => {
+            if !number.value().is_ok_and(|it| it.abs() > REAL_LIMIT) {
+                return;
+            }
+            format!("real number is out of the implementation limits, -{REAL_LIMIT:e} to {REAL_LIMIT:e}")
+        }
+        ast::LiteralKind::Name(name) => {
+            if !name.value().is_ok_and(|it| it.len() > NAME_LENGTH_LIMIT) {
+                return;
+            }
+            format!("name is longer than the implementation limit of {NAME_LENGTH_LIMIT} bytes")
+        }
+        _ => return,
+    };
+    errors.push(SyntaxError::new(message, literal.syntax().text_range()));
+}
+
+/// Only the outermost block exceeding the limit is reported.
+fn validate_graphics_state_nesting(block: &ast::GraphicsStateBlock, errors: &mut Vec<SyntaxError>) {
+    let depth = block.syntax().ancestors().filter(|it| it.kind() == SyntaxKind::GRAPHICS_STATE_BLOCK).count();
+    if depth == GRAPHICS_STATE_NESTING_LIMIT + 1 {
+        let message = format!("graphics state nesting is deeper than the implementation limit of {GRAPHICS_STATE_NESTING_LIMIT}");
+        let range = block.save_token().map_or_else(|| block.syntax().text_range(), |it| it.text_range());
+        errors.push(SyntaxError::new(message, range));
+    }
+}
+
+/// Each subsection of a cross-reference table must have as many entries as it
+/// declares, each entry must be 20 bytes long and point to its object, and
+/// every object in use must be listed.
+/// See ISO `32000-2:2020`, Section 7.5.4 Cross-reference table.
+fn validate_xref_tables(document: &ast::PdfDocument, errors: &mut Vec<SyntaxError>) {
+    let tables: Vec<_> = document.revisions().filter_map(|it| it.x_ref_table()).collect();
+    if tables.is_empty() {
+        return;
+    }
+    let objects: Vec<_> = document
+        .revisions()
+        .filter_map(|it| it.body())
+        .flat_map(|it| it.indirect_object_exprs())
+        .collect();
+    let ids: HashMap<_, _> = objects
+        .iter()
+        .filter_map(|it| Some((it.syntax().text_range().start(), object_id(it)?)))
+        .collect();
+
+    let mut listed = HashSet::new();
+    for subsection in tables.iter().flat_map(|it| it.x_ref_sections()).flat_map(|it| it.x_ref_subsections()) {
+        validate_xref_subsection_count(&subsection, errors);
+        let first_object = subsection.first_object().and_then(|it| ast::Expr::Literal(it).as_int());
+        for (i, entry) in subsection.x_ref_entrys().enumerate() {
+            validate_xref_entry_length(&entry, errors);
+            let Some(object_number) = first_object.map(|it| it + i as i64) else {
+                continue;
+            };
+            if let Some(XRefEntryKind::InUse { .. }) = entry.kind() {
+                listed.insert(object_number);
+                validate_xref_entry_offset(&entry, object_number, &ids, errors);
+            }
+        }
+    }
+
+    // Objects in object streams are only listed by cross-reference streams,
+    // which aren't decoded here.
+    if !objects.iter().any(|it| it.is_x_ref_stream()) {
+        validate_unlisted_objects(&objects, &listed, errors);
+    }
+}
+
+fn validate_xref_subsection_count(subsection: &ast::XRefSubsection, errors: &mut Vec<SyntaxError>) {
+    let Some(count) = subsection.count() else { return };
+    let Some(expected) = ast::Expr::Literal(count.clone()).as_int() else { return };
+    let found = subsection.x_ref_entrys().count();
+    if usize::try_from(expected).ok() != Some(found) {
+        let message = format!("cross-reference subsection declares {expected} entries, but has {found}");
+        errors.push(SyntaxError::new(message, count.syntax().text_range()));
+    }
+}
+
+/// An entry is a 10-digit offset, a 5-digit generation number, the `n` or
+/// `f` keyword and a 2-byte end-of-line marker, separated by single spaces.
+fn validate_xref_entry_length(entry: &ast::XRefEntry, errors: &mut Vec<SyntaxError>) {
+    let Some(keyword) = entry.free_or_used().and_then(|it| it.syntax().last_token()) else {
+        return;
+    };
+    // The end-of-line marker is part of the entry, whether or not the parser
+    // attached it to the entry node.
+    let mut end = keyword.text_range().end();
+    let mut token = keyword.next_token();
+    while let Some(trivia) = token.filter(|it| matches!(it.kind(), SyntaxKind::WHITESPACE | SyntaxKind::NEWLINE)) {
+        end = trivia.text_range().end();
+        if trivia.kind() == SyntaxKind::NEWLINE {
+            break;
+        }
+        token = trivia.next_token();
+    }
+
+    let range = TextRange::new(entry.syntax().text_range().start(), end);
+    if range.len() != TextSize::from(20) {
+        let message = format!("cross-reference entry is {} bytes long, expected 20", u32::from(range.len()));
+        errors.push(SyntaxError::new(message, range));
+    }
+}
+
+fn validate_xref_entry_offset(entry: &ast::XRefEntry, object_number: i64, ids: &HashMap<TextSize, (i64, i64)>, errors: &mut Vec<SyntaxError>) {
+    let Some(XRefEntryKind::InUse { offset, generation }) = entry.kind() else {
+        return;
+    };
+    let found = u32::try_from(offset).ok().and_then(|it| ids.get(&TextSize::from(it)));
+    if found != Some(&(object_number, generation as i64)) {
+        let message = format!(
+            "cross-reference entry for object {object_number} {generation} points to offset {offset}, \
+             which is not the start of `{object_number} {generation} obj`"
+        );
+        let range = entry.offset().map_or_else(|| entry.syntax().text_range(), |it| it.syntax().text_range());
+        errors.push(SyntaxError::new(message, range));
+    }
+}
+
+fn validate_unlisted_objects(objects: &[ast::IndirectObjectExpr], listed: &HashSet<i64>, errors: &mut Vec<SyntaxError>) {
+    for object in objects {
+        let Some((object_number, generation)) = object_id(object) else { continue };
+        if !listed.contains(&object_number) {
+            let message = format!("object {object_number} {generation} is not listed in a cross-reference table");
+            let range = object
+                .indirect_object_id()
+                .map_or_else(|| object.syntax().text_range(), |it| it.syntax().text_range());
+            errors.push(SyntaxError::new(message, range));
+        }
+    }
+}
+
+/// Every `startxref` must point to the `xref` keyword of a cross-reference
+/// table, or to a cross-reference stream.
+/// See ISO `32000-2:2020`, Section 7.5.5 File trailer.
+fn validate_startxref(document: &ast::PdfDocument, errors: &mut Vec<SyntaxError>) {
+    let tables = document
+        .revisions()
+        .filter_map(|it| it.x_ref_table())
+        .map(|it| it.syntax().text_range().start());
+    let streams = document
+        .revisions()
+        .filter_map(|it| it.body())
+        .flat_map(|it| it.indirect_object_exprs())
+        .filter(|it| it.is_x_ref_stream())
+        .map(|it| it.syntax().text_range().start());
+    let targets: HashSet<_> = tables.chain(streams).collect();
+
+    for literal in document.revisions().filter_map(|it| it.trailer()?.literal()) {
+        let Some(offset) = ast::Expr::Literal(literal.clone()).as_int() else {
+            continue;
+        };
+        if !u32::try_from(offset).is_ok_and(|it| targets.contains(&TextSize::from(it))) {
+            let message = format!("startxref offset {offset} doesn't point to a cross-reference table or stream");
+            errors.push(SyntaxError::new(message, literal.syntax().text_range()));
+        }
+    }
+}
+
+fn object_id(object: &ast::IndirectObjectExpr) -> Option<(i64, i64)> {
+    let id = object.indirect_object_id()?;
+    let object_number = ast::Expr::Literal(id.object_number()?).as_int()?;
+    let generation = ast::Expr::Literal(id.generation_number()?).as_int()?;
+    Some((object_number, generation))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Edition, PdfDocument};
+
+    fn errors(text: &str) -> Vec<String> {
+        let parse = PdfDocument::parse(text.as_bytes(), Edition::CURRENT);
+        parse.errors().iter().map(|it| format!("{:?}: {}", it.range(), it)).collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn header_and_eof_marker() {
+        assert_eq!(
+            errors("%PDF-1.7\n%\u{e2}\u{e3}\u{cf}\u{d3}\n1 0 obj null endobj\n%%EOF\n"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            errors("1 0 obj null endobj\n"),
+            vec![
+                "0..0: missing PDF header, expected `%PDF-x.y`",
+                "20..20: missing `%%EOF` marker at the end of the file"
+            ]
+        );
+        assert_eq!(errors("%PDF-9.9\n%%EOF"), vec!["0..8: malformed PDF header, unknown version"]);
+    }
+
+    #[test]
+    fn eof_marker_must_end_the_last_revision() {
+        let text = "%PDF-2.0\ntrailer << >> startxref 0\n%%EOF\n1 0 obj null endobj\n";
+        assert_eq!(
+            errors(text),
+            vec![
+                "33..34: startxref offset 0 doesn't point to a cross-reference table or stream",
+                "61..61: missing `%%EOF` marker at the end of the file"
+            ]
+        );
+    }
+
+    #[test]
+    fn xref_entries() {
+        let text = concat!(
+            "%PDF-1.7\n",
+            "1 0 obj null endobj\n",
+            "2 0 obj null endobj\n",
+            "3 0 obj null endobj\n",
+            "xref\n0 4\n",
+            "0000000000 65535 f \n",
+            "0000000009 00000 n \n",
+            "0000000030 00000 n \n",
+            "0000000049 00000 n\n",
+            "trailer << >>\nstartxref\n69\n%%EOF\n",
+        );
+        assert_eq!(
+            errors(text),
+            vec![
+                "118..128: cross-reference entry for object 2 0 points to offset 30, which is not the start of `2 0 obj`",
+                "138..157: cross-reference entry is 19 bytes long, expected 20",
+            ]
+        );
+    }
+
+    #[test]
+    fn xref_subsections_and_startxref() {
+        let text = concat!(
+            "%PDF-1.7\n",
+            "1 0 obj null endobj\n",
+            "2 0 obj null endobj\n",
+            "xref\n0 3\n",
+            "0000000000 65535 f\r\n",
+            "0000000009 00000 n\r\n",
+            "trailer << >>\nstartxref\n50\n%%EOF\n",
+        );
+        assert_eq!(
+            errors(text),
+            vec![
+                "29..36: object 2 0 is not listed in a cross-reference table",
+                "56..57: cross-reference subsection declares 3 entries, but has 2",
+                "122..124: startxref offset 50 doesn't point to a cross-reference table or stream",
+            ]
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Cross-reference information, from either a classic `xref` table or a
+//! cross-reference stream.
+//!
+//! Both forms map object numbers to [`XRefEntryKind`]s: tables through
+//! [`ast::XRefTable::entries`], streams through [`XRefStream::entries`].
+//!
+//! See ISO `32000-2:2020`, Sections 7.5.4 Cross-Reference Table and 7.5.8
+//! Cross-Reference Streams.
+
+use std::fmt;
+
+use crate::ast;
+
+/// Where to find an object, as recorded by a cross-reference entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XRefEntryKind {
+    /// The object number is not in use.
+    Free { next_free_object: u64, generation: u64 },
+    /// The object is stored in the file body at the given byte offset.
+    InUse { offset: u64, generation: u64 },
+    /// The object is stored in an object stream, at the given index.
+    Compressed { object_stream: u64, index: u64 },
+}
+
+/// A decoded cross-reference stream (a stream with `/Type /XRef`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XRefStream {
+    size: u64,
+    prev: Option<u64>,
+    entries: Vec<(u32, XRefEntryKind)>,
+}
+
+/// The reason why a cross-reference stream couldn't be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XRefStreamError {
+    /// The stream dictionary doesn't have `/Type /XRef`.
+    NotAnXRefStream,
+    /// An entry of the stream dictionary is missing or malformed.
+    InvalidDictionaryEntry(&'static str),
+    /// The stream data is shorter than the entries described by `/W` and
+    /// `/Index`.
+    TruncatedData,
+}
+
+impl fmt::Display for XRefStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XRefStreamError::NotAnXRefStream => write!(f, "stream is not a cross-reference stream"),
+            XRefStreamError::InvalidDictionaryEntry(key) => write!(f, "cross-reference stream has no valid {key} entry"),
+            XRefStreamError::TruncatedData => write!(f, "cross-reference stream data is truncated"),
+        }
+    }
+}
+
+impl std::error::Error for XRefStreamError {}
+
+impl XRefStream {
+    /// Decodes the entries of `stream`, whose decoded (unfiltered and
+    /// unpredicted) data is `data`.
+    pub fn parse(stream: &ast::StreamExpr, data: &[u8]) -> Result<XRefStream, XRefStreamError> {
+        let _p = tracing::info_span!("XRefStream::parse").entered();
+        let dictionary = stream.dictionary_expr().ok_or(XRefStreamError::NotAnXRefStream)?;
+        if !dictionary.get(b"/Type").is_some_and(|it| it.is_name(b"/XRef")) {
+            return Err(XRefStreamError::NotAnXRefStream);
+        }
+
+        let size = dictionary
+            .get(b"/Size")
+            .and_then(|it| non_negative(&it))
+            .ok_or(XRefStreamError::InvalidDictionaryEntry("/Size"))?;
+        let prev = match dictionary.get(b"/Prev") {
+            Some(it) => Some(non_negative(&it).ok_or(XRefStreamError::InvalidDictionaryEntry("/Prev"))?),
+            None => None,
+        };
+
+        let widths = match int_array(&dictionary, b"/W").as_deref() {
+            // A field can't be wider than the integers we store it in.
+            Some(&[w0, w1, w2]) if [w0, w1, w2].iter().all(|&it| it <= 8) => [w0 as usize, w1 as usize, w2 as usize],
+            _ => return Err(XRefStreamError::InvalidDictionaryEntry("/W")),
+        };
+        let entry_len: usize = widths.iter().sum();
+        if entry_len == 0 {
+            return Err(XRefStreamError::InvalidDictionaryEntry("/W"));
+        }
+
+        let index = match dictionary.get(b"/Index") {
+            Some(_) => match int_array(&dictionary, b"/Index") {
+                Some(it) if it.len() % 2 == 0 => it.chunks(2).map(|it| Some((u32::try_from(it[0]).ok()?, u32::try_from(it[1]).ok()?))).collect(),
+                _ => None,
+            },
+            None => u32::try_from(size).ok().map(|size| vec![(0, size)]),
+        };
+        let index: Vec<(u32, u32)> = index.ok_or(XRefStreamError::InvalidDictionaryEntry("/Index"))?;
+
+        let mut entries = Vec::new();
+        let mut rows = data.chunks_exact(entry_len);
+        for (first, count) in index {
+            for object_number in first..first.saturating_add(count) {
+                let row = rows.next().ok_or(XRefStreamError::TruncatedData)?;
+                let (field1, rest) = row.split_at(widths[0]);
+                let (field2, field3) = rest.split_at(widths[1]);
+
+                // A missing type field defaults to an in-use object.
+                let entry_type = if widths[0] == 0 { 1 } else { read_be(field1) };
+                let (field2, field3) = (read_be(field2), read_be(field3));
+                let kind = match entry_type {
+                    0 => XRefEntryKind::Free {
+                        next_free_object: field2,
+                        generation: field3,
+                    },
+                    1 => XRefEntryKind::InUse {
+                        offset: field2,
+                        generation: field3,
+                    },
+                    2 => XRefEntryKind::Compressed {
+                        object_stream: field2,
+                        index: field3,
+                    },
+                    // Other types are references to the null object.
+                    _ => continue,
+                };
+                entries.push((object_number, kind));
+            }
+        }
+
+        Ok(XRefStream { size, prev, entries })
+    }
+
+    /// The value of `/Size`, one greater than the highest object number.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The byte offset of the previous cross-reference section (`/Prev`).
+    pub fn prev(&self) -> Option<u64> {
+        self.prev
+    }
+
+    /// Iterates over the object numbers and their entries, in the order
+    /// they're stored in the stream.
+    pub fn entries(&self) -> impl Iterator<Item = (u32, XRefEntryKind)> + '_ {
+        self.entries.iter().copied()
+    }
+}
+
+impl ast::XRefTable {
+    /// Iterates over the object numbers and their entries, in the order
+    /// they're listed in the table. Malformed entries are skipped.
+    pub fn entries(&self) -> impl Iterator<Item = (u32, XRefEntryKind)> {
+        self.x_ref_sections().flat_map(|section| section.x_ref_subsections()).flat_map(|subsection| {
+            let first_object = subsection
+                .first_object()
+                .and_then(|it| u32::try_from(non_negative(&ast::Expr::Literal(it))?).ok());
+            subsection
+                .x_ref_entrys()
+                .enumerate()
+                .filter_map(move |(i, entry)| Some((first_object?.checked_add(u32::try_from(i).ok()?)?, entry.kind()?)))
+        })
+    }
+}
+
+impl ast::XRefEntry {
+    pub fn kind(&self) -> Option<XRefEntryKind> {
+        let offset = non_negative(&ast::Expr::Literal(self.offset()?))?;
+        let generation = non_negative(&ast::Expr::Literal(self.generation_number()?))?;
+        let entry_type = self.free_or_used()?;
+        if entry_type.n_token().is_some() {
+            Some(XRefEntryKind::InUse { offset, generation })
+        } else {
+            Some(XRefEntryKind::Free {
+                next_free_object: offset,
+                generation,
+            })
+        }
+    }
+}
+
+fn non_negative(expr: &ast::Expr) -> Option<u64> {
+    expr.as_int().and_then(|it| u64::try_from(it).ok())
+}
+
+fn int_array(dictionary: &ast::DictionaryExpr, key: &[u8]) -> Option<Vec<u64>> {
+    match dictionary.get(key)? {
+        ast::Expr::ArrayExpr(array) => array.exprs().map(|it| non_negative(&it)).collect(),
+        _ => None,
+    }
+}
+
+/// Reads a big-endian integer of at most 8 bytes.
+fn read_be(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |acc, &b| (acc << 8) | u64::from(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AstNode, Edition, PdfDocument};
+
+    fn xref_stream(dictionary: &str, data: &[u8]) -> Result<XRefStream, XRefStreamError> {
+        let text = format!("1 0 obj\n{dictionary}\nstream\nDATA\nendstream\nendobj\n");
+        let parse = PdfDocument::parse(text.as_bytes(), Edition::CURRENT);
+        let stream = parse.syntax_node().descendants().find_map(ast::StreamExpr::cast).unwrap();
+        XRefStream::parse(&stream, data)
+    }
+
+    #[test]
+    fn decodes_all_entry_types() {
+        #[rustfmt::skip]
+        let data = [
+            0, 0, 0, 0xff, 0xff,
+            1, 0, 0x11, 0, 0,
+            2, 0, 0x05, 0, 0x03,
+            7, 0, 0x00, 0, 0,
+        ];
+        let stream = xref_stream("<< /Type /XRef /Size 4 /W [1 2 2] /Prev 1234 >>", &data).unwrap();
+
+        assert_eq!(stream.size(), 4);
+        assert_eq!(stream.prev(), Some(1234));
+        assert_eq!(
+            stream.entries().collect::<Vec<_>>(),
+            vec![
+                (
+                    0,
+                    XRefEntryKind::Free {
+                        next_free_object: 0,
+                        generation: 0xffff
+                    }
+                ),
+                (1, XRefEntryKind::InUse { offset: 0x11, generation: 0 }),
+                (2, XRefEntryKind::Compressed { object_stream: 5, index: 3 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_index_subsections_and_default_type() {
+        let data = [0x10, 0x20, 0x30];
+        let stream = xref_stream("<< /Type /XRef /Size 20 /Index [3 1 10 2] /W [0 1 0] >>", &data).unwrap();
+
+        assert_eq!(stream.prev(), None);
+        assert_eq!(
+            stream.entries().collect::<Vec<_>>(),
+            vec![
+                (3, XRefEntryKind::InUse { offset: 0x10, generation: 0 }),
+                (10, XRefEntryKind::InUse { offset: 0x20, generation: 0 }),
+                (11, XRefEntryKind::InUse { offset: 0x30, generation: 0 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_streams() {
+        let err = xref_stream("<< /Type /ObjStm /Size 1 /W [1 1 1] >>", &[1, 0, 0]).unwrap_err();
+        assert_eq!(err, XRefStreamError::NotAnXRefStream);
+
+        let err = xref_stream("<< /Type /XRef /W [1 1 1] >>", &[1, 0, 0]).unwrap_err();
+        assert_eq!(err, XRefStreamError::InvalidDictionaryEntry("/Size"));
+
+        let err = xref_stream("<< /Type /XRef /Size 1 /W [1 1] >>", &[1, 0]).unwrap_err();
+        assert_eq!(err, XRefStreamError::InvalidDictionaryEntry("/W"));
+
+        let err = xref_stream("<< /Type /XRef /Size 2 /Index [0] /W [1 1 1] >>", &[1, 0, 0]).unwrap_err();
+        assert_eq!(err, XRefStreamError::InvalidDictionaryEntry("/Index"));
+
+        let err = xref_stream("<< /Type /XRef /Size 2 /W [1 1 1] >>", &[1, 0, 0]).unwrap_err();
+        assert_eq!(err, XRefStreamError::TruncatedData);
+    }
+
+    #[test]
+    fn table_and_stream_entries_agree() {
+        let text = b"xref\n0 3\n0000000000 65535 f\n0000000017 00000 n\n0000000081 00002 n\n";
+        let parse = PdfDocument::parse(text, Edition::CURRENT);
+        let table = parse.syntax_node().descendants().find_map(ast::XRefTable::cast).unwrap();
+
+        let data = [0, 0, 0, 0xff, 0xff, 1, 0, 17, 0, 0, 1, 0, 81, 0, 2];
+        let stream = xref_stream("<< /Type /XRef /Size 3 /W [1 2 2] >>", &data).unwrap();
+
+        assert_eq!(table.entries().collect::<Vec<_>>(), stream.entries().collect::<Vec<_>>());
+    }
+}
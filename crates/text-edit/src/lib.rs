@@ -0,0 +1,338 @@
+//! Representation of a `TextEdit`.
+//!
+//! PDF files are byte strings rather than UTF-8 text, so unlike most editors'
+//! notion of an edit, the inserted text here is a sequence of raw bytes.
+
+use std::cmp::max;
+
+use itertools::Itertools;
+
+pub use text_size::{TextRange, TextSize};
+
+/// `InsertDelete` -- a single "atomic" change to text
+///
+/// Must not overlap with other `InDel`s
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Indel {
+    pub insert: Vec<u8>,
+    /// Refers to offsets in the original text
+    pub delete: TextRange,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct TextEdit {
+    /// Invariant: disjoint and sorted by `delete`.
+    indels: Vec<Indel>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TextEditBuilder {
+    indels: Vec<Indel>,
+}
+
+impl Indel {
+    pub fn insert(offset: TextSize, text: Vec<u8>) -> Indel {
+        Indel::replace(TextRange::empty(offset), text)
+    }
+
+    pub fn delete(range: TextRange) -> Indel {
+        Indel::replace(range, Vec::new())
+    }
+
+    pub fn replace(range: TextRange, replace_with: Vec<u8>) -> Indel {
+        Indel {
+            delete: range,
+            insert: replace_with,
+        }
+    }
+
+    /// Length of the inserted text.
+    pub fn insert_len(&self) -> TextSize {
+        len_of(&self.insert)
+    }
+
+    pub fn apply(&self, text: &mut Vec<u8>) {
+        let start: usize = self.delete.start().into();
+        let end: usize = self.delete.end().into();
+        text.splice(start..end, self.insert.iter().copied());
+    }
+}
+
+impl TextEdit {
+    pub fn builder() -> TextEditBuilder {
+        TextEditBuilder::default()
+    }
+
+    pub fn insert(offset: TextSize, text: Vec<u8>) -> TextEdit {
+        let mut builder = TextEdit::builder();
+        builder.insert(offset, text);
+        builder.finish()
+    }
+
+    pub fn delete(range: TextRange) -> TextEdit {
+        let mut builder = TextEdit::builder();
+        builder.delete(range);
+        builder.finish()
+    }
+
+    pub fn replace(range: TextRange, replace_with: Vec<u8>) -> TextEdit {
+        let mut builder = TextEdit::builder();
+        builder.replace(range, replace_with);
+        builder.finish()
+    }
+
+    pub fn len(&self) -> usize {
+        self.indels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indels.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Indel> {
+        self.into_iter()
+    }
+
+    pub fn apply(&self, text: &mut Vec<u8>) {
+        match self.len() {
+            0 => return,
+            1 => {
+                self.indels[0].apply(text);
+                return;
+            }
+            _ => (),
+        }
+
+        let text_size = len_of(text);
+        let mut total_len = text_size;
+        let mut max_total_len = text_size;
+        for indel in &self.indels {
+            total_len += indel.insert_len();
+            total_len -= indel.delete.len();
+            max_total_len = max(max_total_len, total_len);
+        }
+
+        if let Some(additional) = max_total_len.checked_sub(text_size) {
+            text.reserve(additional.into());
+        }
+
+        for indel in self.indels.iter().rev() {
+            indel.apply(text);
+        }
+
+        assert_eq!(len_of(text), total_len);
+    }
+
+    /// Merges `other` into `self`, failing (and returning `other` back) if the
+    /// two edits overlap.
+    pub fn union(&mut self, other: TextEdit) -> Result<(), TextEdit> {
+        let iter_merge = self.iter().merge_by(other.iter(), |l, r| l.delete.start() <= r.delete.start());
+        if !check_disjoint(&mut iter_merge.clone()) {
+            return Err(other);
+        }
+
+        // Only dedup deletions and replacements, keep all insertions
+        self.indels = iter_merge.dedup_by(|a, b| a == b && !a.delete.is_empty()).cloned().collect();
+        Ok(())
+    }
+
+    /// Maps an offset in the original text to the corresponding offset in the
+    /// edited text, or `None` if the offset is inside a deleted range.
+    pub fn apply_to_offset(&self, offset: TextSize) -> Option<TextSize> {
+        let mut res = offset;
+        for indel in &self.indels {
+            if indel.delete.start() >= offset {
+                break;
+            }
+            if offset < indel.delete.end() {
+                return None;
+            }
+            res += indel.insert_len();
+            res -= indel.delete.len();
+        }
+        Some(res)
+    }
+}
+
+impl IntoIterator for TextEdit {
+    type Item = Indel;
+    type IntoIter = std::vec::IntoIter<Indel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.indels.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a TextEdit {
+    type Item = &'a Indel;
+    type IntoIter = std::slice::Iter<'a, Indel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.indels.iter()
+    }
+}
+
+impl TextEditBuilder {
+    pub fn is_empty(&self) -> bool {
+        self.indels.is_empty()
+    }
+
+    pub fn replace(&mut self, range: TextRange, replace_with: Vec<u8>) {
+        self.indel(Indel::replace(range, replace_with));
+    }
+
+    pub fn delete(&mut self, range: TextRange) {
+        self.indel(Indel::delete(range));
+    }
+
+    pub fn insert(&mut self, offset: TextSize, text: Vec<u8>) {
+        self.indel(Indel::insert(offset, text));
+    }
+
+    pub fn finish(self) -> TextEdit {
+        let mut indels = self.indels;
+        assert_disjoint_or_equal(&mut indels);
+        indels = coalesce_indels(indels);
+        TextEdit { indels }
+    }
+
+    pub fn invalidates_offset(&self, offset: TextSize) -> bool {
+        self.indels.iter().any(|indel| indel.delete.contains_inclusive(offset))
+    }
+
+    fn indel(&mut self, indel: Indel) {
+        self.indels.push(indel);
+        if self.indels.len() <= 16 {
+            assert_disjoint_or_equal(&mut self.indels);
+        }
+    }
+}
+
+fn len_of(text: &[u8]) -> TextSize {
+    TextSize::try_from(text.len()).unwrap()
+}
+
+fn assert_disjoint_or_equal(indels: &mut [Indel]) {
+    assert!(check_disjoint_and_sort(indels));
+}
+
+fn check_disjoint_and_sort(indels: &mut [Indel]) -> bool {
+    indels.sort_by_key(|indel| (indel.delete.start(), indel.delete.end()));
+    check_disjoint(&mut indels.iter())
+}
+
+fn check_disjoint<'a, I>(indels: &mut I) -> bool
+where
+    I: std::iter::Iterator<Item = &'a Indel> + Clone,
+{
+    indels.clone().zip(indels.skip(1)).all(|(l, r)| l.delete.end() <= r.delete.start() || l == r)
+}
+
+fn coalesce_indels(indels: Vec<Indel>) -> Vec<Indel> {
+    indels
+        .into_iter()
+        .coalesce(|mut a, b| {
+            if a.delete.end() == b.delete.start() {
+                a.insert.extend_from_slice(&b.insert);
+                a.delete = TextRange::new(a.delete.start(), b.delete.end());
+                Ok(a)
+            } else {
+                Err((a, b))
+            }
+        })
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TextEdit, TextEditBuilder, TextRange};
+
+    fn range(start: u32, end: u32) -> TextRange {
+        TextRange::new(start.into(), end.into())
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut text = b"_11h1_2222_xx3333_4444_6666".to_vec();
+
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(3, 4), b"1".to_vec());
+        builder.delete(range(11, 13));
+        builder.insert(22.into(), b"_5555".to_vec());
+
+        let text_edit = builder.finish();
+        text_edit.apply(&mut text);
+
+        assert_eq!(text, b"_1111_2222_3333_4444_5555_6666")
+    }
+
+    #[test]
+    fn test_union() {
+        let mut edit1 = TextEdit::delete(range(7, 11));
+        let mut builder = TextEditBuilder::default();
+        builder.delete(range(1, 5));
+        builder.delete(range(13, 17));
+
+        let edit2 = builder.finish();
+        assert!(edit1.union(edit2).is_ok());
+        assert_eq!(edit1.indels.len(), 3);
+    }
+
+    #[test]
+    fn test_union_with_duplicates() {
+        let mut builder1 = TextEditBuilder::default();
+        builder1.delete(range(7, 11));
+        builder1.delete(range(13, 17));
+
+        let mut builder2 = TextEditBuilder::default();
+        builder2.delete(range(1, 5));
+        builder2.delete(range(13, 17));
+
+        let mut edit1 = builder1.finish();
+        let edit2 = builder2.finish();
+        assert!(edit1.union(edit2).is_ok());
+        assert_eq!(edit1.indels.len(), 3);
+    }
+
+    #[test]
+    fn test_union_panics() {
+        let mut edit1 = TextEdit::delete(range(7, 11));
+        let edit2 = TextEdit::delete(range(9, 13));
+        assert!(edit1.union(edit2).is_err());
+    }
+
+    #[test]
+    fn test_coalesce_disjoint() {
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(1, 3), b"aa".to_vec());
+        builder.replace(range(5, 7), b"bb".to_vec());
+        let edit = builder.finish();
+
+        assert_eq!(edit.indels.len(), 2);
+    }
+
+    #[test]
+    fn test_coalesce_adjacent() {
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(1, 3), b"aa".to_vec());
+        builder.replace(range(3, 5), b"bb".to_vec());
+
+        let edit = builder.finish();
+        assert_eq!(edit.indels.len(), 1);
+        assert_eq!(edit.indels[0].insert, b"aabb");
+        assert_eq!(edit.indels[0].delete, range(1, 5));
+    }
+
+    #[test]
+    fn test_apply_to_offset() {
+        let mut builder = TextEditBuilder::default();
+        builder.delete(range(2, 4));
+        builder.insert(6.into(), b"xyz".to_vec());
+        let edit = builder.finish();
+
+        assert_eq!(edit.apply_to_offset(1.into()), Some(1.into()));
+        assert_eq!(edit.apply_to_offset(3.into()), None);
+        assert_eq!(edit.apply_to_offset(5.into()), Some(3.into()));
+        assert_eq!(edit.apply_to_offset(8.into()), Some(9.into()));
+    }
+}
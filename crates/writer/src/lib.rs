@@ -0,0 +1,651 @@
+//! Serialization of objects into PDF files.
+//!
+//! [`write`] lays out the header, a comment of binary bytes (so that file
+//! transfers treat the file as binary), the objects, the cross-reference
+//! information and the trailer, computing every offset on the way. The
+//! cross-reference information is either a classic `xref` table or a
+//! cross-reference stream, optionally with the objects packed into object
+//! streams.
+//!
+//! [`write_update`] appends an incremental update to an existing file
+//! instead.
+//!
+//! See ISO `32000-2:2020`, Section 7.5 File structure.
+
+mod object;
+mod serialize;
+mod update;
+
+use std::{collections::BTreeMap, fmt};
+
+use document::ObjectId;
+use syntax::{Edition, XRefEntryKind};
+
+pub use crate::object::{Dictionary, Object, Stream};
+use crate::serialize::Serializer;
+pub use crate::update::{Update, write_update};
+
+/// The comment following the header, with 4 bytes above 127.
+const BINARY_COMMENT: &[u8] = b"%\xE2\xE3\xCF\xD3\n";
+
+/// How many objects go into each object stream.
+const OBJECTS_PER_STREAM: usize = 100;
+
+/// The generation number of the head of the free list, object 0.
+const FREE_LIST_HEAD_GENERATION: u64 = 65535;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// The version written in the header.
+    pub edition: Edition,
+    pub layout: Layout,
+    pub xref: XRefForm,
+}
+
+impl Default for WriteOptions {
+    fn default() -> WriteOptions {
+        WriteOptions {
+            edition: Edition::CURRENT,
+            layout: Layout::Pretty,
+            xref: XRefForm::Table,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// As little whitespace as possible.
+    Compact,
+    /// Every indirect object and dictionary entry on its own line, and
+    /// nested dictionaries indented.
+    Pretty,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XRefForm {
+    /// A classic `xref` table, followed by the `trailer` dictionary.
+    Table,
+    /// A cross-reference stream, which also holds the entries of the trailer
+    /// dictionary. With `object_streams`, objects with generation 0 which
+    /// aren't streams are packed into object streams.
+    ///
+    /// Both streams are left unfiltered.
+    Stream { object_streams: bool },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WriteError {
+    /// Object number 0 is the head of the list of free objects.
+    ReservedObjectNumber,
+    /// Two objects have the same number and different generations.
+    DuplicateObjectNumber(u32),
+    /// A stream is nested in another value, in the given object or in the
+    /// trailer if there is none.
+    NestedStream(Option<ObjectId>),
+    /// A real is infinite or NaN, in the given object or in the trailer if
+    /// there is none.
+    NonFiniteReal(Option<ObjectId>),
+    /// Cross-reference streams require PDF 1.5.
+    UnsupportedEdition(Edition),
+    /// The file being updated has no `startxref` for `/Prev` to point to.
+    MissingStartXRef,
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = |id: &Option<ObjectId>| id.map_or_else(|| "the trailer".to_owned(), |it| format!("object {it}"));
+        match self {
+            WriteError::ReservedObjectNumber => write!(f, "object number 0 is reserved"),
+            WriteError::DuplicateObjectNumber(number) => write!(f, "object number {number} is used more than once"),
+            WriteError::NestedStream(id) => write!(f, "{} has a stream nested in another value", location(id)),
+            WriteError::NonFiniteReal(id) => write!(f, "{} has a real which is not finite", location(id)),
+            WriteError::UnsupportedEdition(edition) => write!(f, "cross-reference streams require PDF 1.5, not {edition}"),
+            WriteError::MissingStartXRef => write!(f, "the file has no `startxref` to update"),
+        }
+    }
+}
+
+impl std::error::Error for WriteError {}
+
+/// Writes `objects` into a new file, with the entries of `trailer` (usually
+/// `/Root`, `/Info` and `/ID`) in its trailer.
+///
+/// `/Size` and the entries describing the cross-reference stream are set by
+/// the writer, as is the `/Length` of every stream. `/Prev` is dropped, as
+/// the file has a single revision.
+pub fn write(objects: &BTreeMap<ObjectId, Object>, trailer: &Dictionary, options: &WriteOptions) -> Result<Vec<u8>, WriteError> {
+    let _p = tracing::info_span!("writer::write").entered();
+    check(objects, trailer, options.edition, options.xref)?;
+
+    let mut s = Serializer::new(options.layout);
+    s.raw(format!("%PDF-{}\n", options.edition).as_bytes());
+    s.raw(BINARY_COMMENT);
+
+    let mut trailer = trailer.clone();
+    trailer.remove(b"/Prev");
+    let size = objects.keys().map(|it| it.number + 1).max().unwrap_or(1);
+    let (entries, size) = write_body(&mut s, objects, &trailer, size, options);
+    write_xref_section(&mut s, entries, size, trailer, options.xref, true);
+    Ok(s.out)
+}
+
+/// Writes the indirect objects, packing them into object streams numbered
+/// from `size` if asked to.
+///
+/// Returns the cross-reference entries of the written objects, and the size
+/// including the object streams.
+pub(crate) fn write_body(
+    s: &mut Serializer,
+    objects: &BTreeMap<ObjectId, Object>,
+    trailer: &Dictionary,
+    size: u32,
+    options: &WriteOptions,
+) -> (BTreeMap<u32, XRefEntryKind>, u32) {
+    let packed: Vec<_> = match options.xref {
+        XRefForm::Stream { object_streams: true } => {
+            // The encryption dictionary must be readable before object
+            // streams are decrypted.
+            let encrypt = match trailer.get(b"/Encrypt") {
+                Some(Object::Reference(id)) => Some(*id),
+                _ => None,
+            };
+            let packable = |(id, value): &(&ObjectId, &Object)| id.generation == 0 && !matches!(value, Object::Stream(_)) && Some(**id) != encrypt;
+            objects.iter().filter(packable).collect()
+        }
+        XRefForm::Table | XRefForm::Stream { .. } => Vec::new(),
+    };
+
+    let mut entries = BTreeMap::new();
+    for (id, value) in objects {
+        if packed.binary_search_by_key(&id, |(it, _)| it).is_ok() {
+            continue;
+        }
+        entries.insert(
+            id.number,
+            XRefEntryKind::InUse {
+                offset: s.offset(),
+                generation: id.generation.into(),
+            },
+        );
+        s.object_header(id.number, id.generation);
+        s.value(value);
+        s.object_footer();
+    }
+
+    let mut next_number = size;
+    for chunk in packed.chunks(OBJECTS_PER_STREAM) {
+        let number = next_number;
+        next_number += 1;
+        let stream = object_stream(chunk, options.layout);
+        entries.insert(
+            number,
+            XRefEntryKind::InUse {
+                offset: s.offset(),
+                generation: 0,
+            },
+        );
+        for (index, (id, _)) in chunk.iter().enumerate() {
+            entries.insert(
+                id.number,
+                XRefEntryKind::Compressed {
+                    object_stream: number.into(),
+                    index: index as u64,
+                },
+            );
+        }
+        s.object_header(number, 0);
+        s.value(&Object::Stream(stream));
+        s.object_footer();
+    }
+    (entries, next_number)
+}
+
+/// Writes the cross-reference section listing `entries`, the trailer and
+/// `startxref`. A cross-reference stream is given number `size`.
+///
+/// With `complete`, the section covers every number below the size, the
+/// unused ones being free. Otherwise it only covers `entries`.
+pub(crate) fn write_xref_section(
+    s: &mut Serializer,
+    mut entries: BTreeMap<u32, XRefEntryKind>,
+    size: u32,
+    mut trailer: Dictionary,
+    xref: XRefForm,
+    complete: bool,
+) {
+    let startxref = s.offset();
+    match xref {
+        XRefForm::Table => {
+            if complete {
+                entries = with_free_entries(entries, size);
+            }
+            trailer.set(b"/Size", i64::from(size));
+            s.raw(b"xref\n");
+            let entries: Vec<_> = entries.into_iter().collect();
+            for subsection in entries.chunk_by(|(a, _), (b, _)| a + 1 == *b) {
+                s.raw(format!("{} {}\n", subsection[0].0, subsection.len()).as_bytes());
+                for (_, entry) in subsection {
+                    let line = match *entry {
+                        XRefEntryKind::Free { next_free_object, generation } => format!("{next_free_object:010} {generation:05} f \n"),
+                        XRefEntryKind::InUse { offset, generation } => format!("{offset:010} {generation:05} n \n"),
+                        XRefEntryKind::Compressed { .. } => unreachable!("tables have no compressed entries"),
+                    };
+                    s.raw(line.as_bytes());
+                }
+            }
+            s.token(b"trailer");
+            if s.layout == Layout::Pretty {
+                s.raw(b"\n");
+            }
+            s.dictionary(&trailer);
+            s.raw(b"\n");
+        }
+        XRefForm::Stream { .. } => {
+            entries.insert(
+                size,
+                XRefEntryKind::InUse {
+                    offset: startxref,
+                    generation: 0,
+                },
+            );
+            if complete {
+                entries = with_free_entries(entries, size + 1);
+            }
+            s.object_header(size, 0);
+            s.value(&Object::Stream(xref_stream(&entries, size + 1, trailer)));
+            s.object_footer();
+        }
+    }
+    s.raw(format!("startxref\n{startxref}\n%%EOF\n").as_bytes());
+}
+
+pub(crate) fn check(objects: &BTreeMap<ObjectId, Object>, trailer: &Dictionary, edition: Edition, xref: XRefForm) -> Result<(), WriteError> {
+    if matches!(xref, XRefForm::Stream { .. }) && edition < Edition::Pdf15 {
+        return Err(WriteError::UnsupportedEdition(edition));
+    }
+    let mut previous = None;
+    for (id, value) in objects {
+        if id.number == 0 {
+            return Err(WriteError::ReservedObjectNumber);
+        }
+        if previous == Some(id.number) {
+            return Err(WriteError::DuplicateObjectNumber(id.number));
+        }
+        previous = Some(id.number);
+        match value {
+            Object::Stream(stream) => check_value(&Object::Dictionary(stream.dictionary.clone()), Some(*id))?,
+            value => check_value(value, Some(*id))?,
+        }
+    }
+    check_value(&Object::Dictionary(trailer.clone()), None)
+}
+
+/// Checks that `value` has no streams and only finite reals.
+fn check_value(value: &Object, id: Option<ObjectId>) -> Result<(), WriteError> {
+    match value {
+        Object::Real(value) if !value.is_finite() => Err(WriteError::NonFiniteReal(id)),
+        Object::Stream(_) => Err(WriteError::NestedStream(id)),
+        Object::Array(items) => items.iter().try_for_each(|it| check_value(it, id)),
+        Object::Dictionary(dictionary) => dictionary.iter().try_for_each(|(_, it)| check_value(it, id)),
+        _ => Ok(()),
+    }
+}
+
+/// Adds the free entries: object 0 and the unused numbers below `size`, as a
+/// list in ascending order.
+fn with_free_entries(mut entries: BTreeMap<u32, XRefEntryKind>, size: u32) -> BTreeMap<u32, XRefEntryKind> {
+    let free: Vec<u32> = (0..size).filter(|it| !entries.contains_key(it)).collect();
+    for (i, &number) in free.iter().enumerate() {
+        let next_free_object = free.get(i + 1).map_or(0, |&it| it.into());
+        let generation = if number == 0 { FREE_LIST_HEAD_GENERATION } else { 0 };
+        entries.insert(number, XRefEntryKind::Free { next_free_object, generation });
+    }
+    entries
+}
+
+/// Packs `objects` into an object stream: pairs of object numbers and
+/// offsets, then the objects, separated by line breaks.
+/// See ISO `32000-2:2020`, Section 7.5.7 Object streams.
+fn object_stream(objects: &[(&ObjectId, &Object)], layout: Layout) -> Stream {
+    let mut body = Serializer::new(layout);
+    let mut header = Vec::new();
+    for (id, value) in objects {
+        if body.offset() > 0 {
+            body.raw(b"\n");
+        }
+        header.push(format!("{} {}", id.number, body.offset()));
+        body.value(value);
+    }
+    let header = header.join(" ") + "\n";
+    let dictionary = Dictionary::new()
+        .with(b"/Type", Object::name(b"/ObjStm"))
+        .with(b"/N", objects.len() as i64)
+        .with(b"/First", header.len() as i64);
+    Stream::new(dictionary, [header.into_bytes(), body.out].concat())
+}
+
+/// Encodes `entries` as a cross-reference stream, whose dictionary also holds
+/// the entries of `trailer`. `/Index` is only written if `entries` aren't all
+/// the numbers below `size`.
+/// See ISO `32000-2:2020`, Section 7.5.8 Cross-reference streams.
+fn xref_stream(entries: &BTreeMap<u32, XRefEntryKind>, size: u32, trailer: Dictionary) -> Stream {
+    let fields: Vec<(u8, u64, u64)> = entries
+        .values()
+        .map(|entry| match *entry {
+            XRefEntryKind::Free { next_free_object, generation } => (0, next_free_object, generation),
+            XRefEntryKind::InUse { offset, generation } => (1, offset, generation),
+            XRefEntryKind::Compressed { object_stream, index } => (2, object_stream, index),
+        })
+        .collect();
+    let width = |value: u64| (u64::BITS - value.leading_zeros()).div_ceil(8).max(1) as usize;
+    let w2 = fields.iter().map(|it| width(it.1)).max().unwrap_or(1);
+    let w3 = fields.iter().map(|it| width(it.2)).max().unwrap_or(1);
+    let mut data = Vec::with_capacity(fields.len() * (1 + w2 + w3));
+    for (kind, field2, field3) in fields {
+        data.push(kind);
+        data.extend_from_slice(&field2.to_be_bytes()[8 - w2..]);
+        data.extend_from_slice(&field3.to_be_bytes()[8 - w3..]);
+    }
+
+    let mut dictionary = Dictionary::new().with(b"/Type", Object::name(b"/XRef")).with(b"/Size", i64::from(size));
+    if entries.len() != size as usize {
+        let numbers: Vec<u32> = entries.keys().copied().collect();
+        let index = numbers
+            .chunk_by(|a, b| a + 1 == *b)
+            .flat_map(|it| [Object::Int(it[0].into()), Object::Int(it.len() as i64)])
+            .collect::<Vec<_>>();
+        dictionary.set(b"/Index", index);
+    }
+    dictionary.set(b"/W", vec![Object::Int(1), Object::Int(w2 as i64), Object::Int(w3 as i64)]);
+    for (key, value) in trailer.iter() {
+        if !matches!(key, b"/Type" | b"/Size" | b"/W" | b"/Index" | b"/Filter" | b"/DecodeParms") {
+            dictionary.set(key, value.clone());
+        }
+    }
+    Stream::new(dictionary, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{Expect, expect};
+    use syntax::{AstNode, ObjectStream, PdfDocument, XRefStream, ast};
+
+    use super::*;
+
+    fn id(number: u32) -> ObjectId {
+        ObjectId::new(number, 0)
+    }
+
+    /// A document with one page, and an information dictionary with strings
+    /// and names which need escaping.
+    fn sample() -> (BTreeMap<ObjectId, Object>, Dictionary) {
+        let page = Dictionary::new()
+            .with(b"/Type", Object::name(b"/Page"))
+            .with(b"/Parent", id(2))
+            .with(b"/MediaBox", vec![0.into(), 0.into(), 612.into(), 792.5.into()])
+            .with(b"/Resources", Dictionary::new().with(b"/Font", Dictionary::new()))
+            .with(b"/Contents", id(4));
+        let objects = BTreeMap::from([
+            (id(1), Dictionary::new().with(b"/Type", Object::name(b"/Catalog")).with(b"/Pages", id(2)).into()),
+            (
+                id(2),
+                Dictionary::new()
+                    .with(b"/Type", Object::name(b"/Pages"))
+                    .with(b"/Kids", vec![id(3).into()])
+                    .with(b"/Count", 1)
+                    .into(),
+            ),
+            (id(3), page.into()),
+            (id(4), Stream::new(Dictionary::new(), b"BT /F1 12 Tf (Hi) Tj ET".to_vec()).into()),
+            (
+                id(6),
+                Dictionary::new()
+                    .with(b"/Title", Object::string(b"(a)\\b\r"))
+                    .with(b"/Subject", Object::name(b"/A B#"))
+                    .with(b"/Trapped", Object::name(b"/"))
+                    .with(b"/Marked", true)
+                    .into(),
+            ),
+        ]);
+        let trailer = Dictionary::new().with(b"/Root", id(1)).with(b"/Info", id(6)).with(b"/Prev", 10);
+        (objects, trailer)
+    }
+
+    fn write_valid(options: &WriteOptions) -> Vec<u8> {
+        let (objects, trailer) = sample();
+        let text = write(&objects, &trailer, options).unwrap();
+        let parse = PdfDocument::parse(&text, options.edition);
+        assert_eq!(parse.errors(), Vec::new(), "{}", String::from_utf8_lossy(&text));
+        text
+    }
+
+    fn check(options: WriteOptions, expect: Expect) {
+        let text = write_valid(&options);
+        expect.assert_eq(&String::from_utf8_lossy(&text));
+    }
+
+    #[test]
+    fn pretty_with_table() {
+        check(
+            WriteOptions::default(),
+            expect![[r#"
+                %PDF-2.0
+                %����
+                1 0 obj
+                <<
+                  /Type /Catalog
+                  /Pages 2 0 R
+                >>
+                endobj
+                2 0 obj
+                <<
+                  /Type /Pages
+                  /Kids [3 0 R]
+                  /Count 1
+                >>
+                endobj
+                3 0 obj
+                <<
+                  /Type /Page
+                  /Parent 2 0 R
+                  /MediaBox [0 0 612 792.5]
+                  /Resources <<
+                    /Font << >>
+                  >>
+                  /Contents 4 0 R
+                >>
+                endobj
+                4 0 obj
+                <<
+                  /Length 23
+                >>
+                stream
+                BT /F1 12 Tf (Hi) Tj ET
+                endstream
+                endobj
+                6 0 obj
+                <<
+                  /Title (\(a\)\\b\r)
+                  /Subject /A#20B#23
+                  /Trapped /
+                  /Marked true
+                >>
+                endobj
+                xref
+                0 7
+                0000000005 65535 f 
+                0000000015 00000 n 
+                0000000068 00000 n 
+                0000000131 00000 n 
+                0000000265 00000 n 
+                0000000000 00000 f 
+                0000000340 00000 n 
+                trailer
+                <<
+                  /Root 1 0 R
+                  /Info 6 0 R
+                  /Size 7
+                >>
+                startxref
+                432
+                %%EOF
+            "#]],
+        );
+    }
+
+    #[test]
+    fn compact_with_table() {
+        check(
+            WriteOptions {
+                layout: Layout::Compact,
+                edition: Edition::Pdf17,
+                ..WriteOptions::default()
+            },
+            expect![[r#"
+                %PDF-1.7
+                %����
+                1 0 obj<</Type/Catalog/Pages 2 0 R>>endobj
+                2 0 obj<</Type/Pages/Kids[3 0 R]/Count 1>>endobj
+                3 0 obj<</Type/Page/Parent 2 0 R/MediaBox[0 0 612 792.5]/Resources<</Font<<>>>>/Contents 4 0 R>>endobj
+                4 0 obj<</Length 23>>stream
+                BT /F1 12 Tf (Hi) Tj ET
+                endstream endobj
+                6 0 obj<</Title(\(a\)\\b\r)/Subject/A#20B#23/Trapped//Marked true>>endobj
+                xref
+                0 7
+                0000000005 65535 f 
+                0000000015 00000 n 
+                0000000058 00000 n 
+                0000000107 00000 n 
+                0000000210 00000 n 
+                0000000000 00000 f 
+                0000000279 00000 n 
+                trailer<</Root 1 0 R/Info 6 0 R/Size 7>>
+                startxref
+                353
+                %%EOF
+            "#]],
+        );
+    }
+
+    /// Writes the sample with an xref stream and object streams, checks that
+    /// every entry of the xref stream points to its object, and checks the
+    /// packed objects against `expect`.
+    fn check_object_streams(layout: Layout, expect: Expect) {
+        let edition = Edition::Pdf15;
+        let options = WriteOptions {
+            edition,
+            layout,
+            xref: XRefForm::Stream { object_streams: true },
+        };
+        let text = write_valid(&options);
+        let file = PdfDocument::parse(&text, edition).tree();
+
+        let objects: Vec<_> = file.revisions().filter_map(|it| it.body()).flat_map(|it| it.indirect_object_exprs()).collect();
+        let at_offset = |offset: u64| {
+            objects
+                .iter()
+                .find(|it| u64::from(u32::from(it.syntax().text_range().start())) == offset)
+                .unwrap()
+        };
+        let startxref = file.revisions().last().and_then(|it| it.trailer()?.literal()).unwrap();
+        let xref = at_offset(ast::Expr::Literal(startxref).as_int().unwrap() as u64).stream_expr().unwrap();
+        let xref = XRefStream::parse(&xref, &xref.raw_data().unwrap()).unwrap();
+        assert_eq!(xref.size(), 9);
+
+        let mut packed = Vec::new();
+        for (number, entry) in xref.entries() {
+            match entry {
+                XRefEntryKind::InUse { offset, .. } => {
+                    assert_eq!(ObjectId::of_object(at_offset(offset)), Some(id(number)));
+                }
+                XRefEntryKind::Compressed { object_stream, index } => {
+                    let stream = objects
+                        .iter()
+                        .find(|it| ObjectId::of_object(it) == Some(id(object_stream as u32)))
+                        .and_then(|it| it.stream_expr())
+                        .unwrap();
+                    let data = stream.raw_data().unwrap();
+                    let packed_objects = ObjectStream::parse(&stream, &data, edition).unwrap();
+                    let object = packed_objects.get(number).unwrap();
+                    packed.push(format!(
+                        "{number} in {object_stream} at {index}: {}",
+                        object.syntax_node().text().to_string().trim_end()
+                    ));
+                }
+                XRefEntryKind::Free { .. } => (),
+            }
+        }
+        expect.assert_eq(&packed.join("\n"));
+    }
+
+    #[test]
+    fn compact_with_object_streams() {
+        check_object_streams(
+            Layout::Compact,
+            expect![[r#"
+                1 in 7 at 0: <</Type/Catalog/Pages 2 0 R>>
+                2 in 7 at 1: <</Type/Pages/Kids[3 0 R]/Count 1>>
+                3 in 7 at 2: <</Type/Page/Parent 2 0 R/MediaBox[0 0 612 792.5]/Resources<</Font<<>>>>/Contents 4 0 R>>
+                6 in 7 at 3: <</Title(\(a\)\\b\r)/Subject/A#20B#23/Trapped//Marked true>>"#]],
+        );
+    }
+
+    #[test]
+    fn pretty_with_object_streams() {
+        check_object_streams(
+            Layout::Pretty,
+            expect![[r#"
+                1 in 7 at 0: <<
+                  /Type /Catalog
+                  /Pages 2 0 R
+                >>
+                2 in 7 at 1: <<
+                  /Type /Pages
+                  /Kids [3 0 R]
+                  /Count 1
+                >>
+                3 in 7 at 2: <<
+                  /Type /Page
+                  /Parent 2 0 R
+                  /MediaBox [0 0 612 792.5]
+                  /Resources <<
+                    /Font << >>
+                  >>
+                  /Contents 4 0 R
+                >>
+                6 in 7 at 3: <<
+                  /Title (\(a\)\\b\r)
+                  /Subject /A#20B#23
+                  /Trapped /
+                  /Marked true
+                >>"#]],
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let (mut objects, trailer) = sample();
+        let xref = XRefForm::Stream { object_streams: false };
+        let options = WriteOptions {
+            edition: Edition::Pdf14,
+            xref,
+            ..WriteOptions::default()
+        };
+        assert_eq!(write(&objects, &trailer, &options), Err(WriteError::UnsupportedEdition(Edition::Pdf14)));
+
+        let options = WriteOptions::default();
+        let nested = vec![Object::Stream(Stream::default())];
+        assert_eq!(
+            write(&objects, &Dictionary::new().with(b"/A", nested), &options),
+            Err(WriteError::NestedStream(None))
+        );
+        objects.insert(id(7), vec![Object::Real(f64::NAN)].into());
+        assert_eq!(write(&objects, &trailer, &options), Err(WriteError::NonFiniteReal(Some(id(7)))));
+        objects.insert(id(7), Object::Null);
+        objects.insert(ObjectId::new(7, 1), Object::Null);
+        assert_eq!(write(&objects, &trailer, &options), Err(WriteError::DuplicateObjectNumber(7)));
+        objects.insert(id(0), Object::Null);
+        assert_eq!(write(&objects, &trailer, &options), Err(WriteError::ReservedObjectNumber));
+    }
+}
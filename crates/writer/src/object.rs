@@ -0,0 +1,175 @@
+//! Owned PDF values, to be written into a file.
+
+use document::ObjectId;
+use syntax::ast;
+
+/// A PDF value. Unlike [`document::PdfObject`], it owns its data and isn't
+/// backed by a syntax tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Object {
+    Null,
+    Bool(bool),
+    Int(i64),
+    /// A real number, which must be finite.
+    Real(f64),
+    /// A name including the leading solidus, e.g. `b"/Type"`, with `#xx`
+    /// escapes decoded.
+    Name(Vec<u8>),
+    /// The bytes of a string, written as a literal string.
+    String(Vec<u8>),
+    Array(Vec<Object>),
+    Dictionary(Dictionary),
+    /// A stream, which can only be the value of an indirect object.
+    Stream(Stream),
+    Reference(ObjectId),
+}
+
+impl Object {
+    pub fn name(name: &[u8]) -> Object {
+        Object::Name(name.to_vec())
+    }
+
+    pub fn string(bytes: &[u8]) -> Object {
+        Object::String(bytes.to_vec())
+    }
+
+    /// Copies the value of `expr` out of the syntax tree, keeping indirect
+    /// references as they are.
+    ///
+    /// Returns `None` if `expr` or any value nested in it is malformed.
+    /// Strings are copied as written, i.e. still encrypted in encrypted files.
+    pub fn from_expr(expr: &ast::Expr) -> Option<Object> {
+        let object = match expr {
+            ast::Expr::ArrayExpr(array) => Object::Array(array.exprs().map(|it| Object::from_expr(&it)).collect::<Option<_>>()?),
+            ast::Expr::DictionaryExpr(dictionary) => {
+                let mut entries = Dictionary::new();
+                for item in dictionary.dictionary_item_exprs() {
+                    entries.set(&Object::name_value(&item.key_name()?)?, Object::from_expr(&item.value()?)?);
+                }
+                Object::Dictionary(entries)
+            }
+            ast::Expr::IndirectReferenceExpr(reference) => Object::Reference(ObjectId::of_reference(reference)?),
+            ast::Expr::Literal(literal) => match literal.kind() {
+                ast::LiteralKind::IntNumber(number) => Object::Int(number.value().ok()?),
+                ast::LiteralKind::RealNumber(number) => Object::Real(number.value().ok()?),
+                ast::LiteralKind::Name(name) => Object::Name(Object::name_value(&name)?),
+                ast::LiteralKind::LiteralString(string) => Object::String(string.value().ok()?),
+                ast::LiteralKind::HexString(string) => Object::String(string.value().ok()?),
+                ast::LiteralKind::Bool(value) => Object::Bool(value),
+                ast::LiteralKind::Null => Object::Null,
+            },
+        };
+        Some(object)
+    }
+
+    pub(crate) fn name_value(name: &ast::Name) -> Option<Vec<u8>> {
+        Some([b"/".as_slice(), &name.value().ok()?].concat())
+    }
+}
+
+impl From<bool> for Object {
+    fn from(value: bool) -> Object {
+        Object::Bool(value)
+    }
+}
+
+impl From<i64> for Object {
+    fn from(value: i64) -> Object {
+        Object::Int(value)
+    }
+}
+
+impl From<f64> for Object {
+    fn from(value: f64) -> Object {
+        Object::Real(value)
+    }
+}
+
+impl From<Vec<Object>> for Object {
+    fn from(items: Vec<Object>) -> Object {
+        Object::Array(items)
+    }
+}
+
+impl From<Dictionary> for Object {
+    fn from(dictionary: Dictionary) -> Object {
+        Object::Dictionary(dictionary)
+    }
+}
+
+impl From<Stream> for Object {
+    fn from(stream: Stream) -> Object {
+        Object::Stream(stream)
+    }
+}
+
+impl From<ObjectId> for Object {
+    fn from(id: ObjectId) -> Object {
+        Object::Reference(id)
+    }
+}
+
+/// A dictionary which keeps its entries in insertion order.
+///
+/// Keys are names including the leading solidus, e.g. `b"/Type"`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dictionary {
+    entries: Vec<(Vec<u8>, Object)>,
+}
+
+impl Dictionary {
+    pub fn new() -> Dictionary {
+        Dictionary::default()
+    }
+
+    /// Adds an entry, e.g. `Dictionary::new().with(b"/Type", Object::name(b"/Page"))`.
+    pub fn with(mut self, key: &[u8], value: impl Into<Object>) -> Dictionary {
+        self.set(key, value);
+        self
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<&Object> {
+        self.entries.iter().find(|(it, _)| it == key).map(|(_, value)| value)
+    }
+
+    /// Sets the value of `key`, in place if the key already exists and at the
+    /// end otherwise.
+    pub fn set(&mut self, key: &[u8], value: impl Into<Object>) {
+        let value = value.into();
+        match self.entries.iter_mut().find(|(it, _)| it == key) {
+            Some((_, old)) => *old = value,
+            None => self.entries.push((key.to_vec(), value)),
+        }
+    }
+
+    pub fn remove(&mut self, key: &[u8]) -> Option<Object> {
+        let index = self.entries.iter().position(|(it, _)| it == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], &Object)> {
+        self.entries.iter().map(|(key, value)| (key.as_slice(), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A stream with its data already encoded by the filters its dictionary
+/// lists. `/Length` is set by the writer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stream {
+    pub dictionary: Dictionary,
+    pub data: Vec<u8>,
+}
+
+impl Stream {
+    pub fn new(dictionary: Dictionary, data: Vec<u8>) -> Stream {
+        Stream { dictionary, data }
+    }
+}
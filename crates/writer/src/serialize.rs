@@ -0,0 +1,194 @@
+//! Serialization of values, in either layout.
+
+use crate::{Dictionary, Layout, Object, Stream};
+
+/// How many spaces nested dictionaries are indented by in the pretty layout.
+const INDENT: usize = 2;
+
+pub(crate) struct Serializer {
+    pub(crate) out: Vec<u8>,
+    pub(crate) layout: Layout,
+}
+
+impl Serializer {
+    pub(crate) fn new(layout: Layout) -> Serializer {
+        Serializer { out: Vec::new(), layout }
+    }
+
+    pub(crate) fn offset(&self) -> u64 {
+        self.out.len() as u64
+    }
+
+    /// Writes raw bytes, e.g. line breaks or stream data.
+    pub(crate) fn raw(&mut self, bytes: &[u8]) {
+        self.out.extend_from_slice(bytes);
+    }
+
+    /// Writes a token, preceded by a space if it would otherwise merge with
+    /// the previous one.
+    pub(crate) fn token(&mut self, token: &[u8]) {
+        if let (Some(&last), Some(&first)) = (self.out.last(), token.first()) {
+            // An empty name is just a solidus, which doesn't delimit the
+            // regular characters following it.
+            if is_regular(first) && (is_regular(last) || last == b'/') {
+                self.out.push(b' ');
+            }
+        }
+        self.out.extend_from_slice(token);
+    }
+
+    /// Writes a space in the pretty layout. The compact layout only separates
+    /// tokens which would merge otherwise.
+    fn space(&mut self) {
+        if self.layout == Layout::Pretty {
+            self.out.push(b' ');
+        }
+    }
+
+    pub(crate) fn object_header(&mut self, number: u32, generation: u16) {
+        self.token(number.to_string().as_bytes());
+        self.token(generation.to_string().as_bytes());
+        self.token(b"obj");
+        if self.layout == Layout::Pretty {
+            self.out.push(b'\n');
+        }
+    }
+
+    pub(crate) fn object_footer(&mut self) {
+        if self.layout == Layout::Pretty {
+            self.out.push(b'\n');
+        }
+        self.token(b"endobj");
+        self.out.push(b'\n');
+    }
+
+    pub(crate) fn value(&mut self, value: &Object) {
+        self.value_at(value, 0);
+    }
+
+    fn value_at(&mut self, value: &Object, indent: usize) {
+        match value {
+            Object::Null => self.token(b"null"),
+            Object::Bool(true) => self.token(b"true"),
+            Object::Bool(false) => self.token(b"false"),
+            Object::Int(value) => self.token(value.to_string().as_bytes()),
+            Object::Real(value) => self.token(real(*value).as_bytes()),
+            Object::Name(name) => self.token(&self::name(name)),
+            Object::String(bytes) => self.token(&literal_string(bytes)),
+            Object::Array(items) => {
+                self.token(b"[");
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        self.space();
+                    }
+                    self.value_at(item, indent);
+                }
+                self.token(b"]");
+            }
+            Object::Dictionary(dictionary) => self.dictionary_at(dictionary, indent),
+            Object::Stream(stream) => self.stream(stream),
+            Object::Reference(id) => {
+                self.token(id.number.to_string().as_bytes());
+                self.token(id.generation.to_string().as_bytes());
+                self.token(b"R");
+            }
+        }
+    }
+
+    pub(crate) fn dictionary(&mut self, dictionary: &Dictionary) {
+        self.dictionary_at(dictionary, 0);
+    }
+
+    fn dictionary_at(&mut self, dictionary: &Dictionary, indent: usize) {
+        self.token(b"<<");
+        match self.layout {
+            Layout::Compact => {
+                for (key, value) in dictionary.iter() {
+                    self.token(&name(key));
+                    self.value_at(value, indent);
+                }
+            }
+            Layout::Pretty if dictionary.is_empty() => self.out.push(b' '),
+            Layout::Pretty => {
+                for (key, value) in dictionary.iter() {
+                    self.newline(indent + INDENT);
+                    self.token(&name(key));
+                    self.out.push(b' ');
+                    self.value_at(value, indent + INDENT);
+                }
+                self.newline(indent);
+            }
+        }
+        self.token(b">>");
+    }
+
+    /// Writes the stream with its `/Length` set to the length of its data.
+    fn stream(&mut self, stream: &Stream) {
+        let mut dictionary = stream.dictionary.clone();
+        dictionary.set(b"/Length", stream.data.len() as i64);
+        self.dictionary(&dictionary);
+        if self.layout == Layout::Pretty {
+            self.out.push(b'\n');
+        }
+        self.token(b"stream");
+        self.out.push(b'\n');
+        self.out.extend_from_slice(&stream.data);
+        // The end-of-line marker before `endstream` isn't part of the data.
+        self.out.push(b'\n');
+        self.token(b"endstream");
+    }
+
+    fn newline(&mut self, indent: usize) {
+        self.out.push(b'\n');
+        self.out.extend(std::iter::repeat_n(b' ', indent));
+    }
+}
+
+/// Whether `byte` is neither whitespace nor a delimiter.
+/// See ISO `32000-2:2020`, Section 7.2.3 Character set.
+fn is_regular(byte: u8) -> bool {
+    !matches!(
+        byte,
+        b'\0' | b'\t' | b'\n' | b'\x0c' | b'\r' | b' ' | b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+    )
+}
+
+/// Reals are written without an exponent, which PDF doesn't support, and with
+/// a decimal point, so they are read back as reals.
+fn real(value: f64) -> String {
+    let mut text = value.to_string();
+    if !text.contains('.') {
+        text.push_str(".0");
+    }
+    text
+}
+
+/// Writes `name` with a leading solidus, escaping the bytes which can't
+/// appear in a name as `#xx`.
+fn name(name: &[u8]) -> Vec<u8> {
+    let name = name.strip_prefix(b"/").unwrap_or(name);
+    let mut out = vec![b'/'];
+    for &byte in name {
+        if byte == b'#' || !(b'!'..=b'~').contains(&byte) || !is_regular(byte) {
+            out.extend_from_slice(format!("#{byte:02X}").as_bytes());
+        } else {
+            out.push(byte);
+        }
+    }
+    out
+}
+
+/// Writes `bytes` as a literal string. Carriage returns are escaped, as
+/// readers would turn them into line feeds.
+fn literal_string(bytes: &[u8]) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for &byte in bytes {
+        match byte {
+            b'(' | b')' | b'\\' => out.extend_from_slice(&[b'\\', byte]),
+            b'\r' => out.extend_from_slice(b"\\r"),
+            _ => out.push(byte),
+        }
+    }
+    out.push(b')');
+    out
+}
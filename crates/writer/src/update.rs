@@ -0,0 +1,358 @@
+//! Incremental updates, which append the changed objects to a file instead
+//! of rewriting it.
+//!
+//! The bytes of the original file are kept as they are, which matters for
+//! signed files: a signature covers a byte range of the file, and is broken by
+//! any change to it. The update adds the new and changed objects, a
+//! cross-reference section listing only them and the deleted objects, and a
+//! trailer whose `/Prev` points to the previous cross-reference section.
+//!
+//! See ISO `32000-2:2020`, Section 7.5.6 Incremental updates.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use document::{Document, ObjectId};
+use syntax::{XRefEntryKind, ast};
+
+use crate::{Dictionary, Object, WriteError, WriteOptions, serialize::Serializer};
+
+/// Entries of the previous trailer which describe its cross-reference section
+/// rather than the document, and aren't carried over.
+const XREF_KEYS: &[&[u8]] = &[
+    b"/Prev",
+    b"/Size",
+    b"/XRefStm",
+    b"/Type",
+    b"/W",
+    b"/Index",
+    b"/Length",
+    b"/Filter",
+    b"/DecodeParms",
+    b"/F",
+    b"/FFilter",
+    b"/FDecodeParms",
+    b"/DL",
+];
+
+/// The changes made by an incremental update.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Update {
+    /// New objects, and new definitions of existing ones.
+    pub objects: BTreeMap<ObjectId, Object>,
+    /// Deleted objects, with the ids they had.
+    pub deleted: BTreeSet<ObjectId>,
+    /// Entries added to the previous trailer, or replacing its entries.
+    pub trailer: Dictionary,
+}
+
+/// Appends `update` to the file `document` was parsed from.
+///
+/// The new trailer has the entries of the previous one (e.g. `/Root` and
+/// `/ID`) unless `update` replaces them. Deleted objects are marked as free
+/// with their generation number incremented.
+///
+/// The header isn't written again, so `options.edition` is only used when the
+/// document doesn't declare its version. The writer doesn't encrypt: objects
+/// of encrypted documents must be given already encrypted.
+pub fn write_update(document: &Document, update: &Update, options: &WriteOptions) -> Result<Vec<u8>, WriteError> {
+    let _p = tracing::info_span!("writer::write_update").entered();
+    let file = document.syntax().tree();
+    let prev = file
+        .revisions()
+        .last()
+        .and_then(|it| it.trailer()?.literal())
+        .and_then(|it| ast::Expr::Literal(it).as_int())
+        .ok_or(WriteError::MissingStartXRef)?;
+
+    let previous = document.trailer();
+    let mut trailer = Dictionary::new();
+    for item in previous.iter().flat_map(|it| it.dictionary_item_exprs()) {
+        let Some(key) = item.key_name().and_then(|it| Object::name_value(&it)) else {
+            continue;
+        };
+        if XREF_KEYS.contains(&key.as_slice()) {
+            continue;
+        }
+        match item.value().and_then(|it| Object::from_expr(&it)) {
+            Some(value) => trailer.set(&key, value),
+            None => tracing::warn!("dropping malformed trailer entry {}", String::from_utf8_lossy(&key)),
+        }
+    }
+    for (key, value) in update.trailer.iter() {
+        trailer.set(key, value.clone());
+    }
+    trailer.set(b"/Prev", prev);
+
+    let edition = document.edition().unwrap_or(options.edition);
+    crate::check(&update.objects, &trailer, edition, options.xref)?;
+    for id in &update.deleted {
+        if id.number == 0 {
+            return Err(WriteError::ReservedObjectNumber);
+        }
+        if update.objects.keys().any(|it| it.number == id.number) {
+            return Err(WriteError::DuplicateObjectNumber(id.number));
+        }
+    }
+    if let Some(number) = update.deleted.iter().zip(update.deleted.iter().skip(1)).find(|(a, b)| a.number == b.number) {
+        return Err(WriteError::DuplicateObjectNumber(number.0.number));
+    }
+
+    let mut s = Serializer::new(options.layout);
+    document.syntax().syntax_node().text().for_each_chunk(|it| s.raw(it));
+    if !matches!(s.out.last(), None | Some(b'\n' | b'\r')) {
+        s.raw(b"\n");
+    }
+
+    // New numbers, e.g. of object streams, come after every number in use.
+    let previous_size = previous.and_then(|it| it.get(b"/Size")?.as_int()).and_then(|it| u32::try_from(it).ok());
+    let size = [previous_size, document.objects().map(|(id, _)| id.number + 1).max()]
+        .into_iter()
+        .chain(update.objects.keys().chain(&update.deleted).map(|it| Some(it.number + 1)))
+        .flatten()
+        .max()
+        .unwrap_or(1);
+    let (mut entries, size) = crate::write_body(&mut s, &update.objects, &trailer, size, options);
+
+    let deleted: Vec<_> = update.deleted.iter().collect();
+    for (i, id) in deleted.iter().enumerate() {
+        // Deleted objects are linked into a list of their own, as the previous
+        // list is only known by reading every earlier section.
+        let next_free_object = deleted.get(i + 1).map_or(0, |it| it.number.into());
+        // A generation number of 65535 is never reused.
+        let generation = id.generation.saturating_add(1).into();
+        entries.insert(id.number, XRefEntryKind::Free { next_free_object, generation });
+    }
+    crate::write_xref_section(&mut s, entries, size, trailer, options.xref, false);
+    Ok(s.out)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{Expect, expect};
+    use syntax::{AstNode, Edition};
+
+    use crate::{Layout, XRefForm, write};
+
+    use super::*;
+
+    fn id(number: u32) -> ObjectId {
+        ObjectId::new(number, 0)
+    }
+
+    /// A file with a catalog, an empty page tree and two strings.
+    fn original(xref: XRefForm) -> Vec<u8> {
+        let objects = BTreeMap::from([
+            (id(1), Dictionary::new().with(b"/Type", Object::name(b"/Catalog")).with(b"/Pages", id(2)).into()),
+            (
+                id(2),
+                Dictionary::new()
+                    .with(b"/Type", Object::name(b"/Pages"))
+                    .with(b"/Kids", Vec::new())
+                    .with(b"/Count", 0)
+                    .into(),
+            ),
+            (id(3), Object::string(b"first")),
+            (id(4), Object::string(b"second")),
+        ]);
+        let trailer = Dictionary::new().with(b"/Root", id(1)).with(b"/Info", id(3));
+        let options = WriteOptions {
+            edition: Edition::Pdf17,
+            layout: Layout::Compact,
+            xref,
+        };
+        write(&objects, &trailer, &options).unwrap()
+    }
+
+    /// Changes object 3, deletes object 4 and adds object 10 as the new
+    /// `/Info`.
+    fn update() -> Update {
+        Update {
+            objects: BTreeMap::from([
+                (id(3), Object::string(b"changed")),
+                (id(10), Dictionary::new().with(b"/Title", Object::string(b"new")).into()),
+            ]),
+            deleted: BTreeSet::from([id(4)]),
+            trailer: Dictionary::new().with(b"/Info", id(10)),
+        }
+    }
+
+    /// Writes the update, checks that the updated file keeps the original
+    /// bytes and is valid, and returns the appended bytes.
+    fn write_valid(original: &[u8], update: &Update, options: &WriteOptions) -> Vec<u8> {
+        let document = Document::parse(original, Edition::Pdf17);
+        let text = write_update(&document, update, options).unwrap();
+        assert_eq!(&text[..original.len()], original);
+        let updated = Document::parse(&text, Edition::Pdf17);
+        assert_eq!(updated.syntax().errors(), Vec::new(), "{}", String::from_utf8_lossy(&text));
+
+        let revisions = updated.revisions();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[1].prev(), revisions[0].xref_offset());
+        assert_eq!(revisions[1].changed().collect::<Vec<_>>(), vec![id(3)]);
+        assert_eq!(revisions[1].freed().collect::<Vec<_>>(), vec![id(4)]);
+        assert!(revisions[1].added().any(|it| it == id(10)));
+        assert_eq!(
+            updated.trailer().and_then(|it| it.get(b"/Info")).map(|it| it.syntax().text().to_string()),
+            Some("10 0 R".to_owned())
+        );
+        text[original.len()..].to_vec()
+    }
+
+    fn check(xref: XRefForm, expect: Expect) {
+        let options = WriteOptions {
+            xref,
+            ..WriteOptions::default()
+        };
+        let appended = write_valid(&original(xref), &update(), &options);
+        // The data of cross-reference streams is binary.
+        let text: String = appended
+            .iter()
+            .map(|&it| match it {
+                b'\n' | b' '..=b'~' => char::from(it).to_string(),
+                _ => format!("\\x{it:02x}"),
+            })
+            .collect();
+        expect.assert_eq(&text);
+    }
+
+    #[test]
+    fn with_table() {
+        check(
+            XRefForm::Table,
+            expect![[r#"
+            3 0 obj
+            (changed)
+            endobj
+            10 0 obj
+            <<
+              /Title (new)
+            >>
+            endobj
+            xref
+            3 2
+            0000000315 00000 n 
+            0000000000 00001 f 
+            10 1
+            0000000340 00000 n 
+            trailer
+            <<
+              /Root 1 0 R
+              /Info 10 0 R
+              /Prev 145
+              /Size 11
+            >>
+            startxref
+            377
+            %%EOF
+        "#]],
+        );
+    }
+
+    #[test]
+    fn with_stream() {
+        check(
+            XRefForm::Stream { object_streams: false },
+            expect![[r#"
+                3 0 obj
+                (changed)
+                endobj
+                10 0 obj
+                <<
+                  /Title (new)
+                >>
+                endobj
+                11 0 obj
+                <<
+                  /Type /XRef
+                  /Size 12
+                  /Index [3 2 10 2]
+                  /W [1 2 1]
+                  /Root 1 0 R
+                  /Info 10 0 R
+                  /Prev 145
+                  /Length 16
+                >>
+                stream
+                \x01\x01\x1b\x00\x00\x00\x00\x01\x01\x014\x00\x01\x01Y\x00
+                endstream
+                endobj
+                startxref
+                345
+                %%EOF
+            "#]],
+        );
+    }
+
+    #[test]
+    fn with_object_streams() {
+        check(
+            XRefForm::Stream { object_streams: true },
+            expect![[r#"
+                11 0 obj
+                <<
+                  /Type /ObjStm
+                  /N 2
+                  /First 10
+                  /Length 40
+                >>
+                stream
+                3 0 10 10
+                (changed)
+                <<
+                  /Title (new)
+                >>
+                endstream
+                endobj
+                12 0 obj
+                <<
+                  /Type /XRef
+                  /Size 13
+                  /Index [3 2 10 3]
+                  /W [1 2 1]
+                  /Root 1 0 R
+                  /Info 10 0 R
+                  /Prev 182
+                  /Length 20
+                >>
+                stream
+                \x02\x00\x0b\x00\x00\x00\x00\x01\x02\x00\x0b\x01\x01\x01D\x00\x01\x01\xc4\x00
+                endstream
+                endobj
+                startxref
+                452
+                %%EOF
+            "#]],
+        );
+    }
+
+    #[test]
+    fn appends_a_line_break() {
+        let original = original(XRefForm::Table);
+        let original = original.strip_suffix(b"\n").unwrap();
+        let appended = write_valid(original, &update(), &WriteOptions::default());
+        assert!(appended.starts_with(b"\n3 0 obj"));
+    }
+
+    #[test]
+    fn errors() {
+        let document = Document::parse(&original(XRefForm::Table), Edition::Pdf17);
+        let options = WriteOptions::default();
+        let mut update = update();
+        update.deleted.insert(id(3));
+        assert_eq!(write_update(&document, &update, &options), Err(WriteError::DuplicateObjectNumber(3)));
+        update.deleted.insert(id(0));
+        assert_eq!(write_update(&document, &update, &options), Err(WriteError::ReservedObjectNumber));
+
+        let document = Document::parse(b"%PDF-1.4\n1 0 obj null endobj\n", Edition::Pdf14);
+        assert_eq!(write_update(&document, &Update::default(), &options), Err(WriteError::MissingStartXRef));
+        let document = Document::parse(b"%PDF-1.4\n1 0 obj null endobj\ntrailer << /Size 2 >>\nstartxref\n0\n%%EOF\n", Edition::Pdf14);
+        let options = WriteOptions {
+            xref: XRefForm::Stream { object_streams: false },
+            ..options
+        };
+        assert_eq!(
+            write_update(&document, &Update::default(), &options),
+            Err(WriteError::UnsupportedEdition(Edition::Pdf14))
+        );
+    }
+}
@@ -1,26 +1,38 @@
-// The module 'vscode' contains the VS Code extensibility API
-// Import the module and reference it with the alias vscode in your code below
 import * as vscode from 'vscode';
+import { LanguageClient, LanguageClientOptions, ServerOptions } from 'vscode-languageclient/node';
 
-// This method is called when your extension is activated
-// Your extension is activated the very first time the command is executed
-export function activate(context: vscode.ExtensionContext) {
+let client: LanguageClient | undefined;
 
-    // Use the console to output diagnostic information (console.log) and errors (console.error)
-    // This line of code will only be executed once when your extension is activated
-    console.log('Congratulations, your extension "pdf-vscode" is now active!');
+// Starts the `pdf-analyzer` language server for PDF files. The server is
+// looked up in `PATH` unless `pdf-analyzer.server.path` is set.
+export async function activate(context: vscode.ExtensionContext) {
+    context.subscriptions.push(
+        vscode.commands.registerCommand('pdf-analyzer.restartServer', async () => {
+            await stopClient();
+            await startClient();
+        }),
+    );
+    await startClient();
+}
 
-    // The command has been defined in the package.json file
-    // Now provide the implementation of the command with registerCommand
-    // The commandId parameter must match the command field in package.json
-    const disposable = vscode.commands.registerCommand('pdf-vscode.helloWorld', () => {
-        // The code you place here will be executed every time your command is executed
-        // Display a message box to the user
-        vscode.window.showInformationMessage('Hello World from PDF!');
-    });
+export async function deactivate() {
+    await stopClient();
+}
 
-    context.subscriptions.push(disposable);
+async function startClient() {
+    const command = vscode.workspace.getConfiguration('pdf-analyzer').get<string | null>('server.path') ?? 'pdf-analyzer';
+    const serverOptions: ServerOptions = {
+        run: { command },
+        debug: { command, options: { env: { ...process.env, RUST_BACKTRACE: '1' } } },
+    };
+    const clientOptions: LanguageClientOptions = {
+        documentSelector: [{ language: 'pdf' }],
+    };
+    client = new LanguageClient('pdf-analyzer', 'pdf-analyzer', serverOptions, clientOptions);
+    await client.start();
 }
 
-// This method is called when your extension is deactivated
-export function deactivate() { }
+async function stopClient() {
+    await client?.stop();
+    client = undefined;
+}
@@ -382,6 +382,10 @@ fn generate_syntax_kinds(grammar: KindsSrc) -> String {
 
     let fmt_kw_as_variant = |&name| match name {
         "Self" => format_ident!("SELF_TYPE_KW"),
+        // `q` and `Q` only differ in case.
+        "q" => format_ident!("SAVE_KW"),
+        "Q" => format_ident!("RESTORE_KW"),
+        name if name.chars().all(|c| c.is_ascii_uppercase()) => format_ident!("{name}_KW"),
         name => format_ident!("{}_KW", to_upper_snake_case(name)),
     };
 
@@ -453,7 +457,7 @@ fn generate_syntax_kinds(grammar: KindsSrc) -> String {
     let nodes = grammar.nodes.iter().map(|name| format_ident!("{}", name)).collect::<Vec<_>>();
 
     let ast = quote! {
-        #![allow(bad_style, missing_docs, unreachable_pub)]
+        #![allow(bad_style, missing_docs, unreachable_pub, clippy::match_like_matches_macro)]
         use crate::Edition;
 
         /// The kind of syntax node, e.g. `IDENT`, `USE_KW`, or `STRUCT`.
@@ -571,6 +575,12 @@ fn generate_syntax_kinds(grammar: KindsSrc) -> String {
             #([#contextual_keywords_tokens] => { $crate::SyntaxKind::#contextual_keywords_variants };)*
             #([#edition_dependent_keywords_tokens] => { $crate::SyntaxKind::#edition_dependent_keywords_variants };)*
             [stream_data] => { $crate::SyntaxKind::STREAM_DATA };
+            [operator] => { $crate::SyntaxKind::OPERATOR };
+            [inline_image_data] => { $crate::SyntaxKind::INLINE_IMAGE_DATA };
+            [ident] => { $crate::SyntaxKind::IDENT };
+            [header_comment] => { $crate::SyntaxKind::HEADER_COMMENT };
+            [binary_comment] => { $crate::SyntaxKind::BINARY_COMMENT };
+            [eof_comment] => { $crate::SyntaxKind::EOF_COMMENT };
         }
     };
 
@@ -653,7 +663,9 @@ impl Field {
                     "']'" => "r_brack",
                     "<<" => "l_dict",
                     ">>" => "r_dict",
-                    _ => name,
+                    "q" => "save",
+                    "Q" => "restore",
+                    _ => &name.to_ascii_lowercase(),
                 };
 
                 format!("{name}_token",)
@@ -796,7 +808,10 @@ fn lower_rule(acc: &mut Vec<Field>, grammar: &Grammar, label: Option<&String>, r
         }
         Rule::Labeled { label: l, rule } => {
             assert!(label.is_none());
-            let manually_implemented = matches!(l.as_str(), "value");
+            let manually_implemented = matches!(
+                l.as_str(),
+                "value" | "object_number" | "generation_number" | "offset" | "first_object" | "count"
+            );
             if manually_implemented {
                 return;
             }
@@ -66,14 +66,15 @@ impl ToTokens for Edition {
 /// The punctuations of the language.
 const PUNCT: &[(&str, &str)] = &[("[", "L_BRACK"), ("]", "R_BRACK"), ("<<", "L_DICT"), (">>", "R_DICT")];
 
-const TOKENS: &[&str] = &["ERROR", "WHITESPACE", "NEWLINE", "COMMENT", "RAW_STREAM"];
+const TOKENS: &[&str] = &["ERROR", "IDENT", "WHITESPACE", "NEWLINE", "COMMENT", "RAW_STREAM"];
 
 const EOF: &str = "EOF";
 
 const RESERVED: &[&str] = &[];
 // keywords that are keywords only in specific parse contexts
 #[doc(alias = "WEAK_KEYWORDS")]
-const CONTEXTUAL_KEYWORDS: &[&str] = &[];
+// The content stream operators which delimit blocks.
+const CONTEXTUAL_KEYWORDS: &[&str] = &["BT", "ET", "q", "Q", "BMC", "BDC", "EMC", "BI", "ID", "EI"];
 
 // keywords we use for special macro expansions
 const CONTEXTUAL_BUILTIN_KEYWORDS: &[&str] = &[];